@@ -0,0 +1,106 @@
+//! Measures parsing Exif from a corpus of JPEG files, including iterating
+//! all the entries.
+//!
+//! ```sh
+//! cargo bench --bench parse
+//! # With the per-entry tracing spans
+//! cargo bench --bench parse --features trace_spans
+//! # With an `INFO` level subscriber, which enables the spans if any
+//! NOM_EXIF_BENCH_SUBSCRIBER=1 cargo bench --bench parse --features trace_spans
+//! # Another corpus
+//! NOM_EXIF_BENCH_DIR=/path/to/jpegs cargo bench --bench parse
+//! ```
+//!
+//! The corpus is loaded into memory first, so that the I/O is excluded.
+
+use std::{
+    fs,
+    hint::black_box,
+    io::Cursor,
+    path::PathBuf,
+    time::{Duration, Instant},
+};
+
+use nom_exif::{Exif, ExifIter, MediaParser, MediaSource};
+
+const ROUNDS: usize = 15;
+const MIN_ROUND_TIME: Duration = Duration::from_millis(500);
+
+fn load_corpus() -> Vec<(PathBuf, Vec<u8>)> {
+    let dir = std::env::var_os("NOM_EXIF_BENCH_DIR").unwrap_or_else(|| "testdata".into());
+    let mut files: Vec<_> = fs::read_dir(&dir)
+        .expect("read corpus dir")
+        .filter_map(|x| x.ok().map(|x| x.path()))
+        .filter(|x| {
+            x.extension()
+                .and_then(|x| x.to_str())
+                .is_some_and(|x| x.eq_ignore_ascii_case("jpg") || x.eq_ignore_ascii_case("jpeg"))
+        })
+        .collect();
+    files.sort();
+    files
+        .into_iter()
+        .map(|x| {
+            let data = fs::read(&x).expect("read corpus file");
+            (x, data)
+        })
+        .collect()
+}
+
+/// Parses all the files once, returns the number of entries.
+fn parse_corpus(parser: &mut MediaParser, corpus: &[(PathBuf, Vec<u8>)]) -> usize {
+    let mut entries = 0;
+    for (_, data) in corpus {
+        let Ok(ms) = MediaSource::seekable(Cursor::new(data.as_slice())) else {
+            continue;
+        };
+        let Ok(iter) = parser.parse::<_, _, ExifIter>(ms) else {
+            continue;
+        };
+        entries += iter.clone_and_rewind().count();
+        let exif: Exif = iter.into();
+        black_box(exif);
+    }
+    entries
+}
+
+fn main() {
+    let subscriber = std::env::var_os("NOM_EXIF_BENCH_SUBSCRIBER").is_some();
+    if subscriber {
+        tracing_subscriber::fmt()
+            .with_max_level(tracing::Level::INFO)
+            .with_writer(std::io::sink)
+            .init();
+    }
+
+    let corpus = load_corpus();
+    assert!(!corpus.is_empty(), "no JPEG files found in the corpus");
+
+    let mut parser = MediaParser::new();
+    // Warm up
+    let entries = parse_corpus(&mut parser, &corpus);
+
+    println!(
+        "corpus: {} files, {entries} entries, trace_spans: {}, subscriber: {subscriber}",
+        corpus.len(),
+        cfg!(feature = "trace_spans"),
+    );
+    let mut results = Vec::with_capacity(ROUNDS);
+    for round in 0..ROUNDS {
+        let start = Instant::now();
+        let mut iterations = 0;
+        while start.elapsed() < MIN_ROUND_TIME {
+            black_box(parse_corpus(&mut parser, black_box(&corpus)));
+            iterations += 1;
+        }
+        let per_file = start.elapsed() / (iterations * corpus.len()) as u32;
+        results.push(per_file);
+        println!("round {round}: {per_file:?}/file");
+    }
+    results.sort();
+    println!(
+        "best: {:?}/file, median: {:?}/file",
+        results[0],
+        results[ROUNDS / 2]
+    );
+}
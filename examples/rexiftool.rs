@@ -112,6 +112,7 @@ fn parse_file<P: AsRef<Path>>(
                         x.tag()
                             .map(|x| x.to_string())
                             .unwrap_or_else(|| format!("Unknown(0x{:04x})", x.tag_code())),
+                        x.tag().and_then(|t| t.describe_value(&v)),
                         v,
                     )),
                     Err(e) => {
@@ -124,7 +125,7 @@ fn parse_file<P: AsRef<Path>>(
     } else {
         let info: TrackInfo = parser.parse(ms)?;
         info.into_iter()
-            .map(|x| (x.0.to_string(), x.1))
+            .map(|x| (x.0.to_string(), None, x.1))
             .collect::<Vec<_>>()
     };
     if json {
@@ -135,7 +136,7 @@ fn parse_file<P: AsRef<Path>>(
         match serde_json::to_string_pretty(
             &values
                 .into_iter()
-                .map(|x| (x.0.to_string(), x.1))
+                .map(|x| (x.0, x.2))
                 .collect::<HashMap<_, _>>(),
         ) {
             Ok(s) => {
@@ -144,8 +145,10 @@ fn parse_file<P: AsRef<Path>>(
             Err(e) => eprintln!("Error: {e}"),
         }
     } else {
-        values.iter().for_each(|x| {
-            println!("{:<32}=> {}", x.0, x.1);
+        values.iter().for_each(|x| match &x.1 {
+            // Show the raw value along with its description
+            Some(desc) => println!("{:<32}=> {} ({desc})", x.0, x.2),
+            None => println!("{:<32}=> {}", x.0, x.2),
         });
     };
     Ok(())
@@ -11,6 +11,7 @@ mod idat;
 mod iinf;
 mod iloc;
 mod ilst;
+mod iprp;
 mod keys;
 mod meta;
 mod mvhd;
@@ -19,7 +20,9 @@ pub use ilst::IlstBox;
 pub use keys::KeysBox;
 pub use meta::MetaBox;
 pub use mvhd::MvhdBox;
+pub(crate) use tkhd::find_video_track;
 pub use tkhd::parse_video_tkhd_in_moov;
+pub(crate) use tkhd::TkhdBox;
 
 const MAX_BODY_LEN: usize = 2000 * 1024 * 1024;
 
@@ -151,7 +154,7 @@ impl Debug for BoxHolder<'_> {
 }
 
 impl<'a> BoxHolder<'a> {
-    #[tracing::instrument(skip_all)]
+    #[cfg_attr(feature = "trace_spans", tracing::instrument(skip_all))]
     pub fn parse(input: &'a [u8]) -> IResult<&'a [u8], BoxHolder<'a>> {
         let (_, header) = BoxHeader::parse(input)?;
         tracing::debug!(box_type = header.box_type, ?header, "Got");
@@ -290,7 +293,7 @@ pub trait ParseBox<O> {
 
 /// auto implements parse_box for each Box which implements ParseBody
 impl<O, T: ParseBody<O>> ParseBox<O> for T {
-    #[tracing::instrument(skip_all)]
+    #[cfg_attr(feature = "trace_spans", tracing::instrument(skip_all))]
     fn parse_box(input: &[u8]) -> IResult<&[u8], O> {
         let (remain, header) = FullBoxHeader::parse(input)?;
         assert_eq!(input.len(), header.header_size + remain.len());
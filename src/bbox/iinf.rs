@@ -1,5 +1,3 @@
-use std::collections::HashMap;
-
 use nom::{
     bytes::streaming,
     combinator::{cond, fail, map_res},
@@ -16,7 +14,7 @@ use super::{ParseBody, ParseBox};
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct IinfBox {
     pub(crate) header: FullBoxHeader,
-    pub(crate) entries: HashMap<String, InfeBox>,
+    pub(crate) entries: Vec<InfeBox>,
 }
 
 impl ParseBody<IinfBox> for IinfBox {
@@ -29,21 +27,26 @@ impl ParseBody<IinfBox> for IinfBox {
             map_res(be_u16, |x| Ok::<u32, ()>(x as u32))(remain)?
         };
 
+        // Entries are kept in a `Vec`, since several items may share the same
+        // type, e.g. `mime` items with different content types
         let (remain, entries) =
             many_m_n(item_count as usize, item_count as usize, InfeBox::parse_box)(remain)?;
 
-        let entries = entries
-            .into_iter()
-            .map(|e| (e.key().to_owned(), e))
-            .collect::<HashMap<_, _>>();
-
         Ok((remain, IinfBox { header, entries }))
     }
 }
 
 impl IinfBox {
     pub fn get_infe(&self, item_type: &'static str) -> Option<&InfeBox> {
-        self.entries.get(item_type)
+        self.entries.iter().find(|e| e.key() == item_type)
+    }
+
+    /// Returns the first `mime` item whose content type is `content_type`,
+    /// e.g. "application/rdf+xml" for XMP.
+    pub fn get_infe_by_content_type(&self, content_type: &str) -> Option<&InfeBox> {
+        self.entries
+            .iter()
+            .find(|e| e.content_type.as_deref() == Some(content_type))
     }
 }
 
@@ -123,7 +126,7 @@ impl ParseBody<InfeBox> for InfeBox {
 }
 
 impl InfeBox {
-    fn key(&self) -> &String {
+    fn key(&self) -> &str {
         self.item_type.as_ref().unwrap_or(&self.item_name)
     }
 }
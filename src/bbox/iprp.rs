@@ -0,0 +1,202 @@
+use std::collections::HashMap;
+
+use nom::{
+    combinator::{cond, fail},
+    multi::{many0, many_m_n},
+    number::complete::{be_u16, be_u32, u8},
+    IResult,
+};
+
+use super::{BoxHolder, FullBoxHeader, ParseBody, ParseBox};
+
+/// Transformative item properties, which should be applied to an image item
+/// before displaying it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ItemTransform {
+    /// `irot`: rotation in anti-clockwise quarter turns
+    Rotation(u8),
+    /// `imir`: 0 mirrors about a vertical axis (i.e. a horizontal flip), 1
+    /// mirrors about a horizontal axis
+    Mirror(u8),
+}
+
+/// Item properties which are kept after parsing.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum ItemProperty {
+    Transform(ItemTransform),
+    /// `colr` with an ICC profile, i.e. the `prof` or `rICC` colour type
+    IccProfile(Vec<u8>),
+}
+
+/// Representing the `iprp` box in a HEIF/HEIC file. Only the transformative
+/// properties & ICC profiles are kept.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IprpBox {
+    /// Properties in `ipco`, `None` for the ones not kept
+    properties: Vec<Option<ItemProperty>>,
+    /// Item ID => 1-based property indexes in `ipco`
+    associations: HashMap<u32, Vec<u16>>,
+}
+
+impl IprpBox {
+    pub fn parse(input: &[u8]) -> IResult<&[u8], IprpBox> {
+        let (remain, bbox) = BoxHolder::parse(input)?;
+        let mut properties = Vec::new();
+        let mut associations = HashMap::new();
+
+        for child in children(bbox.body_data())?.1 {
+            match child.box_type() {
+                "ipco" => {
+                    properties = children(child.body_data())?
+                        .1
+                        .iter()
+                        .map(parse_property)
+                        .collect();
+                }
+                "ipma" => {
+                    let (_, ipma) = IpmaBox::parse_box(child.data)?;
+                    associations.extend(ipma.entries);
+                }
+                _ => (),
+            }
+        }
+
+        Ok((
+            remain,
+            IprpBox {
+                properties,
+                associations,
+            },
+        ))
+    }
+
+    /// Returns the transforms associated with the item `id`, in the order
+    /// they should be applied.
+    pub(crate) fn item_transforms(&self, id: u32) -> Vec<ItemTransform> {
+        self.item_properties(id)
+            .filter_map(|p| match p {
+                ItemProperty::Transform(t) => Some(*t),
+                ItemProperty::IccProfile(_) => None,
+            })
+            .collect()
+    }
+
+    /// Returns the ICC profile associated with the item `id`.
+    pub(crate) fn item_icc_profile(&self, id: u32) -> Option<&[u8]> {
+        self.item_properties(id).find_map(|p| match p {
+            ItemProperty::IccProfile(profile) => Some(profile.as_slice()),
+            ItemProperty::Transform(_) => None,
+        })
+    }
+
+    fn item_properties(&self, id: u32) -> impl Iterator<Item = &ItemProperty> {
+        self.associations
+            .get(&id)
+            .into_iter()
+            .flatten()
+            .filter_map(|&index| {
+                let index = (index as usize).checked_sub(1)?;
+                self.properties.get(index)?.as_ref()
+            })
+    }
+}
+
+/// Converts the transforms of an item into an Exif `Orientation` value
+/// (1–8), which describes the same display transform.
+pub(crate) fn transforms_to_orientation(transforms: &[ItemTransform]) -> u16 {
+    // The transform is represented as an optional horizontal flip followed by
+    // a clockwise rotation of `turns` quarter turns.
+    let (mut flip, mut turns) = (false, 0);
+    for t in transforms {
+        match *t {
+            ItemTransform::Rotation(angle) => turns = (turns + 4 - angle % 4) % 4,
+            ItemTransform::Mirror(0) => (flip, turns) = (!flip, (4 - turns) % 4),
+            // A vertical flip is a horizontal flip followed by a 180°
+            // rotation
+            ItemTransform::Mirror(_) => (flip, turns) = (!flip, (6 - turns) % 4),
+        }
+    }
+
+    const ORIENTATIONS: [[u16; 4]; 2] = [[1, 6, 3, 8], [2, 7, 4, 5]];
+    ORIENTATIONS[flip as usize][turns as usize]
+}
+
+fn children<'a>(input: &'a [u8]) -> IResult<&'a [u8], Vec<BoxHolder<'a>>> {
+    many0(|remain: &'a [u8]| {
+        if remain.is_empty() {
+            // stop many0 parsing to prevent Incomplete error
+            fail::<_, (), _>(remain)?;
+        }
+        BoxHolder::parse(remain)
+    })(input)
+}
+
+fn parse_property(bbox: &BoxHolder) -> Option<ItemProperty> {
+    let body = bbox.body_data();
+    let value = *body.first()?;
+    match bbox.box_type() {
+        "irot" => Some(ItemProperty::Transform(ItemTransform::Rotation(
+            value & 0x03,
+        ))),
+        "imir" => Some(ItemProperty::Transform(ItemTransform::Mirror(value & 0x01))),
+        "colr" => match body.split_at_checked(4)? {
+            (b"prof" | b"rICC", profile) => Some(ItemProperty::IccProfile(profile.to_vec())),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Item property association box
+struct IpmaBox {
+    entries: Vec<(u32, Vec<u16>)>,
+}
+
+impl ParseBody<IpmaBox> for IpmaBox {
+    fn parse_body(remain: &[u8], header: FullBoxHeader) -> IResult<&[u8], IpmaBox> {
+        let large_index = header.flags & 1 == 1;
+
+        let (remain, entry_count) = be_u32(remain)?;
+        let (remain, entries) = many_m_n(entry_count as usize, entry_count as usize, |input| {
+            let (remain, id) = if header.version < 1 {
+                let (remain, id) = be_u16(input)?;
+                (remain, id as u32)
+            } else {
+                be_u32(input)?
+            };
+            let (remain, count) = u8(remain)?;
+            let (remain, indexes) = many_m_n(count as usize, count as usize, |input| {
+                // The highest bit is the `essential` flag
+                let (remain, index) = cond(large_index, be_u16)(input)?;
+                match index {
+                    Some(index) => Ok((remain, index & 0x7fff)),
+                    None => u8(remain).map(|(remain, index)| (remain, (index & 0x7f) as u16)),
+                }
+            })(remain)?;
+            Ok((remain, (id, indexes)))
+        })(remain)?;
+
+        Ok((remain, IpmaBox { entries }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+    use ItemTransform::*;
+
+    #[test_case(&[], 1)]
+    #[test_case(&[Rotation(1)], 8)]
+    #[test_case(&[Rotation(2)], 3)]
+    #[test_case(&[Rotation(3)], 6)]
+    #[test_case(&[Mirror(0)], 2)]
+    #[test_case(&[Mirror(1)], 4)]
+    #[test_case(&[Rotation(1), Mirror(0)], 7)]
+    #[test_case(&[Rotation(3), Mirror(0)], 5)]
+    #[test_case(&[Mirror(0), Rotation(1)], 5)]
+    #[test_case(&[Rotation(2), Mirror(1)], 2)]
+    fn orientation(transforms: &[ItemTransform], orientation: u16) {
+        assert_eq!(transforms_to_orientation(transforms), orientation);
+    }
+}
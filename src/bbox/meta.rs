@@ -1,10 +1,20 @@
 use std::{collections::HashMap, fmt::Debug, ops::Range};
 
-use nom::{combinator::fail, multi::many0, IResult, Needed};
+use nom::{
+    combinator::{fail, map},
+    multi::many0,
+    number::complete::{be_u16, be_u32},
+    IResult, Needed,
+};
 
 use crate::bbox::FullBoxHeader;
 
-use super::{iinf::IinfBox, iloc::IlocBox, BoxHolder, ParseBody, ParseBox};
+use super::{
+    iinf::IinfBox,
+    iloc::IlocBox,
+    iprp::{transforms_to_orientation, IprpBox},
+    BoxHolder, ParseBody, ParseBox,
+};
 
 /// Representing the `meta` box in a HEIF/HEIC file.
 #[derive(Clone, PartialEq, Eq)]
@@ -12,6 +22,9 @@ pub struct MetaBox {
     header: FullBoxHeader,
     iinf: Option<IinfBox>,
     iloc: Option<IlocBox>,
+    /// ID of the primary item, from the `pitm` box
+    primary_item: Option<u32>,
+    iprp: Option<IprpBox>,
     // idat: Option<IdatBox<'a>>,
 }
 
@@ -24,6 +37,7 @@ impl Debug for MetaBox {
                 &self.iinf.as_ref().map(|x| x.entries.len()),
             )
             .field("iloc items num", &self.iloc.as_ref().map(|x| x.items.len()))
+            .field("primary item", &self.primary_item)
             .finish()
     }
 }
@@ -58,6 +72,26 @@ impl ParseBody<MetaBox> for MetaBox {
             .transpose()?
             .map(|x| x.1);
 
+        // parse pitm & iprp boxes, they are only used for the orientation of
+        // the primary item, so a broken one shouldn't fail the Exif data
+        let primary_item = boxes
+            .get("pitm")
+            .map(|pitm| PitmBox::parse_box(pitm.data))
+            .and_then(|r| {
+                r.map_err(|e| tracing::warn!(?e, "Ignore invalid pitm box."))
+                    .ok()
+            })
+            .map(|x| x.1 .0);
+
+        let iprp = boxes
+            .get("iprp")
+            .map(|iprp| IprpBox::parse(iprp.data))
+            .and_then(|r| {
+                r.map_err(|e| tracing::warn!(?e, "Ignore invalid iprp box."))
+                    .ok()
+            })
+            .map(|x| x.1);
+
         // parse idat box
         // let idat = boxes
         //     .get("idat")
@@ -71,6 +105,8 @@ impl ParseBody<MetaBox> for MetaBox {
                 header,
                 iinf,
                 iloc,
+                primary_item,
+                iprp,
                 // idat,
             },
         ))
@@ -78,6 +114,23 @@ impl ParseBody<MetaBox> for MetaBox {
 }
 
 impl MetaBox {
+    /// Returns the Exif `Orientation` value (1–8) equivalent to the
+    /// transformative properties (`irot`/`imir`) of the primary item, or
+    /// `None` if there is no such property.
+    pub fn primary_orientation(&self) -> Option<u16> {
+        let transforms = self.iprp.as_ref()?.item_transforms(self.primary_item?);
+        if transforms.is_empty() {
+            None
+        } else {
+            Some(transforms_to_orientation(&transforms))
+        }
+    }
+
+    /// Returns the ICC profile of the primary item, from its `colr` property.
+    pub fn primary_icc_profile(&self) -> Option<&[u8]> {
+        self.iprp.as_ref()?.item_icc_profile(self.primary_item?)
+    }
+
     #[tracing::instrument(skip_all)]
     pub fn exif_data<'a>(&self, input: &'a [u8]) -> IResult<&'a [u8], Option<&'a [u8]>> {
         self.iinf
@@ -112,14 +165,25 @@ impl MetaBox {
 
     #[tracing::instrument(skip_all)]
     pub fn exif_data_offset(&self) -> Option<Range<usize>> {
-        self.iinf
+        let infe = self.iinf.as_ref()?.get_infe("Exif")?;
+        self.item_data_offset(infe.id)
+    }
+
+    /// Returns the file range of the XMP packet, i.e. the data of the `mime`
+    /// item with the "application/rdf+xml" content type.
+    #[tracing::instrument(skip_all)]
+    pub fn xmp_data_offset(&self) -> Option<Range<usize>> {
+        let infe = self
+            .iinf
+            .as_ref()?
+            .get_infe_by_content_type("application/rdf+xml")?;
+        self.item_data_offset(infe.id)
+    }
+
+    fn item_data_offset(&self, id: u32) -> Option<Range<usize>> {
+        self.iloc
             .as_ref()
-            .and_then(|iinf| iinf.get_infe("Exif"))
-            .and_then(|exif_infe| {
-                self.iloc
-                    .as_ref()
-                    .and_then(|iloc| iloc.item_offset_len(exif_infe.id))
-            })
+            .and_then(|iloc| iloc.item_offset_len(id))
             .and_then(|(construction_method, offset, length)| {
                 let start = offset as usize;
                 let end = (offset + length) as usize;
@@ -138,6 +202,19 @@ impl MetaBox {
     }
 }
 
+/// Primary item box
+struct PitmBox(u32);
+
+impl ParseBody<PitmBox> for PitmBox {
+    fn parse_body(remain: &[u8], header: FullBoxHeader) -> IResult<&[u8], PitmBox> {
+        if header.version == 0 {
+            map(be_u16, |x| PitmBox(x as u32))(remain)
+        } else {
+            map(be_u32, PitmBox)(remain)
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 struct ItemLocationExtent {
     index: u64,
@@ -179,5 +256,22 @@ mod tests {
         assert_eq!(remain, b"");
         assert_eq!(meta.header.box_type, "meta");
         assert_eq!(meta.exif_data(&buf).unwrap().1.unwrap().len(), meta_size);
+        // Rotated by `irot`
+        assert_eq!(meta.primary_orientation(), Some(6));
+    }
+
+    #[test]
+    fn meta_invalid_pitm() {
+        let _ = tracing_subscriber::fmt().with_test_writer().try_init();
+
+        let mut buf = read_sample("exif.heic").unwrap();
+        // Claim a 32-bit item ID in the 2-byte pitm body
+        let pos = buf.windows(4).position(|x| x == b"pitm").unwrap();
+        buf[pos + 4] = 1;
+
+        let (_, bbox) = travel_while(&buf, |bbox| bbox.box_type() != "meta").unwrap();
+        let (_, meta) = MetaBox::parse_box(bbox.unwrap().data).unwrap();
+        assert_eq!(meta.primary_item, None);
+        assert_eq!(meta.exif_data(&buf).unwrap().1.unwrap().len(), 2618);
     }
 }
@@ -30,13 +30,32 @@ pub struct MvhdBox {
     ///
     /// seconds = duration / time_scale
     duration: u32,
-    // omit 76 bytes...
+
+    /// The rate at which to play this movie, a fixed-point 16.16 number. A
+    /// value of 1.0 indicates normal rate.
+    preferred_rate: u32,
+    // omit 72 bytes...
     next_track_id: u32,
 }
 
 impl MvhdBox {
-    pub fn duration_ms(&self) -> u64 {
-        ((self.duration as f64) / (self.time_scale as f64) * 1000_f64) as u64
+    /// Returns the duration in milliseconds, or `None` if the time scale is
+    /// 0, which is invalid.
+    pub fn duration_ms(&self) -> Option<u64> {
+        if self.time_scale == 0 {
+            tracing::warn!(duration = self.duration, "invalid mvhd time scale: 0");
+            return None;
+        }
+        // Can't overflow, since duration is a u32
+        Some(self.duration as u64 * 1000 / self.time_scale as u64)
+    }
+
+    pub fn time_scale(&self) -> u32 {
+        self.time_scale
+    }
+
+    pub fn preferred_rate(&self) -> f64 {
+        self.preferred_rate as f64 / 65536.0
     }
 
     fn creation_time_naive(&self) -> NaiveDateTime {
@@ -63,8 +82,26 @@ impl MvhdBox {
 
 impl ParseBody<MvhdBox> for MvhdBox {
     fn parse_body(body: &[u8], header: FullBoxHeader) -> nom::IResult<&[u8], MvhdBox> {
-        let (remain, (creation_time, modification_time, time_scale, duration, _, next_track_id)) =
-            tuple((be_u32, be_u32, be_u32, be_u32, take(76usize), be_u32))(body)?;
+        let (
+            remain,
+            (
+                creation_time,
+                modification_time,
+                time_scale,
+                duration,
+                preferred_rate,
+                _,
+                next_track_id,
+            ),
+        ) = tuple((
+            be_u32,
+            be_u32,
+            be_u32,
+            be_u32,
+            be_u32,
+            take(72usize),
+            be_u32,
+        ))(body)?;
 
         Ok((
             remain,
@@ -74,6 +111,7 @@ impl ParseBody<MvhdBox> for MvhdBox {
                 modification_time,
                 time_scale,
                 duration,
+                preferred_rate,
                 next_track_id,
             },
         ))
@@ -95,15 +133,17 @@ mod tests {
         "meta.mov",
         "2024-02-02T08:09:57.000000Z",
         "2024-02-02T16:09:57+08:00",
-        500
+        500,
+        600
     )]
     #[test_case(
         "meta.mp4",
         "2024-02-03T07:05:38.000000Z",
         "2024-02-03T15:05:38+08:00",
-        1063
+        1063,
+        10000
     )]
-    fn mvhd_box(path: &str, time_utc: &str, time_east8: &str, milliseconds: u64) {
+    fn mvhd_box(path: &str, time_utc: &str, time_east8: &str, milliseconds: u64, time_scale: u32) {
         let _ = tracing_subscriber::fmt().with_test_writer().try_init();
 
         let buf = read_sample(path).unwrap();
@@ -114,7 +154,9 @@ mod tests {
         let bbox = bbox.unwrap();
         let (_, mvhd) = MvhdBox::parse_box(bbox.data).unwrap();
 
-        assert_eq!(mvhd.duration_ms(), milliseconds);
+        assert_eq!(mvhd.duration_ms(), Some(milliseconds));
+        assert_eq!(mvhd.time_scale(), time_scale);
+        assert_eq!(mvhd.preferred_rate(), 1.0);
 
         // time is represented in seconds since midnight, January 1, 1904,
         // preferably using coordinated universal time (UTC).
@@ -131,4 +173,26 @@ mod tests {
             time_east8
         );
     }
+
+    #[test_case(0, 1000, None; "zero time scale")]
+    #[test_case(0, 0, None; "zero time scale & duration")]
+    #[test_case(u32::MAX, u32::MAX, Some(1000); "max time scale")]
+    #[test_case(1, u32::MAX, Some(u32::MAX as u64 * 1000); "max duration")]
+    fn mvhd_unusual_time_scale(time_scale: u32, duration: u32, milliseconds: Option<u64>) {
+        let _ = tracing_subscriber::fmt().with_test_writer().try_init();
+
+        let mut buf = Vec::new();
+        buf.extend_from_slice(&108u32.to_be_bytes());
+        buf.extend_from_slice(b"mvhd");
+        buf.extend_from_slice(&[0; 4]); // version & flags
+        buf.extend_from_slice(&[0; 8]); // creation & modification time
+        buf.extend_from_slice(&time_scale.to_be_bytes());
+        buf.extend_from_slice(&duration.to_be_bytes());
+        buf.extend_from_slice(&0x10000u32.to_be_bytes());
+        buf.extend_from_slice(&[0; 76]);
+
+        let (_, mvhd) = MvhdBox::parse_box(&buf).unwrap();
+        assert_eq!(mvhd.time_scale(), time_scale);
+        assert_eq!(mvhd.duration_ms(), milliseconds);
+    }
 }
@@ -23,12 +23,12 @@ pub struct TkhdBox {
     /// seconds since midnight, January 1, 1904
     modification_time: u32,
 
-    track_id: u32,
+    pub(crate) track_id: u32,
     // reserved: u32,
     duration: u32,
     // reserved2: u64,
     layer: u16,
-    alt_group: u16,
+    pub(crate) alt_group: u16,
     volume: u16,
     // reserved3: u16,
 
@@ -106,7 +106,7 @@ pub fn parse_video_tkhd_in_moov(input: &[u8]) -> crate::Result<Option<TkhdBox>>
     Ok(Some(tkhd))
 }
 
-fn find_video_track(input: &[u8]) -> crate::Result<Option<BoxHolder>> {
+pub(crate) fn find_video_track(input: &[u8]) -> crate::Result<Option<BoxHolder>> {
     let (_, bbox) = travel_while(input, |b| {
         // find video track
         if b.box_type() != "trak" {
@@ -0,0 +1,170 @@
+use std::ops::Range;
+
+use crate::{
+    bbox::travel_while, error::ParsingErrorState, mov::extract_moov_body_from_buf,
+    parser::ParsingState, slice::SubsliceRange,
+};
+
+/// UUID of the `moov/uuid` box holding Canon metadata.
+const CANON_UUID: [u8; 16] = [
+    0x85, 0xc0, 0xb6, 0x87, 0x82, 0x0f, 0x11, 0xe0, 0x81, 0x11, 0xf4, 0xce, 0x46, 0x2b, 0x6a, 0x48,
+];
+
+/// Locations of the TIFF blocks in the Canon metadata box of a CR3 file,
+/// relative to the box body.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Cr3Boxes {
+    /// `CMT1`, IFD0 of the main image
+    pub ifd0: Range<usize>,
+    /// `CMT2`, the Exif IFD
+    pub exif: Option<Range<usize>>,
+    /// `CMT4`, the GPS IFD
+    pub gps: Option<Range<usize>>,
+}
+
+/// Extract the Canon metadata box body of a CR3 file, which holds a TIFF
+/// block in each of the `CMT1` (IFD0), `CMT2` (Exif IFD), `CMT3` (maker
+/// notes) & `CMT4` (GPS IFD) boxes. The locations of the blocks are returned
+/// in `ParsingState::Cr3Boxes`.
+///
+/// Returns `None` if there is no Canon metadata box, or it has no `CMT1`
+/// box.
+#[tracing::instrument(skip_all)]
+pub(crate) fn extract_exif_data(
+    input: &[u8],
+) -> Result<(Option<&[u8]>, Option<ParsingState>), ParsingErrorState> {
+    let moov = extract_moov_body_from_buf(input).map_err(|e| ParsingErrorState::new(e, None))?;
+    let moov_body = &input[moov]; // Safe-slice
+
+    let mut canon = None;
+    let _ = travel_while(moov_body, |b| {
+        if b.box_type() == "uuid" && b.body_data().starts_with(&CANON_UUID) {
+            canon = Some(&b.body_data()[CANON_UUID.len()..]); // Safe-slice
+            false
+        } else {
+            true
+        }
+    });
+    let Some(body) = canon else {
+        tracing::debug!("Canon metadata box not found");
+        return Ok((None, None));
+    };
+
+    let (mut ifd0, mut exif, mut gps) = (None, None, None);
+    // Use the boxes parsed so far if the box is corrupted
+    let _ = travel_while(body, |b| {
+        tracing::debug!(box_type = b.box_type(), size = b.box_size(), "Got");
+        let range = body.subslice_in_range(b.body_data());
+        match b.box_type() {
+            "CMT1" => ifd0 = range,
+            "CMT2" => exif = range,
+            "CMT4" => gps = range,
+            _ => (),
+        }
+        true
+    });
+    let Some(ifd0) = ifd0 else {
+        tracing::debug!("CMT1 not found");
+        return Ok((None, None));
+    };
+
+    let boxes = Cr3Boxes { ifd0, exif, gps };
+    Ok((Some(body), Some(ParsingState::Cr3Boxes(boxes))))
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use crate::error::ParsingError;
+    use crate::testkit::{make_box, TiffBuilder, TiffValue};
+    use nom::number::Endianness;
+
+    use super::*;
+
+    /// Builds a big endian TIFF block with a single IFD, `entries` are
+    /// `(tag, data format, components num, value)`.
+    fn build_tiff(entries: &[(u16, u16, u32, &[u8])]) -> Vec<u8> {
+        let mut tiff = TiffBuilder::new(Endianness::Big);
+        for (tag, format, num, value) in entries {
+            tiff.entry(0, *tag, *format, *num, TiffValue::Data(value.to_vec()));
+        }
+        tiff.build()
+    }
+
+    fn rationals(values: &[(u32, u32)]) -> Vec<u8> {
+        values
+            .iter()
+            .flat_map(|(n, d)| n.to_be_bytes().into_iter().chain(d.to_be_bytes()))
+            .collect()
+    }
+
+    /// Builds a minimal CR3 file with Make/Model in `CMT1`, ExposureTime &
+    /// ISO in `CMT2` and GPS coordinates in `CMT4`, followed by an `mdat` of
+    /// `mdat_size` bytes.
+    pub(crate) fn build_cr3(mdat_size: usize) -> Vec<u8> {
+        let cmt1 = build_tiff(&[
+            (0x010f, 2, 6, b"Canon\0"),
+            (0x0110, 2, 13, b"Canon EOS R5\0"),
+        ]);
+        let cmt2 = build_tiff(&[
+            (0x829a, 5, 1, &rationals(&[(1, 200)])),
+            (0x8827, 3, 1, &400u16.to_be_bytes()),
+        ]);
+        let cmt4 = build_tiff(&[
+            (0x0001, 2, 2, b"N\0"),
+            (0x0002, 5, 3, &rationals(&[(22, 1), (31, 1), (5208, 100)])),
+            (0x0003, 2, 2, b"E\0"),
+            (0x0004, 5, 3, &rationals(&[(114, 1), (1, 1), (1733, 100)])),
+        ]);
+
+        let mut canon = CANON_UUID.to_vec();
+        canon.extend(make_box(b"CMPT", &[0; 16]));
+        canon.extend(make_box(b"CMT1", &cmt1));
+        canon.extend(make_box(b"CMT2", &cmt2));
+        canon.extend(make_box(b"CMT3", &build_tiff(&[])));
+        canon.extend(make_box(b"CMT4", &cmt4));
+        canon.extend(make_box(b"THMB", &[0x55; 64]));
+
+        let mut moov = make_box(b"mvhd", &[0; 100]);
+        moov.extend(make_box(b"uuid", &canon));
+
+        let mut cr3 = make_box(b"ftyp", b"crx \0\0\0\x01crx isom");
+        cr3.extend(make_box(b"moov", &moov));
+        cr3.extend(make_box(b"mdat", &vec![0xaa; mdat_size]));
+        cr3
+    }
+
+    #[test]
+    fn cr3_boxes() {
+        let _ = tracing_subscriber::fmt().with_test_writer().try_init();
+
+        let cr3 = build_cr3(100);
+        let (data, state) = extract_exif_data(&cr3).unwrap();
+        let data = data.unwrap();
+        let Some(ParsingState::Cr3Boxes(boxes)) = state else {
+            panic!("unexpected state: {state:?}");
+        };
+        assert!(data[boxes.ifd0].starts_with(b"MM\0\x2a"));
+        assert!(data[boxes.exif.unwrap()].starts_with(b"MM\0\x2a"));
+        assert!(data[boxes.gps.unwrap()].starts_with(b"MM\0\x2a"));
+
+        // Not loaded yet
+        let err = extract_exif_data(&cr3[..100]).unwrap_err();
+        assert!(matches!(err.err, ParsingError::Need(_)));
+    }
+
+    #[test]
+    fn cr3_without_canon_box() {
+        let _ = tracing_subscriber::fmt().with_test_writer().try_init();
+
+        let mut cr3 = build_cr3(0);
+        let pos = cr3.windows(4).position(|x| x == b"CMT1").unwrap();
+        cr3[pos..pos + 4].copy_from_slice(b"CMTX");
+        let (data, _) = extract_exif_data(&cr3).unwrap();
+        assert_eq!(data, None);
+
+        let pos = cr3.windows(16).position(|x| x == CANON_UUID).unwrap();
+        cr3[pos] = 0;
+        let (data, _) = extract_exif_data(&cr3).unwrap();
+        assert_eq!(data, None);
+    }
+}
@@ -92,6 +92,14 @@ pub(crate) enum EBMLGlobalId {
     Void = 0xEC,
 }
 
+impl EBMLGlobalId {
+    /// `Void` and `CRC-32` elements can appear anywhere in the EBML Body, and
+    /// should always be skipped by their size.
+    pub(crate) fn is_global(id: u64) -> bool {
+        id == Self::Crc32 as u64 || id == Self::Void as u64
+    }
+}
+
 /// Refer to [EBML header
 /// elements](https://github.com/ietf-wg-cellar/ebml-specification/blob/master/specification.markdown#ebml-header-elements)
 pub(crate) fn parse_ebml_doc_type(cursor: &mut Cursor<&[u8]>) -> Result<String, ParseEBMLFailed> {
@@ -211,6 +219,18 @@ fn get_cstr(cursor: &mut Cursor<&[u8]>, size: usize) -> Option<String> {
     Some(s)
 }
 
+/// Get an UTF-8 string, trailing zero bytes will be ignored.
+pub(crate) fn get_as_string(cursor: &mut Cursor<&[u8]>, size: usize) -> Option<String> {
+    if cursor.remaining() < size {
+        return None;
+    }
+    let data = &cursor.chunk()[..size];
+    let end = data.iter().position(|b| *b == 0).unwrap_or(size);
+    let s = String::from_utf8_lossy(&data[..end]).into_owned();
+    cursor.consume(size);
+    Some(s)
+}
+
 pub(crate) fn get_as_u64(cursor: &mut Cursor<&[u8]>, size: usize) -> Option<u64> {
     if cursor.remaining() < size {
         return None;
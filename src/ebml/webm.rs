@@ -1,5 +1,6 @@
 use std::{
-    collections::HashMap,
+    cmp::max,
+    collections::{BTreeMap, HashMap},
     fmt::Debug,
     io::{BufRead, Cursor},
 };
@@ -11,11 +12,12 @@ use thiserror::Error;
 
 use crate::{
     ebml::element::{
-        find_element_by_id, get_as_f64, get_as_u64, next_element_header, parse_ebml_doc_type,
-        EBMLGlobalId, TopElementId,
+        find_element_by_id, get_as_f64, get_as_string, get_as_u64, next_element_header,
+        parse_ebml_doc_type, EBMLGlobalId, TopElementId,
     },
-    error::ParsingError,
-    video::{TrackInfo, TrackInfoTag},
+    error::{ParsingError, ParsingErrorState},
+    parser::ParsingState,
+    video::{DateSource, TrackInfo, TrackInfoTag},
 };
 
 use super::{
@@ -31,6 +33,10 @@ pub struct EbmlFileInfo {
     doc_type: String,
     segment_info: SegmentInfo,
     tracks_info: TracksInfo,
+    tags_info: TagsInfo,
+    /// Position of the `Cues` element, used for estimating the duration when
+    /// the `Duration` element is absent.
+    cues_pos: Option<u64>,
 }
 
 impl From<EbmlFileInfo> for TrackInfo {
@@ -38,6 +44,7 @@ impl From<EbmlFileInfo> for TrackInfo {
         let mut info = TrackInfo::default();
         if let Some(date) = value.segment_info.date {
             info.put(TrackInfoTag::CreateDate, date.into());
+            info.create_date_source = Some(DateSource::EbmlDate);
         }
         info.put(
             TrackInfoTag::DurationMs,
@@ -45,6 +52,29 @@ impl From<EbmlFileInfo> for TrackInfo {
         );
         info.put(TrackInfoTag::ImageWidth, value.tracks_info.width.into());
         info.put(TrackInfoTag::ImageHeight, value.tracks_info.height.into());
+        if let Some(projection) = value.tracks_info.projection {
+            info.put(TrackInfoTag::Projection, projection.into());
+        }
+        if let Some(mode) = value.tracks_info.stereo_mode {
+            info.put(TrackInfoTag::StereoMode, mode.into());
+        }
+        if let Some(primaries) = value.tracks_info.color_primaries {
+            info.put(TrackInfoTag::ColorPrimaries, primaries.into());
+        }
+        if let Some(transfer) = value.tracks_info.transfer_characteristics {
+            info.put(TrackInfoTag::TransferCharacteristics, transfer.into());
+        }
+
+        if let Some(title) = value.tags_info.title.or(value.segment_info.title) {
+            info.put(TrackInfoTag::Title, title.into());
+        }
+        if let Some(artist) = value.tags_info.artist {
+            info.put(TrackInfoTag::Artist, artist.into());
+        }
+        if let Some(album) = value.tags_info.album {
+            info.put(TrackInfoTag::Album, album.into());
+        }
+        info.tags = value.tags_info.tags;
         info
     }
 }
@@ -64,6 +94,44 @@ pub enum ParseWebmFailed {
     InvalidSeekEntry,
 }
 
+/// Don't load a `Cues` element larger than this for estimating the duration.
+const MAX_CUES_SIZE: usize = 1024 * 1024;
+
+/// Same as [`parse_webm`], but if the `Duration` element is absent, the
+/// duration will be estimated from the last `CuePoint` of the `Cues` element,
+/// which is located via the `SeekHead`.
+///
+/// Since `Cues` is usually located at the end of the file, the parser is
+/// asked to skip to it, and `state` will carry the parsed info over.
+pub(crate) fn parse_webm_with_state(
+    input: &[u8],
+    state: Option<ParsingState>,
+) -> Result<EbmlFileInfo, ParsingErrorState> {
+    if let Some(ParsingState::WebmFileInfo(mut info)) = state {
+        // The input starts with the `Cues` element now
+        match parse_cues_duration(input, info.segment_info.time_scale) {
+            Ok(duration) => info.segment_info.duration = duration,
+            Err(ParsingError::Need(n)) => {
+                return Err(ParsingErrorState::new(
+                    ParsingError::Need(n),
+                    Some(ParsingState::WebmFileInfo(info)),
+                ))
+            }
+            Err(e) => tracing::debug!(?e, "estimate duration from Cues failed"),
+        }
+        return Ok(*info);
+    }
+
+    let info = parse_webm(input).map_err(|e| ParsingErrorState::new(e, None))?;
+    match info.cues_pos {
+        Some(pos) if info.segment_info.duration == 0.0 => Err(ParsingErrorState::new(
+            ParsingError::ClearAndSkip(pos as usize),
+            Some(ParsingState::WebmFileInfo(Box::new(info))),
+        )),
+        _ => Ok(info),
+    }
+}
+
 /// Parse EBML based files, e.g.: `.webm`, `.mkv`, etc.
 ///
 /// Refer to:
@@ -81,7 +149,7 @@ pub(crate) fn parse_webm(input: &[u8]) -> Result<EbmlFileInfo, ParsingError> {
 
     let pos = {
         let mut cursor = Cursor::new(&input[pos..]);
-        let header = next_element_header(&mut cursor)?;
+        let header = travel_while(&mut cursor, |h| EBMLGlobalId::is_global(h.id))?;
         tracing::debug!(segment_header = ?header);
         if header.id != TopElementId::Segment as u64 {
             return Err(ParseWebmFailed::NotWebmFile.into());
@@ -100,6 +168,8 @@ pub(crate) fn parse_webm(input: &[u8]) -> Result<EbmlFileInfo, ParsingError> {
     if let Ok(seeks) = parse_seeks(input, pos) {
         let info_seek = seeks.get(&(SegmentId::Info as u32)).cloned();
         let tracks_seek = seeks.get(&(SegmentId::Tracks as u32)).cloned();
+        let tags_seek = seeks.get(&(SegmentId::Tags as u32)).cloned();
+        file_info.cues_pos = seeks.get(&(SegmentId::Cues as u32)).cloned();
         if let Some(pos) = info_seek {
             let info = parse_segment_info(input, pos as usize)?;
             tracing::debug!(?info);
@@ -116,6 +186,14 @@ pub(crate) fn parse_webm(input: &[u8]) -> Result<EbmlFileInfo, ParsingError> {
                 file_info.tracks_info = info;
             }
         }
+        if let Some(pos) = tags_seek {
+            // Tags element is optional, and it's usually located at the end
+            // of the file, so don't try to load more data for it.
+            if let Some(info) = parse_tags_info(input, pos as usize) {
+                tracing::debug!(?info);
+                file_info.tags_info = info;
+            }
+        }
     }
 
     if !info_set {
@@ -157,6 +235,10 @@ pub(crate) fn parse_webm(input: &[u8]) -> Result<EbmlFileInfo, ParsingError> {
 struct TracksInfo {
     width: u32,
     height: u32,
+    projection: Option<&'static str>,
+    stereo_mode: Option<&'static str>,
+    color_primaries: Option<u16>,
+    transfer_characteristics: Option<u16>,
 }
 
 #[tracing::instrument(skip(input))]
@@ -199,6 +281,10 @@ fn parse_tracks_info(input: &[u8], pos: usize) -> Result<Option<TracksInfo>, Par
                 x.map(|x| TracksInfo {
                     width: x.width,
                     height: x.height,
+                    projection: x.projection,
+                    stereo_mode: x.stereo_mode,
+                    color_primaries: x.color_primaries,
+                    transfer_characteristics: x.transfer_characteristics,
                 })
             })?;
 
@@ -288,6 +374,29 @@ fn parse_video_track(input: &[u8]) -> Result<Option<VideoTrackInfo>, ParseWebmFa
         info.height = v as u32;
     }
 
+    cursor.set_position(0);
+    if let Ok(header) = travel_while(&mut cursor, |h| h.id != TracksId::StereoMode as u64) {
+        info.stereo_mode = get_as_u64(&mut cursor, header.data_size).and_then(mkv_stereo_mode);
+    }
+
+    cursor.set_position(0);
+    if let Ok(header) = travel_while(&mut cursor, |h| h.id != TracksId::Projection as u64) {
+        let pos = cursor.position() as usize;
+        if let Some(data) = input.get(pos..pos + header.data_size) {
+            info.projection = parse_projection(data);
+        }
+    }
+
+    cursor.set_position(0);
+    if let Ok(header) = travel_while(&mut cursor, |h| h.id != TracksId::Colour as u64) {
+        let pos = cursor.position() as usize;
+        if let Some(data) = input.get(pos..pos + header.data_size) {
+            info.color_primaries = find_colour_value(data, TracksId::Primaries);
+            info.transfer_characteristics =
+                find_colour_value(data, TracksId::TransferCharacteristics);
+        }
+    }
+
     if info == VideoTrackInfo::default() {
         Ok(None)
     } else {
@@ -295,17 +404,219 @@ fn parse_video_track(input: &[u8]) -> Result<Option<VideoTrackInfo>, ParseWebmFa
     }
 }
 
+/// Maps a Matroska `StereoMode` value to the names used by
+/// [`TrackInfoTag::StereoMode`].
+fn mkv_stereo_mode(mode: u64) -> Option<&'static str> {
+    let mode = match mode {
+        0 => "mono",
+        1 => "left-right",
+        2 => "bottom-top",
+        3 => "top-bottom",
+        11 => "right-left",
+        4..=14 => "stereo-custom",
+        _ => return None,
+    };
+    Some(mode)
+}
+
+/// Finds the value of `id` in a `Colour` element, the values are defined in
+/// ISO/IEC 23091-2, the same as the `colr` box of ISOBMFF.
+fn find_colour_value(input: &[u8], id: TracksId) -> Option<u16> {
+    let mut cursor = Cursor::new(input);
+    let header = travel_while(&mut cursor, |h| h.id != id as u64).ok()?;
+    get_as_u64(&mut cursor, header.data_size)?.try_into().ok()
+}
+
+fn parse_projection(input: &[u8]) -> Option<&'static str> {
+    let mut cursor = Cursor::new(input);
+    let header = travel_while(&mut cursor, |h| h.id != TracksId::ProjectionType as u64).ok()?;
+    let projection = match get_as_u64(&mut cursor, header.data_size)? {
+        1 => "equirectangular",
+        2 => "cubemap",
+        3 => "mesh",
+        // 0 means rectangular, i.e. not a 360° video
+        _ => return None,
+    };
+    Some(projection)
+}
+
 #[derive(Debug, Clone, Default, PartialEq, Eq)]
 struct VideoTrackInfo {
     width: u32,
     height: u32,
+    projection: Option<&'static str>,
+    stereo_mode: Option<&'static str>,
+    color_primaries: Option<u16>,
+    transfer_characteristics: Option<u16>,
 }
 
 #[derive(Debug, Clone, Default)]
 struct SegmentInfo {
     // in nano seconds
     duration: f64,
+    // nano seconds per segment tick
+    time_scale: u64,
     date: Option<DateTime<Utc>>,
+    title: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct TagsInfo {
+    title: Option<String>,
+    artist: Option<String>,
+    album: Option<String>,
+    /// Tags of the default target, i.e. the whole segment
+    tags: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct SimpleTag {
+    target_type_value: u64,
+    /// Whether the tag is bound to a specific track, edition, chapter or
+    /// attachment via a `Tag*UID` element in `Targets`.
+    uid_targeted: bool,
+    name: String,
+    value: String,
+}
+
+/// Returns `None` if the Tags element is incomplete or invalid.
+#[tracing::instrument(skip(input))]
+fn parse_tags_info(input: &[u8], pos: usize) -> Option<TagsInfo> {
+    let mut cursor = Cursor::new(input.get(pos..)?);
+    let header = next_element_header(&mut cursor).ok()?;
+    tracing::debug!(tags_header = ?header);
+    if header.id != SegmentId::Tags as u64 || cursor.remaining() < header.data_size {
+        return None;
+    }
+
+    let mut tags: Vec<SimpleTag> = Vec::new();
+    let mut cursor = Cursor::new(&cursor.chunk()[..header.data_size]);
+    while cursor.has_remaining() {
+        let header = next_element_header(&mut cursor).ok()?;
+        if cursor.remaining() < header.data_size {
+            return None;
+        }
+        if header.id == TagsId::Tag as u64 {
+            let data = &cursor.chunk()[..header.data_size];
+            tags.extend(parse_tag(data).ok()?);
+        }
+        cursor.consume(header.data_size);
+    }
+
+    // According to the specification, the TITLE of TargetTypeValue 50 is the
+    // album title if the track (TargetTypeValue 30) has its own TITLE.
+    let mut titles: Vec<_> = tags.iter().filter(|x| x.name == "TITLE").collect();
+    titles.sort_by_key(|x| x.target_type_value);
+    let mut artists: Vec<_> = tags.iter().filter(|x| x.name == "ARTIST").collect();
+    artists.sort_by_key(|x| x.target_type_value);
+
+    let mut info = TagsInfo {
+        title: titles.first().map(|x| x.value.clone()),
+        artist: artists.first().map(|x| x.value.clone()),
+        album: titles
+            .iter()
+            .skip(1)
+            .find(|x| {
+                x.target_type_value == ALBUM_TARGET_TYPE_VALUE
+                    && titles[0].target_type_value < ALBUM_TARGET_TYPE_VALUE
+            })
+            .map(|x| x.value.clone()),
+        tags: BTreeMap::new(),
+    };
+    for tag in tags
+        .into_iter()
+        .filter(|x| x.target_type_value == ALBUM_TARGET_TYPE_VALUE && !x.uid_targeted)
+    {
+        // Keep the first one if a tag name is repeated
+        info.tags.entry(tag.name).or_insert(tag.value);
+    }
+    Some(info)
+}
+
+const ALBUM_TARGET_TYPE_VALUE: u64 = 50;
+
+fn parse_tag(input: &[u8]) -> Result<Vec<SimpleTag>, ParseEBMLFailed> {
+    let mut target_type_value = ALBUM_TARGET_TYPE_VALUE;
+    let mut uid_targeted = false;
+    let mut simple_tags = Vec::new();
+
+    let mut cursor = Cursor::new(input);
+    while cursor.has_remaining() {
+        let header = next_element_header(&mut cursor)?;
+        if cursor.remaining() < header.data_size {
+            return Err(ParseEBMLFailed::Need(header.data_size - cursor.remaining()));
+        }
+        let data = &cursor.chunk()[..header.data_size];
+
+        match header.id {
+            x if x == TagsId::Targets as u64 => {
+                let mut cur = Cursor::new(data);
+                while let Ok(h) = next_element_header(&mut cur) {
+                    if cur.remaining() < h.data_size {
+                        break;
+                    }
+                    let mut value = Cursor::new(&cur.chunk()[..h.data_size]);
+                    let value = get_as_u64(&mut value, h.data_size);
+                    cur.consume(h.data_size);
+                    match h.id {
+                        x if x == TagsId::TargetTypeValue as u64 => {
+                            if let Some(v) = value {
+                                target_type_value = v;
+                            }
+                        }
+                        // A UID of 0 means all the elements of the type
+                        x if TagsId::is_target_uid(x) && value.is_some_and(|x| x != 0) => {
+                            uid_targeted = true;
+                        }
+                        _ => (),
+                    }
+                }
+            }
+            x if x == TagsId::SimpleTag as u64 => {
+                if let Some(tag) = parse_simple_tag(data)? {
+                    simple_tags.push(tag);
+                }
+            }
+            _ => (),
+        }
+        cursor.consume(header.data_size);
+    }
+
+    Ok(simple_tags
+        .into_iter()
+        .map(|(name, value)| SimpleTag {
+            target_type_value,
+            uid_targeted,
+            name,
+            value,
+        })
+        .collect())
+}
+
+fn parse_simple_tag(input: &[u8]) -> Result<Option<(String, String)>, ParseEBMLFailed> {
+    let mut name = None;
+    let mut value = None;
+
+    let mut cursor = Cursor::new(input);
+    while cursor.has_remaining() {
+        let header = next_element_header(&mut cursor)?;
+        match header.id {
+            x if x == TagsId::TagName as u64 => {
+                name = get_as_string(&mut cursor, header.data_size);
+            }
+            x if x == TagsId::TagString as u64 => {
+                value = get_as_string(&mut cursor, header.data_size);
+            }
+            _ => {
+                if cursor.remaining() < header.data_size {
+                    return Err(ParseEBMLFailed::Need(header.data_size - cursor.remaining()));
+                }
+                cursor.consume(header.data_size);
+            }
+        }
+    }
+
+    Ok(name.map(|x| x.to_uppercase()).zip(value))
 }
 
 #[tracing::instrument(skip(input))]
@@ -365,15 +676,71 @@ fn parse_segment_info_body(cursor: &mut Cursor<&[u8]>) -> Result<SegmentInfo, Pa
                         info.date = Some(DateTime::from_timestamp_nanos(v as i64) + diff);
                     }
                 }
+                InfoId::Title => {
+                    info.title = get_as_string(cursor, header.data_size);
+                }
             }
         } else {
             cursor.consume(header.data_size);
         }
     }
 
+    info.time_scale = time_scale;
     Ok(info)
 }
 
+/// Estimates the duration (in nano seconds) by the time of the last
+/// `CuePoint`, `input` should start with the `Cues` element.
+fn parse_cues_duration(input: &[u8], time_scale: u64) -> Result<f64, ParsingError> {
+    let mut cursor = Cursor::new(input);
+    let header = next_element_header(&mut cursor)?;
+    tracing::debug!(cues_header = ?header);
+
+    if header.id != SegmentId::Cues as u64 {
+        return Err(ParsingError::Failed("Cues element not found".into()));
+    }
+    if header.data_size > MAX_CUES_SIZE {
+        return Err(ParsingError::Failed(format!(
+            "Cues element is too big: {}",
+            header.data_size
+        )));
+    }
+    if cursor.remaining() < header.data_size {
+        return Err(ParsingError::Need(header.data_size - cursor.remaining()));
+    }
+
+    let mut cursor = Cursor::new(&cursor.chunk()[..header.data_size]);
+    let mut last_time = 0;
+    // Cues element is complete now, so any error means it's broken
+    while let Ok(header) = next_element_header(&mut cursor) {
+        if cursor.remaining() < header.data_size {
+            break;
+        }
+        if header.id == CuesId::CuePoint as u64 {
+            let mut point = Cursor::new(&cursor.chunk()[..header.data_size]);
+            if let Some(time) = parse_cue_time(&mut point) {
+                last_time = max(last_time, time);
+            }
+        }
+        cursor.consume(header.data_size);
+    }
+
+    Ok(last_time as f64 * time_scale as f64)
+}
+
+fn parse_cue_time(cursor: &mut Cursor<&[u8]>) -> Option<u64> {
+    while let Ok(header) = next_element_header(cursor) {
+        if header.id == CuesId::CueTime as u64 {
+            return get_as_u64(cursor, header.data_size);
+        }
+        if cursor.remaining() < header.data_size {
+            break;
+        }
+        cursor.consume(header.data_size);
+    }
+    None
+}
+
 fn parse_seeks(input: &[u8], pos: usize) -> Result<HashMap<u32, u64>, ParsingError> {
     let mut cursor = Cursor::new(&input[pos..]);
     // find SeekHead element
@@ -383,11 +750,12 @@ fn parse_seeks(input: &[u8], pos: usize) -> Result<HashMap<u32, u64>, ParsingErr
         return Err(ParsingError::Need(header.data_size - cursor.remaining()));
     }
 
-    let header_pos = pos + cursor.position() as usize - header.header_size;
     let mut cur = Cursor::new(&cursor.chunk()[..header.data_size]);
     let mut seeks = parse_seek_head(&mut cur)?;
-    for (_, pos) in seeks.iter_mut() {
-        *pos += header_pos as u64;
+    // Seek positions are relative to the beginning of the Segment data, which
+    // is not necessarily where SeekHead is, e.g. a Void element comes first.
+    for (_, seek_pos) in seeks.iter_mut() {
+        *seek_pos += pos as u64;
     }
     Ok(seeks)
 }
@@ -446,7 +814,7 @@ fn parse_seek_entry(input: &mut Cursor<&[u8]>) -> Result<Option<SeekEntry>, Pars
 
     if id != SeekHeadId::Seek as u64 {
         input.consume(data_size);
-        if id == EBMLGlobalId::Crc32 as u64 || id == EBMLGlobalId::Void as u64 {
+        if EBMLGlobalId::is_global(id) {
             return Ok(None);
         }
         tracing::debug!(
@@ -473,6 +841,12 @@ fn parse_seek_entry(input: &mut Cursor<&[u8]>) -> Result<Option<SeekEntry>, Pars
                 seek_pos =
                     get_as_u64(&mut buf, size).ok_or_else(|| ParseWebmFailed::InvalidSeekEntry)?;
             }
+            x if EBMLGlobalId::is_global(x) => {
+                if buf.remaining() < size {
+                    return Err(ParseWebmFailed::InvalidSeekEntry);
+                }
+                buf.consume(size);
+            }
             _ => {
                 tracing::debug!(id = format!("0x{id:x}"), "invalid seek entry");
                 return Err(ParseWebmFailed::InvalidSeekEntry);
@@ -498,6 +872,7 @@ enum SegmentId {
     Tracks = 0x1654AE6B,
     Cluster = 0x1F43B675,
     Cues = 0x1C53BB6B,
+    Tags = 0x1254C367,
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -505,6 +880,40 @@ enum InfoId {
     TimestampScale = 0x2AD7B1,
     Duration = 0x4489,
     Date = 0x4461,
+    Title = 0x7BA9,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum CuesId {
+    CuePoint = 0xBB,
+    CueTime = 0xB3,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum TagsId {
+    Tag = 0x7373,
+    Targets = 0x63C0,
+    TargetTypeValue = 0x68CA,
+    SimpleTag = 0x67C8,
+    TagName = 0x45A3,
+    TagString = 0x4487,
+    TagTrackUID = 0x63C5,
+    TagEditionUID = 0x63C9,
+    TagChapterUID = 0x63C4,
+    TagAttachmentUID = 0x63C6,
+}
+
+impl TagsId {
+    fn is_target_uid(id: u64) -> bool {
+        [
+            TagsId::TagTrackUID,
+            TagsId::TagEditionUID,
+            TagsId::TagChapterUID,
+            TagsId::TagAttachmentUID,
+        ]
+        .iter()
+        .any(|x| *x as u64 == id)
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -514,6 +923,12 @@ enum TracksId {
     VideoTrack = 0xE0,
     PixelWidth = 0xB0,
     PixelHeight = 0xBA,
+    StereoMode = 0x53B8,
+    Projection = 0x7670,
+    ProjectionType = 0x7671,
+    Colour = 0x55B0,
+    TransferCharacteristics = 0x55BA,
+    Primaries = 0x55BB,
 }
 
 impl TryFrom<u64> for TracksId {
@@ -525,6 +940,12 @@ impl TryFrom<u64> for TracksId {
             x if x == Self::VideoTrack as u64 => Self::VideoTrack,
             x if x == Self::PixelWidth as u64 => Self::PixelWidth,
             x if x == Self::PixelHeight as u64 => Self::PixelHeight,
+            x if x == Self::StereoMode as u64 => Self::StereoMode,
+            x if x == Self::Projection as u64 => Self::Projection,
+            x if x == Self::ProjectionType as u64 => Self::ProjectionType,
+            x if x == Self::Colour as u64 => Self::Colour,
+            x if x == Self::TransferCharacteristics as u64 => Self::TransferCharacteristics,
+            x if x == Self::Primaries as u64 => Self::Primaries,
             o => return Err(UnknowEbmlIDError(o)),
         };
         Ok(id)
@@ -538,6 +959,7 @@ impl TryFrom<u64> for InfoId {
             x if x == Self::TimestampScale as u64 => Self::TimestampScale,
             x if x == Self::Duration as u64 => Self::Duration,
             x if x == Self::Date as u64 => Self::Date,
+            x if x == Self::Title as u64 => Self::Title,
             o => return Err(UnknowEbmlIDError(o)),
         };
         Ok(id)
@@ -560,6 +982,7 @@ impl TryFrom<u64> for SegmentId {
             x if x == Self::Tracks as u64 => Self::Tracks,
             x if x == Self::Cluster as u64 => Self::Cluster,
             x if x == Self::Cues as u64 => Self::Cues,
+            x if x == Self::Tags as u64 => Self::Tags,
             o => return Err(UnknowEbmlIDError(o)),
         };
         Ok(id)
@@ -650,3 +1073,232 @@ impl From<ParseWebmFailed> for nom::Err<(&[u8], ErrorKind)> {
         nom::Err::Error((&[], ErrorKind::Fail))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    fn element(id: &[u8], data: &[u8]) -> Vec<u8> {
+        assert!(data.len() < 0x7F);
+        let mut v = id.to_vec();
+        v.push(0x80 | data.len() as u8);
+        v.extend_from_slice(data);
+        v
+    }
+
+    fn tag(target: Option<u8>, simple_tags: &[(&str, &str)]) -> Vec<u8> {
+        let mut data = Vec::new();
+        if let Some(target) = target {
+            data.extend(element(&[0x63, 0xC0], &element(&[0x68, 0xCA], &[target])));
+        }
+        for (name, value) in simple_tags {
+            let mut simple = element(&[0x45, 0xA3], name.as_bytes());
+            simple.extend(element(&[0x44, 0x87], value.as_bytes()));
+            data.extend(element(&[0x67, 0xC8], &simple));
+        }
+        element(&[0x73, 0x73], &data)
+    }
+
+    type Tags<'a> = &'a [(Option<u8>, &'a [(&'a str, &'a str)])];
+
+    #[test_case(&[(None, &[("TITLE", "Movie")])], Some("Movie"), None, None, &[("TITLE", "Movie")])]
+    #[test_case(&[(Some(30), &[("title", "Song"), ("ARTIST", "Singer")]), (Some(50), &[("TITLE", "Best Of")])], Some("Song"), Some("Singer"), Some("Best Of"), &[("TITLE", "Best Of")])]
+    #[test_case(&[(Some(50), &[("ENCODER", "Lavf"), ("encoder", "x264")])], None, None, None, &[("ENCODER", "Lavf")])]
+    fn parse_tags(
+        tags: Tags,
+        title: Option<&str>,
+        artist: Option<&str>,
+        album: Option<&str>,
+        default_tags: &[(&str, &str)],
+    ) {
+        let _ = tracing_subscriber::fmt().with_test_writer().try_init();
+
+        let data: Vec<u8> = tags.iter().flat_map(|(t, s)| tag(*t, s)).collect();
+        let buf = element(&[0x12, 0x54, 0xC3, 0x67], &data);
+
+        let info = parse_tags_info(&buf, 0).unwrap();
+        assert_eq!(
+            info,
+            TagsInfo {
+                title: title.map(|x| x.to_string()),
+                artist: artist.map(|x| x.to_string()),
+                album: album.map(|x| x.to_string()),
+                tags: default_tags
+                    .iter()
+                    .map(|(k, v)| (k.to_string(), v.to_string()))
+                    .collect(),
+            }
+        );
+
+        // incomplete Tags element
+        assert!(parse_tags_info(&buf[..buf.len() - 1], 0).is_none());
+    }
+
+    #[test]
+    fn parse_track_targeted_tags() {
+        let _ = tracing_subscriber::fmt().with_test_writer().try_init();
+
+        let simple_tag = |name: &str, value: &str| {
+            let mut simple = element(&[0x45, 0xA3], name.as_bytes());
+            simple.extend(element(&[0x44, 0x87], value.as_bytes()));
+            element(&[0x67, 0xC8], &simple)
+        };
+        // Tag with a TagTrackUID of `uid`
+        let track_tag = |uid: u8, name: &str, value: &str| {
+            let mut data = element(&[0x63, 0xC0], &element(&[0x63, 0xC5], &[uid]));
+            data.extend(simple_tag(name, value));
+            element(&[0x73, 0x73], &data)
+        };
+
+        let mut data = tag(None, &[("ENCODER", "Lavf")]);
+        data.extend(track_tag(1, "HANDLER_NAME", "Video Handler"));
+        // A UID of 0 targets all tracks
+        data.extend(track_tag(0, "COMMENT", "All"));
+        let buf = element(&[0x12, 0x54, 0xC3, 0x67], &data);
+
+        let info = parse_tags_info(&buf, 0).unwrap();
+        assert_eq!(
+            info.tags.into_iter().collect::<Vec<_>>(),
+            [
+                ("COMMENT".to_string(), "All".to_string()),
+                ("ENCODER".to_string(), "Lavf".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_cues() {
+        let _ = tracing_subscriber::fmt().with_test_writer().try_init();
+
+        let cue_point = |time: u8| {
+            let mut data = element(&[0xB3], &[time]);
+            data.extend(element(&[0xB7], &element(&[0xF7], &[1])));
+            element(&[0xBB], &data)
+        };
+        let data: Vec<u8> = [10, 30, 20].into_iter().flat_map(cue_point).collect();
+        let buf = element(&[0x1C, 0x53, 0xBB, 0x6B], &data);
+
+        let duration = parse_cues_duration(&buf, 1_000_000).unwrap();
+        assert_eq!(duration, 30_000_000.0);
+
+        // incomplete Cues element
+        assert!(matches!(
+            parse_cues_duration(&buf[..buf.len() - 2], 1_000_000),
+            Err(ParsingError::Need(2))
+        ));
+
+        // not a Cues element
+        assert!(parse_cues_duration(&data, 1_000_000).is_err());
+    }
+
+    #[test]
+    fn parse_seek_entry_with_global_elements() {
+        let _ = tracing_subscriber::fmt().with_test_writer().try_init();
+
+        let mut data = element(&[0xBF], &[1, 2, 3, 4]);
+        data.extend(element(&[0x53, 0xAB], &[0x15, 0x49, 0xA9, 0x66]));
+        data.extend(element(&[0xEC], &[0, 0]));
+        data.extend(element(&[0x53, 0xAC], &[0x10]));
+        let buf = element(&[0x4D, 0xBB], &data);
+
+        let entry = parse_seek_entry(&mut Cursor::new(&buf)).unwrap().unwrap();
+        assert_eq!(entry.seek_id, SegmentId::Info as u32);
+        assert_eq!(entry.seek_pos, 0x10);
+    }
+
+    #[test]
+    fn parse_seeks_after_void() {
+        let _ = tracing_subscriber::fmt().with_test_writer().try_init();
+
+        let mut data = element(&[0x53, 0xAB], &[0x15, 0x49, 0xA9, 0x66]);
+        data.extend(element(&[0x53, 0xAC], &[0x10]));
+        let mut buf = element(&[0xEC], &[0, 0]);
+        buf.extend(element(
+            &[0x11, 0x4D, 0x9B, 0x74],
+            &element(&[0x4D, 0xBB], &data),
+        ));
+
+        // Seek positions are relative to the Segment data, not the SeekHead
+        let seeks = parse_seeks(&buf, 0).unwrap();
+        assert_eq!(seeks.get(&(SegmentId::Info as u32)), Some(&0x10));
+    }
+
+    #[test_case(None, None, None, None)]
+    #[test_case(Some(1), Some(1), Some("equirectangular"), Some("left-right"))]
+    #[test_case(Some(0), Some(3), None, Some("top-bottom"))]
+    #[test_case(Some(2), Some(0), Some("cubemap"), Some("mono"))]
+    fn parse_video_track_projection(
+        projection_type: Option<u8>,
+        stereo_mode: Option<u8>,
+        projection: Option<&str>,
+        mode: Option<&str>,
+    ) {
+        let _ = tracing_subscriber::fmt().with_test_writer().try_init();
+
+        let mut video = element(&[0xB0], &[0x02, 0x80]);
+        video.extend(element(&[0xBA], &[0x01, 0x68]));
+        if let Some(x) = stereo_mode {
+            video.extend(element(&[0x53, 0xB8], &[x]));
+        }
+        if let Some(x) = projection_type {
+            video.extend(element(&[0x76, 0x70], &element(&[0x76, 0x71], &[x])));
+        }
+        let mut track = element(&[0x83], &[1]);
+        track.extend(element(&[0xE0], &video));
+
+        let info = parse_track(&track).unwrap().unwrap();
+        assert_eq!((info.width, info.height), (640, 360));
+        assert_eq!(info.projection, projection);
+        assert_eq!(info.stereo_mode, mode);
+    }
+
+    #[test_case(Some((9, 16)), Some(9), Some(16); "pq")]
+    #[test_case(Some((1, 18)), Some(1), Some(18); "hlg")]
+    #[test_case(None, None, None; "no colour")]
+    fn parse_video_track_colour(
+        colour: Option<(u8, u8)>,
+        primaries: Option<u16>,
+        transfer: Option<u16>,
+    ) {
+        let _ = tracing_subscriber::fmt().with_test_writer().try_init();
+
+        let mut video = element(&[0xB0], &[0x02, 0x80]);
+        video.extend(element(&[0xBA], &[0x01, 0x68]));
+        if let Some((p, t)) = colour {
+            // MatrixCoefficients, TransferCharacteristics, Primaries
+            let mut data = element(&[0x55, 0xB1], &[9]);
+            data.extend(element(&[0x55, 0xBA], &[t]));
+            data.extend(element(&[0x55, 0xBB], &[p]));
+            video.extend(element(&[0x55, 0xB0], &data));
+        }
+        let mut track = element(&[0x83], &[1]);
+        track.extend(element(&[0xE0], &video));
+
+        let info = parse_track(&track).unwrap().unwrap();
+        assert_eq!(info.color_primaries, primaries);
+        assert_eq!(info.transfer_characteristics, transfer);
+    }
+
+    #[test_case("mkv_640x360.mkv")]
+    #[test_case("webm_480.webm")]
+    fn parse_webm_with_void(path: &str) {
+        let _ = tracing_subscriber::fmt().with_test_writer().try_init();
+
+        let buf = crate::testkit::read_sample(path).unwrap();
+        let expect: TrackInfo = parse_webm(&buf).unwrap().into();
+
+        // Insert a Void element between the EBML header and the Segment
+        let ebml_end = {
+            let mut cursor = Cursor::new(&buf[..]);
+            parse_ebml_doc_type(&mut cursor).unwrap();
+            cursor.position() as usize
+        };
+        let mut data = buf[..ebml_end].to_vec();
+        data.extend(element(&[0xEC], &[0, 0]));
+        data.extend(&buf[ebml_end..]);
+
+        let info: TrackInfo = parse_webm(&data).unwrap().into();
+        assert_eq!(format!("{info:?}"), format!("{expect:?}"));
+    }
+}
@@ -93,6 +93,12 @@ impl ParsingErrorState {
     }
 }
 
+impl From<ParsingError> for ParsingErrorState {
+    fn from(err: ParsingError) -> Self {
+        Self::new(err, None)
+    }
+}
+
 impl Display for ParsingErrorState {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         Display::fmt(
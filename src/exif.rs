@@ -1,29 +1,40 @@
-use crate::error::{nom_error_to_parsing_error_with_state, ParsingError, ParsingErrorState};
+use crate::error::{
+    nom_error_to_parsing_error_with_state, ParsedError, ParsingError, ParsingErrorState,
+};
 use crate::file::MimeImage;
-use crate::parser::{BufParser, ParsingState, ShareBuf};
+use crate::parser::{Buf, BufParser, ParsingState, ShareBuf};
 use crate::raf::RafInfo;
 use crate::skip::Skip;
 use crate::slice::SubsliceRange;
-use crate::{heif, jpeg, MediaParser, MediaSource};
+use crate::{cr3, heif, jp2, jpeg, png, webp, MediaParser, MediaSource};
 #[allow(deprecated)]
 use crate::{partial_vec::PartialVec, FileFormat};
-use exif_exif::check_exif_header2;
-pub use exif_exif::Exif;
+pub use camera::CameraSettings;
+use exif_exif::{heif_exif_tiff_data, EXIF_IDENT};
+pub use exif_exif::{is_exif_header, is_tiff_header, DuplicateStrategy, Exif, TagDiff};
 use exif_iter::input_into_iter;
-pub use exif_iter::{ExifIter, ParsedExifEntry};
+pub use exif_iter::{ExifIter, IfdKind, ParsedExifEntry};
 pub use gps::{GPSInfo, LatLng};
-pub use tags::ExifTag;
+pub use makernote::{AppleMakerNote, CanonMakerNote, LensResolverFn, MakerNoteEntries};
+#[cfg(feature = "json_dump")]
+pub use snapshot::ExifSnapshot;
+pub use tags::{ExifTag, FileSource, SceneType, SensingMethod};
 
 use std::io::Read;
 use std::ops::Range;
 
 pub(crate) mod ifd;
+pub(crate) mod makernote;
 pub(crate) use exif_exif::{check_exif_header, TiffHeader};
+pub(crate) use makernote::LensResolver;
 pub(crate) use travel::IfdHeaderTravel;
 
+mod camera;
 mod exif_exif;
 mod exif_iter;
 mod gps;
+#[cfg(feature = "json_dump")]
+mod snapshot;
 mod tags;
 mod travel;
 
@@ -74,40 +85,170 @@ pub(crate) fn parse_exif_iter<R: Read, S: Skip<R>>(
 ) -> Result<ExifIter, crate::Error> {
     let out = parser.load_and_parse::<R, S, _, _>(reader, |buf, state| {
         extract_exif_range(mime_img, buf, state)
-    })?;
+    });
+    let recover = parser.search_exif_on_corrupt_jpeg && mime_img == MimeImage::Jpeg;
+    let out = if recover && is_damaged(parser, &out) {
+        // Load enough data for searching, since the structured parsing may
+        // have failed early
+        let size = jpeg::EXIF_RECOVERY_SIZE.saturating_sub(parser.buffer().len());
+        let _ = parser.fill_buf(reader, size);
+        Some(recover_jpeg_exif(parser, out)?)
+    } else {
+        out?
+    };
 
-    range_to_iter(parser, out)
+    let swap_endian = parser.swap_endian_on_mismatch;
+    let mut iter = range_to_iter(parser, out, swap_endian)?;
+    iter.set_lens_resolver(parser.lens_resolver.clone());
+    iter.set_max_entry_components(parser.max_entry_components);
+    Ok(iter)
+}
+
+/// Parses the Exif data in parser's buffer, which holds the payload of a JPEG
+/// `APP1` segment. The payload may or may not start with the "Exif\0\0"
+/// header.
+pub(crate) fn parse_exif_iter_from_jpeg_segment(
+    parser: &mut MediaParser,
+) -> Result<ExifIter, crate::Error> {
+    let buf = parser.buffer();
+    let start = if check_exif_header(buf).unwrap_or(false) {
+        EXIF_IDENT.len()
+    } else {
+        0
+    };
+    let range = start..buf.len();
+    let swap_endian = parser.swap_endian_on_mismatch;
+    let mut iter = range_to_iter(parser, Some((range, None)), swap_endian)?;
+    iter.set_lens_resolver(parser.lens_resolver.clone());
+    iter.set_max_entry_components(parser.max_entry_components);
+    Ok(iter)
 }
 
-type ExifRangeResult = Result<Option<(Range<usize>, Option<TiffHeader>)>, ParsingErrorState>;
+type ExifRange = (Range<usize>, Option<ParsingState>);
+type ExifRangeResult = Result<Option<ExifRange>, ParsingErrorState>;
 
 fn extract_exif_range(img: MimeImage, buf: &[u8], state: Option<ParsingState>) -> ExifRangeResult {
     let (exif_data, state) = extract_exif_with_mime(img, buf, state)?;
-    let header = state.and_then(|x| match x {
-        ParsingState::TiffHeader(h) => Some(h),
-        ParsingState::HeifExifSize(_) => None,
-    });
     Ok(exif_data
         .and_then(|x| buf.subslice_in_range(x))
-        .map(|x| (x, header)))
+        .map(|x| (x, state)))
+}
+
+/// Returns true if locating the Exif data failed, or the IFD0 of the located
+/// Exif data is truncated, e.g. because of a corrupt segment length.
+fn is_damaged(parser: &impl Buf, out: &Result<Option<ExifRange>, ParsedError>) -> bool {
+    match out {
+        Ok(Some((range, _))) => {
+            let data = &parser.buffer()[range.clone()]; // Safe-slice
+            let Ok((_, header)) = TiffHeader::parse(data) else {
+                return true;
+            };
+            let Some(ifd0) = data.get(header.ifd0_offset as usize..) else {
+                return true;
+            };
+            IfdHeaderTravel::new(ifd0, header.ifd0_offset, header.endian)
+                .travel_ifd(0)
+                .is_err()
+        }
+        Ok(None) => false,
+        Err(_) => true,
+    }
+}
+
+/// Searches the Exif signature linearly in the loaded data, when the JPEG
+/// segments are damaged, see [`MediaParser::search_exif_on_corrupt_jpeg`].
+///
+/// The original `out` is returned if the signature is not found.
+fn recover_jpeg_exif(
+    parser: &impl Buf,
+    out: Result<Option<ExifRange>, ParsedError>,
+) -> crate::Result<ExifRange> {
+    match (jpeg::search_exif_data(parser.buffer()), out) {
+        (Some(range), _) => {
+            tracing::warn!(?range, "JPEG segments are damaged, Exif data recovered");
+            Ok((range, None))
+        }
+        (None, Ok(Some(out))) => Ok(out),
+        (None, Ok(None)) => Err(EXIF_NOT_FOUND.into()),
+        (None, Err(e)) => Err(e.into()),
+    }
+}
+
+/// Message of the error returned when a media has no Exif data.
+const EXIF_NOT_FOUND: &str = "Exif not found";
+
+/// Returns true if `err` means that the media has no Exif data, rather than
+/// that it's broken.
+pub(crate) fn is_exif_not_found(err: &crate::Error) -> bool {
+    matches!(err, crate::Error::ParseFailed(e) if e.to_string() == EXIF_NOT_FOUND)
 }
 
 fn range_to_iter(
     parser: &mut impl ShareBuf,
-    out: Option<(Range<usize>, Option<TiffHeader>)>,
+    out: Option<ExifRange>,
+    swap_endian_on_mismatch: bool,
 ) -> Result<ExifIter, crate::Error> {
-    if let Some((range, header)) = out {
-        tracing::debug!(?range, ?header, "Got Exif data");
+    if let Some((range, state)) = out {
+        let (header, orientation, dimensions) = match state {
+            Some(ParsingState::TiffHeader(h)) => (Some(h), None, None),
+            Some(ParsingState::HeifExif { orientation, .. }) => (None, orientation, None),
+            Some(ParsingState::WebpChunk { canvas_size }) => (None, None, Some(canvas_size)),
+            Some(ParsingState::Cr3Boxes(boxes)) => {
+                tracing::debug!(?range, ?boxes, "Got CR3 Exif data");
+                let input: PartialVec = parser.share_buf(range);
+                return cr3_into_iter(input, boxes, swap_endian_on_mismatch);
+            }
+            Some(ParsingState::WebmFileInfo(_))
+            | Some(ParsingState::PngChunk)
+            | Some(ParsingState::HeifXmp { .. })
+            | None => (None, None, None),
+        };
+        tracing::debug!(?range, ?header, ?orientation, ?dimensions, "Got Exif data");
         let input: PartialVec = parser.share_buf(range);
-        let iter = input_into_iter(input, header)?;
+        let mut iter = input_into_iter(input, header, swap_endian_on_mismatch)?;
+        iter.set_container_orientation(orientation);
+        iter.set_container_dimensions(dimensions);
 
         Ok(iter)
     } else {
         tracing::debug!("Exif not found");
-        Err("Exif not found".into())
+        Err(EXIF_NOT_FOUND.into())
     }
 }
 
+/// Builds an [`ExifIter`] from the TIFF blocks of a CR3 file, the Exif & GPS
+/// IFDs are stored in their own blocks rather than linked from IFD0. Broken
+/// sub-IFD blocks are ignored, and the first value of a duplicated tag is
+/// kept when converting into an [`Exif`].
+fn cr3_into_iter(
+    input: PartialVec,
+    boxes: cr3::Cr3Boxes,
+    swap_endian_on_mismatch: bool,
+) -> crate::Result<ExifIter> {
+    let block = |range: Range<usize>| {
+        input
+            .get(range)
+            .map(|x| input.partial(x))
+            .ok_or_else(|| crate::Error::ParseFailed("CR3 box is out of range".into()))
+    };
+
+    let mut iter = input_into_iter(block(boxes.ifd0)?, None, swap_endian_on_mismatch)?;
+    iter.set_duplicate_strategy(DuplicateStrategy::FirstWins);
+    let sub_ifds = [
+        (boxes.exif, ExifTag::ExifOffset),
+        (boxes.gps, ExifTag::GPSInfo),
+    ];
+    for (range, tag) in sub_ifds {
+        let Some(range) = range else {
+            continue;
+        };
+        if let Err(e) = block(range).and_then(|x| iter.add_sub_ifd(x, tag)) {
+            tracing::warn!(?tag, error = %e, "ignored broken CR3 sub-IFD");
+        }
+    }
+    Ok(iter)
+}
+
 #[cfg(feature = "async")]
 #[tracing::instrument(skip(reader))]
 pub(crate) async fn parse_exif_iter_async<
@@ -124,9 +265,21 @@ pub(crate) async fn parse_exif_iter_async<
         .load_and_parse::<R, S, _, _>(reader, |buf, state| {
             extract_exif_range(mime_img, buf, state)
         })
-        .await?;
+        .await;
+    let recover = parser.search_exif_on_corrupt_jpeg && mime_img == MimeImage::Jpeg;
+    let out = if recover && is_damaged(parser, &out) {
+        let size = jpeg::EXIF_RECOVERY_SIZE.saturating_sub(parser.buffer().len());
+        let _ = parser.fill_buf(reader, size).await;
+        Some(recover_jpeg_exif(parser, out)?)
+    } else {
+        out?
+    };
 
-    range_to_iter(parser, out)
+    let swap_endian = parser.swap_endian_on_mismatch;
+    let mut iter = range_to_iter(parser, out, swap_endian)?;
+    iter.set_lens_resolver(parser.lens_resolver.clone());
+    iter.set_max_entry_components(parser.max_entry_components);
+    Ok(iter)
 }
 
 pub(crate) fn extract_exif_with_mime(
@@ -138,7 +291,7 @@ pub(crate) fn extract_exif_with_mime(
         MimeImage::Jpeg => jpeg::extract_exif_data(buf)
             .map(|res| (res.1, state.clone()))
             .map_err(|e| nom_error_to_parsing_error_with_state(e, state))?,
-        MimeImage::Heic | crate::file::MimeImage::Heif => heif_extract_exif(state, buf)?,
+        MimeImage::Heic | MimeImage::Heif | MimeImage::Avif => heif_extract_exif(state, buf)?,
         MimeImage::Tiff => {
             let (header, data_start) = match state {
                 Some(ParsingState::TiffHeader(ref h)) => (h.to_owned(), 0),
@@ -168,6 +321,12 @@ pub(crate) fn extract_exif_with_mime(
         MimeImage::Raf => RafInfo::parse(buf)
             .map(|res| (res.1.exif_data, state.clone()))
             .map_err(|e| nom_error_to_parsing_error_with_state(e, state))?,
+        MimeImage::Jp2 => jp2::extract_exif_data(buf)
+            .map(|res| (res.1, state.clone()))
+            .map_err(|e| nom_error_to_parsing_error_with_state(e, state))?,
+        MimeImage::Png => png::extract_exif_data(buf, state)?,
+        MimeImage::WebP => webp::extract_exif_data(buf, state)?,
+        MimeImage::Cr3 => cr3::extract_exif_data(buf)?,
     };
     Ok((exif_data, state))
 }
@@ -177,8 +336,8 @@ fn heif_extract_exif(
     buf: &[u8],
 ) -> Result<(Option<&[u8]>, Option<ParsingState>), ParsingErrorState> {
     let (data, state) = match state {
-        Some(ParsingState::HeifExifSize(size)) => {
-            let (_, data) = nom::bytes::streaming::take(size)(buf)
+        Some(ParsingState::HeifExif { exif_size, .. }) => {
+            let (_, data) = nom::bytes::streaming::take(exif_size)(buf)
                 .map_err(|e| nom_error_to_parsing_error_with_state(e, state.clone()))?;
             (Some(data), state)
         }
@@ -188,12 +347,17 @@ fn heif_extract_exif(
 
             if let Some(meta) = meta {
                 if let Some(range) = meta.exif_data_offset() {
+                    // Keep the container orientation in the state, since
+                    // the meta box is gone after skipping
+                    let state = Some(ParsingState::HeifExif {
+                        exif_size: range.len(),
+                        orientation: meta.primary_orientation(),
+                    });
                     if range.end > buf.len() {
-                        let state = ParsingState::HeifExifSize(range.len());
                         let clear_and_skip = ParsingError::ClearAndSkip(range.start);
-                        return Err(ParsingErrorState::new(clear_and_skip, Some(state)));
+                        return Err(ParsingErrorState::new(clear_and_skip, state));
                     } else {
-                        (Some(&buf[range]), None)
+                        (Some(&buf[range]), state)
                     }
                 } else {
                     return Err(ParsingErrorState::new(
@@ -208,7 +372,7 @@ fn heif_extract_exif(
         _ => unreachable!(),
     };
 
-    let data = data.and_then(|x| check_exif_header2(x).map(|x| x.0).ok());
+    let data = data.and_then(heif_exif_tiff_data);
 
     Ok((data, state))
 }
@@ -249,9 +413,15 @@ mod tests {
 
     use super::*;
 
-    #[test_case("exif.heic", "+43.29013+084.22713+1595.950CRSWGS_84/")]
-    #[test_case("exif.jpg", "+22.53113+114.02148/")]
-    fn gps(path: &str, gps_str: &str) {
+    #[test_case(
+        "exif.heic",
+        "+43.29013+084.22713+1595.950CRSWGS_84/",
+        "43.29013",
+        "84.22713",
+        "1595.950"
+    )]
+    #[test_case("exif.jpg", "+22.53113+114.02148/", "22.53113", "114.02148", "0.000")]
+    fn gps(path: &str, gps_str: &str, lat: &str, lng: &str, altitude: &str) {
         let f = open_sample(path).unwrap();
         let iter = parse_exif(f, None)
             .expect("should be Ok")
@@ -266,6 +436,14 @@ mod tests {
         //     .expect("should be parsed Ok")
         //     .expect("should not be None");
         assert_eq!(gps_info.format_iso6709(), gps_str);
+
+        let (latitude, longitude) = gps_info.to_decimal();
+        assert_eq!(format!("{latitude:.5}"), lat);
+        assert_eq!(format!("{longitude:.5}"), lng);
+        assert_eq!(gps_info.latitude.as_degrees(), latitude);
+
+        let meters = gps_info.altitude_meters().unwrap();
+        assert_eq!(format!("{meters:.3}"), altitude);
     }
 
     #[cfg(feature = "async")]
@@ -285,7 +463,7 @@ mod tests {
         let gps_str = gps_str.to_owned();
         let _ = tokio::spawn(async move {
             let exif: Exif = iter.into();
-            let gps_info = exif.get_gps_info().expect("ok").expect("some");
+            let gps_info = exif.gps().expect("some");
             assert_eq!(gps_info.format_iso6709(), gps_str);
         })
         .await;
@@ -321,10 +499,10 @@ mod tests {
         let data = data.unwrap();
 
         let subslice_in_range = buf.subslice_in_range(data).unwrap();
-        let iter = input_into_iter((buf, subslice_in_range), None).unwrap();
+        let iter = input_into_iter((buf, subslice_in_range), None, false).unwrap();
         let exif: Exif = iter.into();
 
-        let gps = exif.get_gps_info().unwrap().unwrap();
+        let gps = exif.gps().unwrap().clone();
         assert_eq!(
             gps,
             GPSInfo {
@@ -336,8 +514,17 @@ mod tests {
                 altitude,
                 speed_ref,
                 speed,
+                time_stamp: Some([(12, 1).into(), (36, 1).into(), (33, 1).into()]),
+                date_stamp: Some("2023:07:09".to_string()),
+                ..Default::default()
             }
-        )
+        );
+        assert_eq!(
+            gps.format_gps_datetime_iso8601().as_deref(),
+            Some("2023-07-09T12:36:33Z")
+        );
+        assert_eq!(exif.get_gps_datetime(), gps.gps_datetime());
+        assert!(exif.get_gps_datetime().is_some());
     }
 
     #[test_case("exif.heic")]
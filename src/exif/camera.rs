@@ -0,0 +1,115 @@
+use chrono::{DateTime, FixedOffset};
+
+use crate::{values::URational, EntryValue, Exif, ExifTag};
+
+use super::exif_exif::entry_as_u32_vec;
+
+/// The commonly used capture parameters of a photo, see
+/// [`Exif::camera_settings`].
+///
+/// APEX values (`ShutterSpeedValue`, `ApertureValue`) are used as fallbacks
+/// when `ExposureTime`/`FNumber` are absent.
+///
+/// New fields may be added in minor releases, so this struct can't be
+/// constructed with a struct literal outside this crate.
+#[derive(Debug, Default, Clone, PartialEq)]
+#[non_exhaustive]
+pub struct CameraSettings {
+    pub make: Option<String>,
+    pub model: Option<String>,
+    /// `LensModel`, or `LensMake` if the model is absent
+    pub lens: Option<String>,
+
+    /// ISO speed
+    pub iso: Option<u32>,
+    /// Shutter speed in seconds, e.g. 1/100
+    pub exposure_time: Option<URational>,
+    /// Aperture as an f-number, e.g. 1.8
+    pub f_number: Option<f64>,
+    /// Focal length in millimeters
+    pub focal_length: Option<f64>,
+    /// Focal length in 35 mm film equivalent, in millimeters
+    pub focal_length_35mm: Option<u32>,
+
+    /// Raw `Flash` value, bit 0 indicates whether the flash fired
+    pub flash: Option<u16>,
+    /// Exposure compensation in EV
+    pub exposure_bias: Option<f64>,
+    /// Raw `MeteringMode` value
+    /// - 0: Unknown
+    /// - 1: Average
+    /// - 2: Center-weighted average
+    /// - 3: Spot
+    /// - 4: Multi-spot
+    /// - 5: Multi-segment
+    /// - 6: Partial
+    /// - 255: Other
+    pub metering_mode: Option<u16>,
+
+    /// `DateTimeOriginal`, or `CreateDate`/`ModifyDate` if it's absent, see
+    /// [`Exif::best_datetime`]
+    pub date_time: Option<DateTime<FixedOffset>>,
+}
+
+impl CameraSettings {
+    pub(crate) fn new(exif: &Exif) -> Self {
+        let get_str = |tag| exif.get(tag).and_then(|x| x.as_str()).map(str::to_owned);
+        let get_u32 = |tag| {
+            exif.get(tag)
+                .and_then(entry_as_u32_vec)
+                .and_then(|x| x.first().copied())
+        };
+        let get_f64 = |tag| exif.get(tag).and_then(EntryValue::as_f64);
+
+        let exposure_time = exif
+            .get(ExifTag::ExposureTime)
+            .and_then(EntryValue::as_urational)
+            .filter(|x| x.1 != 0)
+            .or_else(|| get_f64(ExifTag::ShutterSpeedValue).map(apex_to_exposure_time));
+        let f_number = get_f64(ExifTag::FNumber)
+            .or_else(|| get_f64(ExifTag::ApertureValue).map(|av| 2f64.powf(av / 2.0)));
+
+        CameraSettings {
+            make: get_str(ExifTag::Make),
+            model: get_str(ExifTag::Model),
+            lens: get_str(ExifTag::LensModel).or_else(|| get_str(ExifTag::LensMake)),
+            iso: get_u32(ExifTag::ISOSpeedRatings),
+            exposure_time,
+            f_number,
+            focal_length: get_f64(ExifTag::FocalLength),
+            focal_length_35mm: get_u32(ExifTag::FocalLengthIn35mmFilm),
+            flash: get_u32(ExifTag::Flash).and_then(|x| x.try_into().ok()),
+            exposure_bias: get_f64(ExifTag::ExposureBiasValue),
+            metering_mode: get_u32(ExifTag::MeteringMode).and_then(|x| x.try_into().ok()),
+            date_time: exif.best_datetime(),
+        }
+    }
+
+    /// Returns whether the flash fired.
+    pub fn flash_fired(&self) -> Option<bool> {
+        self.flash.map(|x| x & 1 == 1)
+    }
+}
+
+/// Converts an APEX time value (Tv) to an exposure time, i.e. `2^-Tv`
+/// seconds.
+fn apex_to_exposure_time(tv: f64) -> URational {
+    let t = 2f64.powf(tv);
+    if t >= 1.0 {
+        URational::from((1, t.round() as u32))
+    } else {
+        URational::from(((1.0 / t).round() as u32, 1))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn apex() {
+        assert_eq!(apex_to_exposure_time(6.644), (1, 100).into());
+        assert_eq!(apex_to_exposure_time(0.0), (1, 1).into());
+        assert_eq!(apex_to_exposure_time(-2.0), (4, 1).into());
+    }
+}
@@ -1,24 +1,58 @@
+use std::ops::Range;
+
+use chrono::{DateTime, FixedOffset, TimeZone, Utc};
 use nom::{
     branch::alt, bytes::streaming::tag, combinator, number::Endianness, sequence, IResult, Needed,
 };
 
-use crate::{EntryValue, ExifIter, ExifTag, GPSInfo, ParsedExifEntry};
+use crate::{
+    CameraSettings, EntryValue, ExifIter, ExifTag, GPSInfo, IRational, IfdKind, ParsedExifEntry,
+    URational,
+};
+
+use super::ifd::{ParsedIdfEntry, ParsedImageFileDirectory};
 
-use super::ifd::ParsedImageFileDirectory;
+/// `Compression` value of a JPEG compressed thumbnail.
+const JPEG_COMPRESSION: u16 = 6;
+
+/// Resolves the TIFF-relative range of the JPEG thumbnail from the values of
+/// `ThumbnailOffset`, `ThumbnailLength` & `Compression` in ifd1.
+pub(crate) fn thumbnail_range(
+    offset: Option<&EntryValue>,
+    length: Option<&EntryValue>,
+    compression: Option<&EntryValue>,
+) -> Option<Range<usize>> {
+    if let Some(compression) = compression {
+        if compression.as_u16() != Some(JPEG_COMPRESSION) {
+            tracing::debug!(?compression, "thumbnail isn't JPEG compressed");
+            return None;
+        }
+    }
+    let offset = offset?.as_u32()? as usize;
+    let length = length?.as_u32()? as usize;
+    if length == 0 {
+        return None;
+    }
+    Some(offset..offset.checked_add(length)?)
+}
 
 /// Represents parsed Exif information, can be converted from an [`ExifIter`]
 /// like this: `let exif: Exif = iter.into()`.
 #[derive(Clone, Debug, PartialEq)]
 pub struct Exif {
-    ifds: Vec<ParsedImageFileDirectory>,
-    gps_info: Option<GPSInfo>,
+    pub(crate) ifds: Vec<ParsedImageFileDirectory>,
+    pub(crate) gps_info: Option<GPSInfo>,
+    pub(crate) container_orientation: Option<u16>,
+    pub(crate) container_dimensions: Option<(u32, u32)>,
 }
 
 impl Exif {
-    fn new(gps_info: Option<GPSInfo>) -> Exif {
+    fn new(gps_info: Option<GPSInfo>, container_orientation: Option<u16>) -> Exif {
         Exif {
             ifds: Vec::new(),
             gps_info,
+            container_orientation,
+            container_dimensions: None,
         }
     }
 
@@ -62,6 +96,9 @@ impl Exif {
     /// - 0: ifd0 (the main image)
     /// - 1: ifd1 (thumbnail image)
     ///
+    /// Entries of sub-IFDs (e.g. the Exif & GPS sub-IFDs) are stored in their
+    /// parent IFD, see [`ParsedExifEntry::ifd_index`].
+    ///
     /// *Note*:
     ///
     /// - The parsing error related to this tag won't be reported by this
@@ -92,6 +129,155 @@ impl Exif {
         self.ifds.get(ifd).and_then(|ifd| ifd.get(tag))
     }
 
+    /// List `(ifd index, tag code)` of all present entries, ordered by IFD
+    /// index and then by tag code.
+    ///
+    /// Tags which aren't recognized as an [`ExifTag`], e.g. vendor specific
+    /// ones, are included as well, their values can be fetched with
+    /// [`Self::get_by_ifd_tag_code`].
+    ///
+    /// ```rust
+    /// use nom_exif::*;
+    ///
+    /// fn main() -> Result<()> {
+    ///     let mut parser = MediaParser::new();
+    ///     let ms = MediaSource::file_path("./testdata/exif.jpg")?;
+    ///     let iter: ExifIter = parser.parse(ms)?;
+    ///     let exif: Exif = iter.into();
+    ///
+    ///     let codes = exif.raw_tag_codes();
+    ///     assert!(codes.contains(&(0, ExifTag::Make.code())));
+    ///     for (ifd, tag) in codes {
+    ///         assert!(exif.get_by_ifd_tag_code(ifd, tag).is_some());
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn raw_tag_codes(&self) -> Vec<(usize, u16)> {
+        let mut codes = self
+            .ifds
+            .iter()
+            .enumerate()
+            .flat_map(|(idx, ifd)| ifd.entries.keys().map(move |tag| (idx, *tag)))
+            .collect::<Vec<_>>();
+        codes.sort();
+        codes
+    }
+
+    /// Estimate the memory footprint of the parsed Exif data in bytes,
+    /// including the entry tables of IFDs and the heap allocations of string
+    /// & array values, e.g. for bounding the total size of a metadata cache.
+    ///
+    /// It's an approximation: the bookkeeping of the allocator & the hash
+    /// tables isn't counted exactly.
+    ///
+    /// ```rust
+    /// use nom_exif::*;
+    ///
+    /// let mut parser = MediaParser::new();
+    /// let ms = MediaSource::file_path("./testdata/exif.jpg").unwrap();
+    /// let exif: Exif = parser.parse::<_, _, ExifIter>(ms).unwrap().into();
+    /// assert!(exif.approx_heap_bytes() > std::mem::size_of::<Exif>());
+    /// ```
+    pub fn approx_heap_bytes(&self) -> usize {
+        use std::mem::size_of;
+
+        let ifds: usize = self
+            .ifds
+            .iter()
+            .map(|ifd| {
+                let table =
+                    ifd.entries.capacity() * (size_of::<u16>() + size_of::<ParsedIdfEntry>());
+                let values: usize = ifd.entries.values().map(|x| x.value.heap_bytes()).sum();
+                table + values
+            })
+            .sum();
+        let gps = self
+            .gps_info
+            .as_ref()
+            .and_then(|x| x.date_stamp.as_ref())
+            .map(|x| x.capacity())
+            .unwrap_or(0);
+
+        size_of::<Self>()
+            + self.ifds.capacity() * size_of::<ParsedImageFileDirectory>()
+            + ifds
+            + gps
+    }
+
+    /// Get the `u8` array value of `tag` in ifd0 (the main image).
+    ///
+    /// Like the other typed array getters, e.g. [`Self::get_u16_array`], a
+    /// single value is returned as a one-element slice, since an array with
+    /// only one component is parsed as a scalar value. `None` is returned if
+    /// the tag doesn't exist or has a different type.
+    pub fn get_u8_array(&self, tag: ExifTag) -> Option<&[u8]> {
+        match self.get(tag)? {
+            EntryValue::U8(v) => Some(std::slice::from_ref(v)),
+            v => v.as_u8_array(),
+        }
+    }
+
+    /// Get the `u16` array value of `tag` in ifd0 (the main image), e.g.
+    /// `BitsPerSample`, see [`Self::get_u8_array`].
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// use nom_exif::*;
+    ///
+    /// let mut parser = MediaParser::new();
+    /// let ms = MediaSource::file_path("./testdata/tif.tif").unwrap();
+    /// let exif: Exif = parser.parse::<_, _, ExifIter>(ms).unwrap().into();
+    /// // A grayscale image, whose single value is returned as a slice
+    /// assert_eq!(exif.get_u16_array(ExifTag::BitsPerSample), Some(&[8][..]));
+    ///
+    /// // Not an array of u16
+    /// assert_eq!(exif.get_u16_array(ExifTag::Make), None);
+    /// ```
+    pub fn get_u16_array(&self, tag: ExifTag) -> Option<&[u16]> {
+        match self.get(tag)? {
+            EntryValue::U16(v) => Some(std::slice::from_ref(v)),
+            v => v.as_u16_array(),
+        }
+    }
+
+    /// Get the `u32` array value of `tag` in ifd0 (the main image), see
+    /// [`Self::get_u8_array`].
+    pub fn get_u32_array(&self, tag: ExifTag) -> Option<&[u32]> {
+        match self.get(tag)? {
+            EntryValue::U32(v) => Some(std::slice::from_ref(v)),
+            v => v.as_u32_array(),
+        }
+    }
+
+    /// Get the `i16` array value of `tag` in ifd0 (the main image), see
+    /// [`Self::get_u8_array`].
+    pub fn get_i16_array(&self, tag: ExifTag) -> Option<&[i16]> {
+        match self.get(tag)? {
+            EntryValue::I16(v) => Some(std::slice::from_ref(v)),
+            v => v.as_i16_array(),
+        }
+    }
+
+    /// Get the unsigned rational array value of `tag` in ifd0 (the main
+    /// image), e.g. `ReferenceBlackWhite`, see [`Self::get_u8_array`].
+    pub fn get_urational_array(&self, tag: ExifTag) -> Option<&[URational]> {
+        match self.get(tag)? {
+            EntryValue::URational(v) => Some(std::slice::from_ref(v)),
+            v => v.as_urational_array(),
+        }
+    }
+
+    /// Get the signed rational array value of `tag` in ifd0 (the main
+    /// image), see [`Self::get_u8_array`].
+    pub fn get_irational_array(&self, tag: ExifTag) -> Option<&[IRational]> {
+        match self.get(tag)? {
+            EntryValue::IRational(v) => Some(std::slice::from_ref(v)),
+            v => v.as_irational_array(),
+        }
+    }
+
     /// Get entry values for the specified `tags` in ifd0 (the main image).
     ///
     /// Please note that this method will ignore errors encountered during the
@@ -126,31 +312,523 @@ impl Exif {
         Ok(self.get_by_ifd_tag_code(0, tag).map(|x| x.to_owned()))
     }
 
+    /// Get the orientation (1–8, the same as the Exif `Orientation` values)
+    /// the image should be displayed with.
+    ///
+    /// Some formats, e.g. HEIF/HEIC, can declare the orientation in the
+    /// container (the `irot`/`imir` properties) as well as in the Exif data,
+    /// and the two may conflict. The precedence is:
+    ///
+    /// 1. The container transform, since readers are required to apply it
+    ///    (see [`ExifIter::container_orientation`]).
+    /// 2. The `Orientation` entry of ifd0, if the container doesn't declare
+    ///    any transform.
+    ///
+    /// Apply only the returned orientation, applying both would rotate the
+    /// image twice.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// use nom_exif::*;
+    ///
+    /// let mut parser = MediaParser::new();
+    /// let ms = MediaSource::file_path("./testdata/exif.heic").unwrap();
+    /// let exif: Exif = parser.parse::<_, _, ExifIter>(ms).unwrap().into();
+    /// assert_eq!(exif.effective_orientation(), Some(6));
+    /// ```
+    pub fn effective_orientation(&self) -> Option<u16> {
+        self.container_orientation.or_else(|| {
+            self.get(ExifTag::Orientation)
+                .and_then(entry_as_u32)
+                .and_then(|x| x.try_into().ok())
+        })
+    }
+
+    /// Get the image dimensions `(width, height)` after applying the
+    /// orientation, i.e. the dimensions the image should be displayed with.
+    ///
+    /// `ExifImageWidth`/`ExifImageHeight` are preferred, `ImageWidth`/
+    /// `ImageHeight` are used as a fallback, and then the dimensions declared
+    /// by the container (see [`ExifIter::container_dimensions`]). Width and
+    /// height are swapped if
+    /// the [effective orientation](Self::effective_orientation) is 5–8, which
+    /// means the image needs to be rotated by 90°.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// use nom_exif::*;
+    ///
+    /// fn main() -> Result<()> {
+    ///     let mut parser = MediaParser::new();
+    ///
+    ///     let ms = MediaSource::file_path("./testdata/exif.heic")?;
+    ///     let iter: ExifIter = parser.parse(ms)?;
+    ///     let exif: Exif = iter.into();
+    ///
+    ///     assert_eq!(exif.get(ExifTag::ExifImageWidth).unwrap(), &4032_u32.into());
+    ///     assert_eq!(exif.display_dimensions(), Some((3024, 4032)));
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn display_dimensions(&self) -> Option<(u32, u32)> {
+        let get_u32 = |tag| self.get(tag).and_then(entry_as_u32);
+        let (width, height) = get_u32(ExifTag::ExifImageWidth)
+            .zip(get_u32(ExifTag::ExifImageHeight))
+            .or_else(|| get_u32(ExifTag::ImageWidth).zip(get_u32(ExifTag::ImageHeight)))
+            .or(self.container_dimensions)?;
+
+        if matches!(self.effective_orientation(), Some(5..=8)) {
+            Some((height, width))
+        } else {
+            Some((width, height))
+        }
+    }
+
+    /// Get the byte range of the JPEG thumbnail in ifd1, resolved from
+    /// `ThumbnailOffset`/`ThumbnailLength`.
+    ///
+    /// The range is relative to the start of the TIFF header like
+    /// [`Self::strip_ranges`], since an `Exif` doesn't keep the raw data. Use
+    /// [`ExifIter::extract_thumbnail`] to get the thumbnail bytes.
+    ///
+    /// Returns `None` if the entries are missing, or the thumbnail isn't a
+    /// JPEG image, i.e. `Compression` of ifd1 is present and isn't 6.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// use nom_exif::*;
+    ///
+    /// let mut parser = MediaParser::new();
+    /// let ms = MediaSource::file_path("./testdata/exif.jpg").unwrap();
+    /// let exif: Exif = parser.parse::<_, _, ExifIter>(ms).unwrap().into();
+    /// let range = exif.thumbnail_range().unwrap();
+    /// assert_eq!(range.len(), 16234);
+    /// ```
+    pub fn thumbnail_range(&self) -> Option<Range<usize>> {
+        let get = |tag: ExifTag| self.get_by_ifd_tag_code(1, tag.code());
+        thumbnail_range(
+            get(ExifTag::ThumbnailOffset),
+            get(ExifTag::ThumbnailLength),
+            get(ExifTag::Compression),
+        )
+    }
+
+    /// Get the byte ranges of the main image's pixel data, resolved from
+    /// `StripOffsets`/`StripByteCounts`, or `TileOffsets`/`TileByteCounts`
+    /// for tiled images.
+    ///
+    /// The ranges are relative to the start of the TIFF header, which is the
+    /// start of the file for *.tif/*.tiff files. An empty `Vec` is returned if
+    /// the entries are missing or inconsistent.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// use nom_exif::*;
+    ///
+    /// fn main() -> Result<()> {
+    ///     let mut parser = MediaParser::new();
+    ///
+    ///     let ms = MediaSource::file_path("./testdata/tif.tif")?;
+    ///     let iter: ExifIter = parser.parse(ms)?;
+    ///     let exif: Exif = iter.into();
+    ///
+    ///     let ranges = exif.strip_ranges();
+    ///     assert_eq!(ranges.len(), 40);
+    ///     assert_eq!(ranges[0], 8..7746);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn strip_ranges(&self) -> Vec<Range<usize>> {
+        let get_u32s = |tag| self.get(tag).and_then(entry_as_u32_vec);
+        let Some((offsets, counts)) = get_u32s(ExifTag::StripOffsets)
+            .zip(get_u32s(ExifTag::StripByteCounts))
+            .or_else(|| get_u32s(ExifTag::TileOffsets).zip(get_u32s(ExifTag::TileByteCounts)))
+        else {
+            return Vec::new();
+        };
+
+        if offsets.len() != counts.len() {
+            tracing::warn!(
+                offsets = offsets.len(),
+                counts = counts.len(),
+                "strip offsets and byte counts mismatch"
+            );
+            return Vec::new();
+        }
+
+        offsets
+            .into_iter()
+            .zip(counts)
+            .map(|(offset, count)| {
+                offset as usize..(offset as usize).saturating_add(count as usize)
+            })
+            .collect()
+    }
+
+    /// Get the best guess of when the photo was taken, i.e. `DateTimeOriginal`,
+    /// falling back to `CreateDate`, then `ModifyDate`, which is often the
+    /// only date of scanned or processed images.
+    ///
+    /// The offset tag corresponding to the chosen date is applied if it
+    /// exists, i.e. `OffsetTimeOriginal`, `OffsetTimeDigitized` and
+    /// `OffsetTime` respectively. Otherwise the time zone used when parsing
+    /// is kept, see [`ExifIter::timezone`].
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// use nom_exif::*;
+    ///
+    /// let mut parser = MediaParser::new();
+    /// let ms = MediaSource::file_path("./testdata/exif.jpg").unwrap();
+    /// let exif: Exif = parser.parse::<_, _, ExifIter>(ms).unwrap().into();
+    /// assert_eq!(
+    ///     exif.best_datetime().unwrap().to_rfc3339(),
+    ///     "2023-07-09T20:36:33+08:00"
+    /// );
+    /// ```
+    pub fn best_datetime(&self) -> Option<DateTime<FixedOffset>> {
+        [
+            (ExifTag::DateTimeOriginal, ExifTag::OffsetTimeOriginal),
+            (ExifTag::CreateDate, ExifTag::OffsetTimeDigitized),
+            (ExifTag::ModifyDate, ExifTag::OffsetTime),
+        ]
+        .into_iter()
+        .find_map(|(tag, offset_tag)| {
+            let t = self.get(tag)?.as_time()?;
+            let offset = self
+                .get(offset_tag)
+                .and_then(EntryValue::as_str)
+                .and_then(|x| x.parse::<FixedOffset>().ok());
+            match offset {
+                Some(offset) => offset.from_local_datetime(&t.naive_local()).single(),
+                None => Some(t),
+            }
+        })
+    }
+
+    /// Decodes [`ExifTag::ComponentsConfiguration`] into the channel order,
+    /// e.g. "Y,Cb,Cr,-" for the usual YCbCr images, where "-" means the
+    /// component doesn't exist. Unknown components are kept as numbers.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// use nom_exif::*;
+    ///
+    /// let mut parser = MediaParser::new();
+    /// let ms = MediaSource::file_path("./testdata/exif.jpg").unwrap();
+    /// let exif: Exif = parser.parse::<_, _, ExifIter>(ms).unwrap().into();
+    /// assert_eq!(exif.components_configuration().as_deref(), Some("Y,Cb,Cr,-"));
+    /// ```
+    pub fn components_configuration(&self) -> Option<String> {
+        let components = match self.get(ExifTag::ComponentsConfiguration)? {
+            EntryValue::Undefined(v) | EntryValue::U8Array(v) => v,
+            _ => return None,
+        };
+        let s = components
+            .iter()
+            .map(|x| match x {
+                0 => "-".to_string(),
+                1 => "Y".to_string(),
+                2 => "Cb".to_string(),
+                3 => "Cr".to_string(),
+                4 => "R".to_string(),
+                5 => "G".to_string(),
+                6 => "B".to_string(),
+                x => x.to_string(),
+            })
+            .collect::<Vec<_>>()
+            .join(",");
+        Some(s)
+    }
+
+    /// Get the commonly used capture parameters, e.g. ISO, shutter speed and
+    /// aperture, in one go.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// use nom_exif::*;
+    ///
+    /// fn main() -> Result<()> {
+    ///     let mut parser = MediaParser::new();
+    ///
+    ///     let ms = MediaSource::file_path("./testdata/exif.jpg")?;
+    ///     let iter: ExifIter = parser.parse(ms)?;
+    ///     let exif: Exif = iter.into();
+    ///
+    ///     let settings = exif.camera_settings();
+    ///     assert_eq!(settings.model.as_deref(), Some("vivo X90 Pro+"));
+    ///     assert_eq!(settings.iso, Some(454));
+    ///     assert_eq!(settings.f_number, Some(1.75));
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn camera_settings(&self) -> CameraSettings {
+        CameraSettings::new(self)
+    }
+
+    /// Compares with `other` and returns the tags which are only present in
+    /// one of them, or whose values differ, sorted by IFD index and tag code.
+    ///
+    /// Numeric values are compared by value rather than by type, e.g.
+    /// `U16(3)` equals `U32(3)`, and `URational(1/2)` equals
+    /// `URational(2/4)`.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// use nom_exif::*;
+    ///
+    /// fn main() -> Result<()> {
+    ///     let mut parser = MediaParser::new();
+    ///
+    ///     let ms = MediaSource::file_path("./testdata/exif.jpg")?;
+    ///     let iter: ExifIter = parser.parse(ms)?;
+    ///     let exif: Exif = iter.into();
+    ///
+    ///     let ms = MediaSource::file_path("./testdata/exif.heic")?;
+    ///     let iter: ExifIter = parser.parse(ms)?;
+    ///     let other: Exif = iter.into();
+    ///
+    ///     assert!(exif.diff(&exif).is_empty());
+    ///
+    ///     let diffs = exif.diff(&other);
+    ///     let make = diffs.iter().find(|x| x.ifd == 0 && x.tag == 0x010f).unwrap();
+    ///     assert_eq!(make.old, Some("vivo".into()));
+    ///     assert_eq!(make.new, Some("Apple".into()));
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn diff(&self, other: &Exif) -> Vec<TagDiff> {
+        let mut diffs = Vec::new();
+        for ifd in 0..self.ifds.len().max(other.ifds.len()) {
+            let mut tags = [self, other]
+                .iter()
+                .filter_map(|x| x.ifds.get(ifd))
+                .flat_map(|x| x.entries.keys().copied())
+                .collect::<Vec<_>>();
+            tags.sort_unstable();
+            tags.dedup();
+
+            for tag in tags {
+                let old = self.get_by_ifd_tag_code(ifd, tag);
+                let new = other.get_by_ifd_tag_code(ifd, tag);
+                let changed = match (old, new) {
+                    (Some(a), Some(b)) => !a.numeric_eq(b),
+                    _ => true,
+                };
+                if changed {
+                    diffs.push(TagDiff {
+                        ifd,
+                        tag,
+                        old: old.cloned(),
+                        new: new.cloned(),
+                    });
+                }
+            }
+        }
+        diffs
+    }
+
     /// Get parsed GPS information.
+    #[deprecated(note = "use `Exif::gps` instead")]
     pub fn get_gps_info(&self) -> crate::Result<Option<GPSInfo>> {
         Ok(self.gps_info.clone())
     }
 
-    fn put(&mut self, res: &mut ParsedExifEntry) {
+    /// Get the GPS information, which has been parsed when the `Exif` was
+    /// created.
+    ///
+    /// ```rust
+    /// use nom_exif::*;
+    ///
+    /// let mut parser = MediaParser::new();
+    /// let ms = MediaSource::file_path("./testdata/exif.jpg").unwrap();
+    /// let exif: Exif = parser.parse::<_, _, ExifIter>(ms).unwrap().into();
+    /// let gps = exif.gps().unwrap();
+    /// assert_eq!(gps.latitude_ref, 'N');
+    /// assert_eq!(gps.format_iso6709(), "+22.53113+114.02148/");
+    /// ```
+    pub fn gps(&self) -> Option<&GPSInfo> {
+        self.gps_info.as_ref()
+    }
+
+    /// Get the UTC datetime of the GPS fix, which combines `GPSDateStamp` &
+    /// `GPSTimeStamp`, see [`GPSInfo::gps_datetime`]. Returns `None` if
+    /// either of them is absent or invalid.
+    ///
+    /// ```rust
+    /// use nom_exif::*;
+    ///
+    /// let mut parser = MediaParser::new();
+    /// let ms = MediaSource::file_path("./testdata/exif.jpg").unwrap();
+    /// let exif: Exif = parser.parse::<_, _, ExifIter>(ms).unwrap().into();
+    /// assert_eq!(
+    ///     exif.get_gps_datetime().unwrap().to_rfc3339(),
+    ///     "2023-07-09T12:36:33+00:00"
+    /// );
+    /// ```
+    pub fn get_gps_datetime(&self) -> Option<DateTime<Utc>> {
+        self.gps_info.as_ref()?.gps_datetime()
+    }
+
+    /// Converts an [`ExifIter`] into an `Exif`, using `strategy` to decide
+    /// which value is kept when a tag appears more than once in the same IFD.
+    ///
+    /// `Exif::from(iter)` is equivalent to
+    /// `Exif::from_iter_with(iter, iter.duplicate_strategy())`, which is
+    /// `DuplicateStrategy::LastWins` except for CR3 files, see
+    /// [`ExifIter::duplicate_strategy`].
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// use nom_exif::*;
+    ///
+    /// fn main() -> Result<()> {
+    ///     let mut parser = MediaParser::new();
+    ///
+    ///     let ms = MediaSource::file_path("./testdata/exif.jpg")?;
+    ///     let iter: ExifIter = parser.parse(ms)?;
+    ///     let exif = Exif::from_iter_with(iter, DuplicateStrategy::FirstWins);
+    ///
+    ///     assert_eq!(exif.get(ExifTag::Model).unwrap().as_str().unwrap(), "vivo X90 Pro+");
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn from_iter_with(iter: ExifIter, strategy: DuplicateStrategy) -> Exif {
+        let gps_info = iter.parse_gps_info().ok().flatten();
+        let mut exif = Exif::new(gps_info, iter.container_orientation());
+        exif.container_dimensions = iter.container_dimensions();
+
+        for mut it in iter {
+            exif.put(&mut it, strategy);
+        }
+
+        exif
+    }
+
+    /// Iterates `(ifd index, tag code, value)` of all entries, in no
+    /// particular order.
+    #[cfg(test)]
+    pub(crate) fn entries(&self) -> impl Iterator<Item = (usize, u16, &EntryValue)> {
+        self.ifds.iter().enumerate().flat_map(|(idx, ifd)| {
+            ifd.entries
+                .iter()
+                .map(move |(tag, entry)| (idx, *tag, &entry.value))
+        })
+    }
+
+    fn put(&mut self, res: &mut ParsedExifEntry, strategy: DuplicateStrategy) {
+        // Entries of other images, e.g. the full-size image of a NEF file,
+        // would override the ones of IFD0. They are available from `ExifIter`.
+        if res.ifd_kind() == IfdKind::SubIfd {
+            return;
+        }
         while self.ifds.len() < res.ifd_index() + 1 {
             self.ifds.push(ParsedImageFileDirectory::new());
         }
+        let ifd = &mut self.ifds[res.ifd_index()];
+        if strategy == DuplicateStrategy::FirstWins && ifd.get(res.tag_code()).is_some() {
+            tracing::debug!(tag = res.tag_code(), "ignore duplicate tag");
+            return;
+        }
         if let Some(v) = res.take_value() {
-            self.ifds[res.ifd_index()].put(res.tag_code(), v);
+            ifd.put(res.tag_code(), v);
         }
     }
 }
 
+/// Decides which value is kept when a tag appears more than once in the same
+/// IFD, see [`Exif::from_iter_with`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DuplicateStrategy {
+    /// Keep the first value.
+    FirstWins,
+
+    /// Keep the last value, this is the default behavior.
+    #[default]
+    LastWins,
+}
+
+/// A tag which differs between two [`Exif`]s, see [`Exif::diff`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct TagDiff {
+    /// IFD index, 0 for the main image and 1 for the thumbnail
+    pub ifd: usize,
+    /// Raw tag code, see [`ExifTag`]
+    pub tag: u16,
+    /// Value in the original `Exif`, `None` if the tag is absent
+    pub old: Option<EntryValue>,
+    /// Value in the compared `Exif`, `None` if the tag is absent
+    pub new: Option<EntryValue>,
+}
+
+fn entry_as_u32(v: &EntryValue) -> Option<u32> {
+    v.as_u32().or_else(|| v.as_u16().map(u32::from))
+}
+
+pub(super) fn entry_as_u32_vec(v: &EntryValue) -> Option<Vec<u32>> {
+    match v {
+        EntryValue::U32Array(v) => Some(v.clone()),
+        EntryValue::U16Array(v) => Some(v.iter().map(|x| *x as u32).collect()),
+        v => entry_as_u32(v).map(|x| vec![x]),
+    }
+}
+
 impl From<ExifIter> for Exif {
     fn from(iter: ExifIter) -> Self {
-        let gps_info = iter.parse_gps_info().ok().flatten();
-        let mut exif = Exif::new(gps_info);
+        let strategy = iter.duplicate_strategy();
+        Exif::from_iter_with(iter, strategy)
+    }
+}
 
-        for mut it in iter {
-            exif.put(&mut it);
-        }
+/// Consumes the `Exif` and yields owned `(ifd index, tag code, value)`
+/// tuples, ordered by IFD index and then by tag code.
+///
+/// The parsed `GPSInfo` is not included, its source entries are yielded
+/// from the GPS IFD like any others.
+///
+/// ```rust
+/// use nom_exif::*;
+///
+/// fn main() -> Result<()> {
+///     let mut parser = MediaParser::new();
+///     let ms = MediaSource::file_path("./testdata/exif.jpg")?;
+///     let iter: ExifIter = parser.parse(ms)?;
+///     let exif: Exif = iter.into();
+///
+///     let (_, _, value) = exif
+///         .into_iter()
+///         .find(|(ifd, tag, _)| *ifd == 0 && *tag == ExifTag::Make.code())
+///         .unwrap();
+///     assert_eq!(value.as_str(), Some("vivo"));
+///     Ok(())
+/// }
+/// ```
+impl IntoIterator for Exif {
+    type Item = (usize, u16, EntryValue);
+    type IntoIter = std::vec::IntoIter<Self::Item>;
 
-        exif
+    fn into_iter(self) -> Self::IntoIter {
+        let mut entries = self
+            .ifds
+            .into_iter()
+            .enumerate()
+            .flat_map(|(idx, ifd)| {
+                ifd.entries
+                    .into_iter()
+                    .map(move |(tag, entry)| (idx, tag, entry.value))
+            })
+            .collect::<Vec<_>>();
+        entries.sort_by_key(|(idx, tag, _)| (*idx, *tag));
+        entries.into_iter()
     }
 }
 
@@ -174,8 +852,23 @@ pub(crate) const IFD_ENTRY_SIZE: usize = 12;
 
 impl TiffHeader {
     pub fn parse(input: &[u8]) -> IResult<&[u8], TiffHeader> {
+        Self::parse_impl(input, false)
+    }
+
+    /// Parses the header with the byte order opposite to its marker, for
+    /// headers whose byte order marker is mis-tagged.
+    pub fn parse_swapped(input: &[u8]) -> IResult<&[u8], TiffHeader> {
+        Self::parse_impl(input, true)
+    }
+
+    fn parse_impl(input: &[u8], swap: bool) -> IResult<&[u8], TiffHeader> {
         use nom::number::streaming::{u16, u32};
         let (remain, endian) = TiffHeader::parse_endian(input)?;
+        let endian = match endian {
+            Endianness::Big if swap => Endianness::Little,
+            Endianness::Little if swap => Endianness::Big,
+            x => x,
+        };
         let (_, (_, offset)) = sequence::tuple((
             combinator::verify(u16(endian), |magic| *magic == 0x2a),
             u32(endian),
@@ -233,12 +926,56 @@ pub(crate) fn check_exif_header(data: &[u8]) -> Result<bool, nom::Err<nom::error
     tag::<_, _, nom::error::Error<_>>(EXIF_IDENT)(data).map(|_| true)
 }
 
-pub(crate) fn check_exif_header2(i: &[u8]) -> IResult<&[u8], ()> {
-    let (remain, _) = nom::sequence::tuple((
-        nom::number::complete::be_u32,
-        nom::bytes::complete::tag(EXIF_IDENT),
-    ))(i)?;
-    Ok((remain, ()))
+/// Returns true if `data` starts with the "Exif\0\0" marker, i.e. the
+/// header of the Exif data in a JPEG `APP1` segment.
+///
+/// Whether the Exif data is prefixed with this header depends on the format:
+///
+/// - JPEG `APP1` segments always have it.
+/// - HEIF/HEIC/AVIF `Exif` items (and JPEG XL `Exif` boxes) start with a
+///   4-byte offset pointing to the TIFF header, the skipped bytes are usually
+///   this header.
+/// - PNG `eXIf` chunks, WebP `EXIF` chunks and TIFF files start with the
+///   TIFF header directly, though some writers add the header to PNG/WebP
+///   chunks anyway.
+///
+/// The parser handles all of these, this function is for callers dealing
+/// with raw Exif payloads themselves.
+///
+/// ```rust
+/// use nom_exif::*;
+///
+/// assert!(is_exif_header(b"Exif\0\0MM\0*\0\0\0\x08"));
+/// assert!(!is_exif_header(b"MM\0*\0\0\0\x08"));
+/// ```
+pub fn is_exif_header(data: &[u8]) -> bool {
+    check_exif_header(data).unwrap_or(false)
+}
+
+/// Returns true if `data` starts with a valid TIFF header, i.e. a byte order
+/// mark ("II" or "MM"), the magic number 42 and the offset of IFD0.
+///
+/// ```rust
+/// use nom_exif::*;
+///
+/// assert!(is_tiff_header(b"MM\0*\0\0\0\x08"));
+/// assert!(is_tiff_header(b"II*\0\x08\0\0\0"));
+/// assert!(!is_tiff_header(b"Exif\0\0MM\0*\0\0\0\x08"));
+/// ```
+pub fn is_tiff_header(data: &[u8]) -> bool {
+    TiffHeader::parse(data).is_ok()
+}
+
+/// Returns the TIFF data of a HEIF `Exif` item, which starts with a 4-byte
+/// `exif_tiff_header_offset`, i.e. the number of bytes to skip to reach the
+/// TIFF header (usually the length of the "Exif\0\0" header, or 0).
+///
+/// Some writers keep the "Exif\0\0" header but set the offset to 0, so the
+/// header is stripped as well if it's still there.
+pub(crate) fn heif_exif_tiff_data(i: &[u8]) -> Option<&[u8]> {
+    let offset = u32::from_be_bytes(i.get(..4)?.try_into().ok()?) as usize;
+    let data = i.get(4..)?.get(offset..)?;
+    Some(data.strip_prefix(EXIF_IDENT.as_bytes()).unwrap_or(data))
 }
 
 pub(crate) const EXIF_IDENT: &str = "Exif\0\0";
@@ -254,7 +991,7 @@ mod tests {
     use crate::exif::input_into_iter;
     use crate::jpeg::extract_exif_data;
     use crate::slice::SubsliceRange;
-    use crate::testkit::{open_sample, read_sample};
+    use crate::testkit::{open_sample, read_sample, TiffBuilder};
     use crate::ParsedExifEntry;
 
     use super::*;
@@ -275,6 +1012,17 @@ mod tests {
         );
     }
 
+    #[test_case(b"\0\0\0\x06Exif\0\0MM", Some(b"MM"); "offset to TIFF")]
+    #[test_case(b"\0\0\0\0MM", Some(b"MM"); "no Exif header")]
+    #[test_case(b"\0\0\0\0Exif\0\0MM", Some(b"MM"); "zero offset with Exif header")]
+    #[test_case(b"\0\0\0\x08MM", None; "offset out of range")]
+    #[test_case(b"\0\0", None; "truncated")]
+    fn heif_exif_header(input: &[u8], expect: Option<&[u8]>) {
+        let _ = tracing_subscriber::fmt().with_test_writer().try_init();
+
+        assert_eq!(heif_exif_tiff_data(input), expect);
+    }
+
     #[test_case("exif.jpg")]
     fn exif_iter_gps(path: &str) {
         let buf = read_sample(path).unwrap();
@@ -283,7 +1031,7 @@ mod tests {
             .and_then(|x| buf.subslice_in_range(x))
             .map(|x| PartialVec::from_vec_range(buf, x))
             .unwrap();
-        let iter = input_into_iter(data, None).unwrap();
+        let iter = input_into_iter(data, None, false).unwrap();
         let gps = iter.parse_gps_info().unwrap().unwrap();
         assert_eq!(gps.format_iso6709(), "+22.53113+114.02148/");
     }
@@ -296,7 +1044,7 @@ mod tests {
             .and_then(|x| buf.subslice_in_range(x))
             .map(|x| PartialVec::from_vec_range(buf, x))
             .unwrap();
-        let iter = input_into_iter(data, None).unwrap();
+        let iter = input_into_iter(data, None, false).unwrap();
         let iter2 = iter.clone();
 
         let mut expect = String::new();
@@ -318,6 +1066,334 @@ mod tests {
         assert_eq!(jh.join().unwrap().trim(), expect.trim());
     }
 
+    #[test_case("exif.jpg", Some((3072, 4096)))]
+    #[test_case("exif.heic", Some((3024, 4032)))]
+    #[test_case("exif-one-entry.heic", None)]
+    fn display_dimensions(path: &str, expect: Option<(u32, u32)>) {
+        let _ = tracing_subscriber::fmt().with_test_writer().try_init();
+
+        let mut parser = crate::MediaParser::new();
+        let ms = crate::MediaSource::file(open_sample(path).unwrap()).unwrap();
+        let iter: ExifIter = parser.parse(ms).unwrap();
+        let exif: Exif = iter.into();
+        assert_eq!(exif.display_dimensions(), expect);
+    }
+
+    #[test_case("tif.tif", 40, Some(301851..309596))]
+    #[test_case("exif.jpg", 0, None)]
+    fn strip_ranges(path: &str, num: usize, last: Option<Range<usize>>) {
+        let _ = tracing_subscriber::fmt().with_test_writer().try_init();
+
+        let mut parser = crate::MediaParser::new();
+        let ms = crate::MediaSource::file(open_sample(path).unwrap()).unwrap();
+        let iter: ExifIter = parser.parse(ms).unwrap();
+        let exif: Exif = iter.into();
+        let ranges = exif.strip_ranges();
+        assert_eq!(ranges.len(), num);
+        assert_eq!(ranges.last().cloned(), last);
+    }
+
+    #[test]
+    fn effective_orientation() {
+        let _ = tracing_subscriber::fmt().with_test_writer().try_init();
+
+        let mut parser = crate::MediaParser::new();
+        let ms = crate::MediaSource::file(open_sample("exif.heic").unwrap()).unwrap();
+        let iter: ExifIter = parser.parse(ms).unwrap();
+        assert_eq!(iter.container_orientation(), Some(6));
+
+        let ms = crate::MediaSource::file(open_sample("exif.jpg").unwrap()).unwrap();
+        let iter: ExifIter = parser.parse(ms).unwrap();
+        assert_eq!(iter.container_orientation(), None);
+        let mut exif: Exif = iter.into();
+        exif.ifds[0].put(ExifTag::Orientation.code(), EntryValue::U16(6));
+        assert_eq!(exif.effective_orientation(), Some(6));
+        assert_eq!(exif.display_dimensions(), Some((4096, 3072)));
+
+        // The container transform wins
+        exif.container_orientation = Some(1);
+        assert_eq!(exif.effective_orientation(), Some(1));
+        assert_eq!(exif.display_dimensions(), Some((3072, 4096)));
+
+        exif.ifds[0].entries.remove(&ExifTag::Orientation.code());
+        exif.container_orientation = None;
+        assert_eq!(exif.effective_orientation(), None);
+
+        // Container dimensions are used only if the Exif data has none
+        exif.container_dimensions = Some((640, 480));
+        assert_eq!(exif.display_dimensions(), Some((3072, 4096)));
+        exif.ifds[0].entries.remove(&ExifTag::ExifImageWidth.code());
+        exif.ifds[0].entries.remove(&ExifTag::ImageWidth.code());
+        assert_eq!(exif.display_dimensions(), Some((640, 480)));
+    }
+
+    #[test]
+    fn components_configuration() {
+        let _ = tracing_subscriber::fmt().with_test_writer().try_init();
+
+        let mut exif = Exif::new(None, None);
+        assert_eq!(exif.components_configuration(), None);
+
+        exif.ifds.push(ParsedImageFileDirectory::new());
+        let code = ExifTag::ComponentsConfiguration.code();
+        exif.ifds[0].put(code, EntryValue::Undefined(vec![4, 5, 6, 0]));
+        assert_eq!(exif.components_configuration().as_deref(), Some("R,G,B,-"));
+
+        exif.ifds[0].put(code, EntryValue::U8Array(vec![1, 2, 3, 9]));
+        assert_eq!(
+            exif.components_configuration().as_deref(),
+            Some("Y,Cb,Cr,9")
+        );
+
+        exif.ifds[0].put(code, EntryValue::U32(1));
+        assert_eq!(exif.components_configuration(), None);
+    }
+
+    #[test]
+    fn exif_into_iter() {
+        let _ = tracing_subscriber::fmt().with_test_writer().try_init();
+
+        let buf = read_sample("exif.jpg").unwrap();
+        let (_, data) = extract_exif_data(&buf).unwrap();
+        let data = data
+            .and_then(|x| buf.subslice_in_range(x))
+            .map(|x| PartialVec::from_vec_range(buf, x))
+            .unwrap();
+        let exif: Exif = input_into_iter(data, None, false).unwrap().into();
+
+        let borrowed = exif
+            .entries()
+            .map(|(ifd, tag, v)| (ifd, tag, v.clone()))
+            .collect::<Vec<_>>();
+        let owned = exif.into_iter().collect::<Vec<_>>();
+
+        assert_eq!(owned.len(), borrowed.len());
+        assert!(borrowed.iter().all(|x| owned.contains(x)));
+        assert!(owned
+            .windows(2)
+            .all(|x| (x[0].0, x[0].1) < (x[1].0, x[1].1)));
+    }
+
+    #[test]
+    fn raw_tag_codes() {
+        let _ = tracing_subscriber::fmt().with_test_writer().try_init();
+
+        let mut parser = crate::MediaParser::new();
+        let ms = crate::MediaSource::file(open_sample("exif.heic").unwrap()).unwrap();
+        let exif: Exif = parser.parse::<_, _, ExifIter>(ms).unwrap().into();
+
+        let codes = exif.raw_tag_codes();
+        // CompositeImage (0xa460) isn't recognized as an `ExifTag`
+        assert!(ExifTag::try_from(0xa460).is_err());
+        assert!(codes.contains(&(0, 0xa460)));
+        assert!(exif.get_by_ifd_tag_code(0, 0xa460).is_some());
+        assert!(codes.windows(2).all(|x| x[0] < x[1]));
+        assert_eq!(
+            codes,
+            exif.into_iter()
+                .map(|(ifd, tag, _)| (ifd, tag))
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn approx_heap_bytes() {
+        let _ = tracing_subscriber::fmt().with_test_writer().try_init();
+
+        let mut parser = crate::MediaParser::new();
+        let ms = crate::MediaSource::file(open_sample("exif.jpg").unwrap()).unwrap();
+        let mut exif: Exif = parser.parse::<_, _, ExifIter>(ms).unwrap().into();
+
+        let size = exif.approx_heap_bytes();
+        let values: usize = exif
+            .ifds
+            .iter()
+            .flat_map(|x| x.entries.values())
+            .map(|x| x.value.heap_bytes())
+            .sum();
+        assert!(values > 0);
+        assert!(size > values + std::mem::size_of::<Exif>());
+
+        // Replacing a value with a longer string grows the estimate by its
+        // length at least
+        let make = exif.get(ExifTag::Make).unwrap().heap_bytes();
+        exif.ifds[0].put(ExifTag::Make.code(), "x".repeat(1000).into());
+        assert!(exif.approx_heap_bytes() >= size - make + 1000);
+
+        assert_eq!(
+            EntryValue::U16Array(Vec::with_capacity(10)).heap_bytes(),
+            20
+        );
+        assert_eq!(EntryValue::U32(1).heap_bytes(), 0);
+    }
+
+    #[test]
+    fn typed_array_getters() {
+        let _ = tracing_subscriber::fmt().with_test_writer().try_init();
+
+        let mut parser = crate::MediaParser::new();
+        let ms = crate::MediaSource::file(open_sample("exif.jpg").unwrap()).unwrap();
+        let mut exif: Exif = parser.parse::<_, _, ExifIter>(ms).unwrap().into();
+
+        // A scalar is returned as a one-element slice
+        assert_eq!(
+            exif.get_u16_array(ExifTag::ISOSpeedRatings),
+            Some(&[454][..])
+        );
+        assert_eq!(exif.get_u32_array(ExifTag::ISOSpeedRatings), None);
+        assert_eq!(
+            exif.get_urational_array(ExifTag::FNumber),
+            Some(&[(175, 100).into()][..])
+        );
+
+        exif.ifds[0].put(
+            ExifTag::BitsPerSample.code(),
+            EntryValue::U16Array(vec![8, 8]),
+        );
+        exif.ifds[0].put(
+            ExifTag::ReferenceBlackWhite.code(),
+            EntryValue::URationalArray(vec![(0, 1).into(), (255, 1).into()]),
+        );
+        exif.ifds[0].put(
+            ExifTag::GPSVersionID.code(),
+            EntryValue::U8Array(vec![2, 3]),
+        );
+        assert_eq!(
+            exif.get_u16_array(ExifTag::BitsPerSample),
+            Some(&[8, 8][..])
+        );
+        assert_eq!(
+            exif.get_urational_array(ExifTag::ReferenceBlackWhite),
+            Some(&[(0, 1).into(), (255, 1).into()][..])
+        );
+        assert_eq!(exif.get_u8_array(ExifTag::GPSVersionID), Some(&[2, 3][..]));
+        assert_eq!(exif.get_irational_array(ExifTag::ReferenceBlackWhite), None);
+        assert_eq!(exif.get_i16_array(ExifTag::Make), None);
+    }
+
+    #[test]
+    fn best_datetime() {
+        let _ = tracing_subscriber::fmt().with_test_writer().try_init();
+
+        let mut parser = crate::MediaParser::new();
+        let ms = crate::MediaSource::file(open_sample("exif.jpg").unwrap()).unwrap();
+        let mut exif: Exif = parser.parse::<_, _, ExifIter>(ms).unwrap().into();
+        let t = |s| Some(DateTime::parse_from_rfc3339(s).unwrap());
+
+        exif.ifds[0].put(
+            ExifTag::DateTimeOriginal.code(),
+            EntryValue::Time(t("2023-07-09T20:36:30+08:00").unwrap()),
+        );
+        assert_eq!(exif.best_datetime(), t("2023-07-09T20:36:30+08:00"));
+
+        // Falls back to `CreateDate`, then `ModifyDate`, with their own offsets
+        exif.ifds[0]
+            .entries
+            .remove(&ExifTag::DateTimeOriginal.code());
+        exif.ifds[0].put(
+            ExifTag::CreateDate.code(),
+            EntryValue::Time(t("2023-07-09T20:36:31+08:00").unwrap()),
+        );
+        exif.ifds[0].put(ExifTag::OffsetTimeDigitized.code(), "+02:00".into());
+        assert_eq!(exif.best_datetime(), t("2023-07-09T20:36:31+02:00"));
+
+        exif.ifds[0].entries.remove(&ExifTag::CreateDate.code());
+        exif.ifds[0].entries.remove(&ExifTag::OffsetTime.code());
+        assert_eq!(exif.best_datetime(), t("2023-07-09T20:36:33+08:00"));
+
+        exif.ifds[0].entries.remove(&ExifTag::ModifyDate.code());
+        assert_eq!(exif.best_datetime(), None);
+    }
+
+    #[test]
+    fn diff() {
+        let _ = tracing_subscriber::fmt().with_test_writer().try_init();
+
+        let mut parser = crate::MediaParser::new();
+        let ms = crate::MediaSource::file(open_sample("exif.jpg").unwrap()).unwrap();
+        let iter: ExifIter = parser.parse(ms).unwrap();
+        let exif: Exif = iter.into();
+        assert_eq!(exif.diff(&exif), vec![]);
+
+        let mut other = exif.clone();
+        other.ifds[0].entries.remove(&ExifTag::Make.code());
+        other.ifds[0].put(ExifTag::Model.code(), "edited".into());
+        other.ifds[0].put(ExifTag::ISOSpeedRatings.code(), EntryValue::U32(454));
+        other.ifds[1].put(0xfffe, EntryValue::U8(1));
+        assert_eq!(
+            exif.diff(&other),
+            vec![
+                TagDiff {
+                    ifd: 0,
+                    tag: ExifTag::Make.code(),
+                    old: Some("vivo".into()),
+                    new: None,
+                },
+                TagDiff {
+                    ifd: 0,
+                    tag: ExifTag::Model.code(),
+                    old: Some("vivo X90 Pro+".into()),
+                    new: Some("edited".into()),
+                },
+                TagDiff {
+                    ifd: 1,
+                    tag: 0xfffe,
+                    old: None,
+                    new: Some(EntryValue::U8(1)),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn camera_settings() {
+        let _ = tracing_subscriber::fmt().with_test_writer().try_init();
+
+        let mut parser = crate::MediaParser::new();
+        let ms = crate::MediaSource::file(open_sample("exif.jpg").unwrap()).unwrap();
+        let iter: ExifIter = parser.parse(ms).unwrap();
+        let exif: Exif = iter.into();
+        assert_eq!(
+            exif.camera_settings(),
+            CameraSettings {
+                make: Some("vivo".to_string()),
+                model: Some("vivo X90 Pro+".to_string()),
+                lens: None,
+                iso: Some(454),
+                exposure_time: Some((9997, 1000000).into()),
+                f_number: Some(1.75),
+                focal_length: Some(8.67),
+                focal_length_35mm: Some(23),
+                flash: Some(16),
+                exposure_bias: Some(0.0),
+                metering_mode: Some(1),
+                date_time: Some(
+                    chrono::DateTime::parse_from_rfc3339("2023-07-09T20:36:33+08:00").unwrap()
+                ),
+            }
+        );
+        assert_eq!(exif.camera_settings().flash_fired(), Some(false));
+
+        let ms = crate::MediaSource::file(open_sample("exif-one-entry.heic").unwrap()).unwrap();
+        let iter: ExifIter = parser.parse(ms).unwrap();
+        let exif: Exif = iter.into();
+        assert_eq!(exif.camera_settings().iso, None);
+    }
+
+    #[test_case(DuplicateStrategy::FirstWins, 100)]
+    #[test_case(DuplicateStrategy::LastWins, 200)]
+    fn duplicate_strategy(strategy: DuplicateStrategy, expect: u16) {
+        let _ = tracing_subscriber::fmt().with_test_writer().try_init();
+
+        // IFD0 with two ImageWidth entries
+        let mut tiff = TiffBuilder::new(Endianness::Little);
+        tiff.short(0, 0x0100, &[100]).short(0, 0x0100, &[200]);
+
+        let iter = input_into_iter(tiff.build(), None, false).unwrap();
+        let exif = Exif::from_iter_with(iter, strategy);
+        assert_eq!(exif.get(ExifTag::ImageWidth), Some(&expect.into()));
+    }
+
     fn iter_to_str(it: impl Iterator<Item = ParsedExifEntry>) -> String {
         let ss = it
             .map(|x| {
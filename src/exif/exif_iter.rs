@@ -1,5 +1,11 @@
-use std::{fmt::Debug, sync::Arc};
+use std::{
+    collections::HashMap,
+    fmt::Debug,
+    ops::Range,
+    sync::{Arc, OnceLock},
+};
 
+use chrono::{DateTime, Utc};
 use nom::{
     number::{complete, Endianness},
     sequence::tuple,
@@ -9,11 +15,19 @@ use thiserror::Error;
 use crate::{
     partial_vec::{AssociatedInput, PartialVec},
     slice::SliceChecked,
-    values::{DataFormat, EntryData, IRational, ParseEntryError, URational},
+    values::{
+        get_cstr_ref, is_date_time_tag, DataFormat, EntryData, IRational, ParseEntryError,
+        URational,
+    },
     EntryValue, ExifTag,
 };
 
-use super::{exif_exif::IFD_ENTRY_SIZE, tags::ExifTagCode, GPSInfo, TiffHeader};
+use super::{
+    exif_exif::{thumbnail_range, IFD_ENTRY_SIZE},
+    makernote::{self, AppleMakerNote, CanonMakerNote, LensResolver},
+    tags::{ExifTagCode, FileSource, SceneType, SensingMethod},
+    DuplicateStrategy, GPSInfo, TiffHeader,
+};
 
 /// Parses header from input data, and returns an [`ExifIter`].
 ///
@@ -27,20 +41,34 @@ use super::{exif_exif::IFD_ENTRY_SIZE, tags::ExifTagCode, GPSInfo, TiffHeader};
 pub(crate) fn input_into_iter(
     input: impl Into<PartialVec> + Debug,
     state: Option<TiffHeader>,
+    swap_endian_on_mismatch: bool,
 ) -> crate::Result<ExifIter> {
     let input = input.into();
-    let (header, start) = match state {
+    let (mut header, start) = match state {
         // header has been parsed, and header has been skipped, input data
         // is the IFD data
         Some(header) => (header, 0),
         _ => {
             // header has not been parsed, input data includes IFD header
-            let (_, header) = TiffHeader::parse(&input[..])?;
+            let header = match parse_tiff_header(&input[..]) {
+                Ok(header) => header,
+                // The magic number & IFD0 offset are decoded with the wrong
+                // byte order as well, if the header is mis-tagged
+                Err(e) if swap_endian_on_mismatch => {
+                    let Some(header) = parse_tiff_header_swapped(&input[..]) else {
+                        return Err(e);
+                    };
+                    tracing::warn!(
+                        endian = ?header.endian,
+                        "TIFF header looks invalid under the declared byte order, \
+                        but valid under the opposite one; the TIFF header may be \
+                        mis-tagged"
+                    );
+                    header
+                }
+                Err(e) => return Err(e),
+            };
             let start = header.ifd0_offset as usize;
-            if start > input.len() {
-                return Err(crate::Error::ParseFailed("no enough bytes".into()));
-            }
-
             (header, start)
         }
     };
@@ -49,6 +77,18 @@ pub(crate) fn input_into_iter(
 
     let data = &input[..];
 
+    if is_endian_mismatched(&data[start..], header.endian) {
+        tracing::warn!(
+            endian = ?header.endian,
+            swap_endian_on_mismatch,
+            "IFD0 entry num looks invalid under the declared byte order, but \
+            valid under the opposite one; the TIFF header may be mis-tagged"
+        );
+        if swap_endian_on_mismatch {
+            header.endian = swap_endian(header.endian);
+        }
+    }
+
     let mut ifd0 = IfdIter::try_new(
         0,
         input.partial(&data[start..]),
@@ -66,6 +106,44 @@ pub(crate) fn input_into_iter(
     Ok(iter)
 }
 
+fn parse_tiff_header(input: &[u8]) -> crate::Result<TiffHeader> {
+    let (_, header) = TiffHeader::parse(input)?;
+    if header.ifd0_offset as usize > input.len() {
+        return Err(crate::Error::ParseFailed("no enough bytes".into()));
+    }
+    Ok(header)
+}
+
+/// Parses a TIFF header with the byte order opposite to its marker, returns
+/// `None` if it's still invalid.
+fn parse_tiff_header_swapped(input: &[u8]) -> Option<TiffHeader> {
+    let (_, header) = TiffHeader::parse_swapped(input).ok()?;
+    let ifd = input.get(header.ifd0_offset as usize..)?;
+    let (_, num) = complete::u16::<_, nom::error::Error<_>>(header.endian)(ifd).ok()?;
+    (2 + num as usize * IFD_ENTRY_SIZE <= ifd.len()).then_some(header)
+}
+
+// Real world IFDs rarely have more than a few hundred entries.
+const MAX_SANE_IFD_ENTRY_NUM: u16 = 1000;
+
+/// Returns true if the IFD entry num in `ifd` is absurd when decoded with
+/// `endian`, but sane when decoded with the opposite byte order.
+fn is_endian_mismatched(ifd: &[u8], endian: Endianness) -> bool {
+    let Ok((_, num)) = complete::u16::<_, nom::error::Error<_>>(endian)(ifd) else {
+        return false;
+    };
+    let is_sane =
+        |num: u16| num <= MAX_SANE_IFD_ENTRY_NUM && 2 + num as usize * IFD_ENTRY_SIZE <= ifd.len();
+    num != 0 && !is_sane(num) && is_sane(num.swap_bytes())
+}
+
+fn swap_endian(endian: Endianness) -> Endianness {
+    match endian {
+        Endianness::Big => Endianness::Little,
+        _ => Endianness::Big,
+    }
+}
+
 /// An iterator version of [`Exif`](crate::Exif). Use [`ParsedExifEntry`] as
 /// iterator items.
 ///
@@ -78,15 +156,28 @@ pub(crate) fn input_into_iter(
 /// to clone the `ExifIter` and use the new cloned one to do the converting.
 /// Since the original's iteration index may have been modified by
 /// `Iterator::next()` calls.
+///
+/// Iterating over a borrowed `&ExifIter` iterates over a rewound clone, thus
+/// the original's iteration index won't be affected.
 pub struct ExifIter {
     // Use Arc to make sure we won't clone the owned data.
     input: Arc<PartialVec>,
     tiff_header: TiffHeader,
     tz: Option<String>,
     ifd0: IfdIter,
+    continue_on_error: bool,
+    container_orientation: Option<u16>,
+    container_dimensions: Option<(u32, u32)>,
+    duplicate_strategy: DuplicateStrategy,
+    lens_resolver: Option<LensResolver>,
+
+    // IFDs located in separate TIFF blocks, e.g. the Exif & GPS IFDs of CR3
+    // files, which are iterated as sub-IFDs of IFD0 after the main block.
+    sub_ifds: Vec<IfdIter>,
 
     // Iterating status
     ifds: Vec<IfdIter>,
+    peeked: Option<ParsedExifEntry>,
 }
 
 impl Debug for ExifIter {
@@ -120,8 +211,255 @@ impl ExifIter {
             tiff_header,
             tz,
             ifd0,
+            continue_on_error: false,
+            container_orientation: None,
+            container_dimensions: None,
+            duplicate_strategy: DuplicateStrategy::default(),
+            lens_resolver: None,
+            sub_ifds: Vec::new(),
             ifds,
+            peeked: None,
+        }
+    }
+
+    /// Enable/disable the recovery mode for partially corrupt Exif data,
+    /// which is disabled by default.
+    ///
+    /// By default, once an invalid entry is encountered, the iterator yields
+    /// the error entry and then skips the rest of the current IFD, including
+    /// the IFDs linked after it, e.g. a broken ifd0 will make the thumbnail
+    /// IFD (ifd1) unreachable.
+    ///
+    /// In recovery mode, the iterator skips invalid entries and keeps going,
+    /// so that the remaining entries and the next IFDs can still be parsed.
+    ///
+    /// ```rust
+    /// use nom_exif::*;
+    ///
+    /// let mut parser = MediaParser::new();
+    /// let ms = MediaSource::file_path("./testdata/exif.jpg").unwrap();
+    /// let iter: ExifIter = parser.parse(ms).unwrap();
+    /// let exif: Exif = iter.continue_on_error(true).into();
+    /// assert_eq!(exif.get(ExifTag::Make).unwrap().as_str().unwrap(), "vivo");
+    /// ```
+    pub fn continue_on_error(mut self, enable: bool) -> Self {
+        self.continue_on_error = enable;
+        self
+    }
+
+    /// Returns the time zone offset (e.g. "+08:00") applied when parsing the
+    /// time entries, if any.
+    ///
+    /// The offset is taken from `OffsetTimeOriginal`/`OffsetTimeDigitized`,
+    /// or `OffsetTime` as a fallback. For older files without these tags, the
+    /// legacy `TimeZoneOffset` tag is used.
+    ///
+    /// ```rust
+    /// use nom_exif::*;
+    ///
+    /// let mut parser = MediaParser::new();
+    /// let ms = MediaSource::file_path("./testdata/exif.jpg").unwrap();
+    /// let iter: ExifIter = parser.parse(ms).unwrap();
+    /// assert_eq!(iter.timezone(), Some("+08:00"));
+    /// ```
+    pub fn timezone(&self) -> Option<&str> {
+        self.tz.as_deref()
+    }
+
+    /// Returns the orientation declared by the container rather than the Exif
+    /// data, as an Exif `Orientation` value (1–8), e.g. the `irot`/`imir`
+    /// transforms of the primary item in a HEIF/HEIC file.
+    ///
+    /// Returns `None` if the container doesn't declare any transform. See
+    /// [`Exif::effective_orientation`](crate::Exif::effective_orientation).
+    pub fn container_orientation(&self) -> Option<u16> {
+        self.container_orientation
+    }
+
+    /// Returns the image dimensions `(width, height)` declared by the
+    /// container rather than the Exif data, e.g. the canvas size of the
+    /// `VP8X` chunk in a WebP file.
+    ///
+    /// Returns `None` if the container doesn't declare any. See
+    /// [`Exif::display_dimensions`](crate::Exif::display_dimensions).
+    pub fn container_dimensions(&self) -> Option<(u32, u32)> {
+        self.container_dimensions
+    }
+
+    /// Returns the [`DuplicateStrategy`] used when converting into an
+    /// [`Exif`](crate::Exif) with `Exif::from`.
+    ///
+    /// It's [`DuplicateStrategy::FirstWins`] for CR3 files, whose tags are
+    /// spread over several TIFF blocks, so that a tag repeated in a later
+    /// block doesn't override the main one. Otherwise it's
+    /// [`DuplicateStrategy::LastWins`].
+    pub fn duplicate_strategy(&self) -> DuplicateStrategy {
+        self.duplicate_strategy
+    }
+
+    /// Size of the Exif data (the TIFF header included) in bytes.
+    pub(crate) fn data_len(&self) -> usize {
+        self.input.len()
+    }
+
+    pub(crate) fn set_container_orientation(&mut self, orientation: Option<u16>) {
+        self.container_orientation = orientation;
+    }
+
+    pub(crate) fn set_container_dimensions(&mut self, dimensions: Option<(u32, u32)>) {
+        self.container_dimensions = dimensions;
+    }
+
+    pub(crate) fn set_lens_resolver(&mut self, resolver: Option<LensResolver>) {
+        self.lens_resolver = resolver;
+    }
+
+    pub(crate) fn set_duplicate_strategy(&mut self, strategy: DuplicateStrategy) {
+        self.duplicate_strategy = strategy;
+    }
+
+    /// See [`MediaParser::max_entry_components`](crate::MediaParser::max_entry_components).
+    ///
+    /// Must be called before iterating.
+    pub(crate) fn set_max_entry_components(&mut self, max: Option<u32>) {
+        self.ifd0.max_components = max;
+        for ifd in self.sub_ifds.iter_mut() {
+            ifd.max_components = max;
+        }
+        self.ifds = initial_ifds(&self.ifd0, &self.sub_ifds);
+    }
+
+    /// Attaches the IFD0 of a separate TIFF block as a sub-IFD of IFD0, e.g.
+    /// the Exif IFD (`tag` is [`ExifTag::ExifOffset`]) stored in the `CMT2`
+    /// box of a CR3 file. Sub-IFDs are iterated in the order they are added,
+    /// after all IFDs of the main block.
+    ///
+    /// Must be called before iterating.
+    pub(crate) fn add_sub_ifd(&mut self, input: PartialVec, tag: ExifTag) -> crate::Result<()> {
+        let (_, header) = TiffHeader::parse(&input[..])?;
+        let Some(data) = input.get(header.ifd0_offset as usize..) else {
+            return Err(crate::Error::ParseFailed(
+                "sub-IFD offset is out of range".into(),
+            ));
+        };
+        let ifd = IfdIter::try_new(
+            0,
+            input.partial(data),
+            header.ifd0_offset,
+            header.endian,
+            self.tz.clone(),
+        )?
+        .max_components(self.ifd0.max_components)
+        .tag_code(tag.code());
+
+        self.sub_ifds.push(ifd);
+        self.ifds = initial_ifds(&self.ifd0, &self.sub_ifds);
+        Ok(())
+    }
+
+    /// Returns a reference to the next entry without advancing the iterator.
+    ///
+    /// The peeked entry is buffered, and will be returned by the following
+    /// `next()` call.
+    ///
+    /// ```rust
+    /// use nom_exif::*;
+    ///
+    /// let mut parser = MediaParser::new();
+    /// let ms = MediaSource::file_path("./testdata/exif.jpg").unwrap();
+    /// let mut iter: ExifIter = parser.parse(ms).unwrap();
+    ///
+    /// let tag = iter.peek().unwrap().tag();
+    /// assert_eq!(iter.next().unwrap().tag(), tag);
+    /// ```
+    pub fn peek(&mut self) -> Option<&ParsedExifEntry> {
+        if self.peeked.is_none() {
+            self.peeked = self.advance();
         }
+        self.peeked.as_ref()
+    }
+
+    /// Collects the values of `tags` of the main image, and stops iterating
+    /// as soon as all of them are found, e.g. for showing a few fields
+    /// without parsing the remaining entries (such as a huge `MakerNote`).
+    ///
+    /// Like [`Exif::get`](crate::Exif::get), only entries of IFD0 & its
+    /// Exif/GPS sub-IFDs are taken. The first value is kept if a tag appears
+    /// more than once, and tags which are not found are absent from the
+    /// returned map.
+    ///
+    /// ```rust
+    /// use nom_exif::*;
+    ///
+    /// let mut parser = MediaParser::new();
+    /// let ms = MediaSource::file_path("./testdata/exif.jpg").unwrap();
+    /// let iter: ExifIter = parser.parse(ms).unwrap();
+    ///
+    /// let tags = iter.take_tags(&[ExifTag::Make, ExifTag::Model]);
+    /// assert_eq!(tags[&ExifTag::Make].as_str(), Some("vivo"));
+    /// assert_eq!(tags[&ExifTag::Model].as_str(), Some("vivo X90 Pro+"));
+    /// ```
+    pub fn take_tags(self, tags: &[ExifTag]) -> HashMap<ExifTag, EntryValue> {
+        let mut values = HashMap::new();
+        let mut remaining = tags.to_vec();
+        remaining.sort_by_key(|x| x.code());
+        remaining.dedup();
+
+        for mut entry in self {
+            if remaining.is_empty() {
+                break;
+            }
+            if entry.ifd_index() != 0 || entry.ifd_kind() == IfdKind::SubIfd {
+                continue;
+            }
+            let Some(tag) = entry.tag() else {
+                continue;
+            };
+            let Some(pos) = remaining.iter().position(|x| *x == tag) else {
+                continue;
+            };
+            if let Some(value) = entry.take_value() {
+                remaining.swap_remove(pos);
+                values.insert(tag, value);
+            }
+        }
+        values
+    }
+
+    /// Groups the entries by their IFD index (see
+    /// [`ParsedExifEntry::ifd_index`]), yields `(ifd index, entries)` in
+    /// traversal order, e.g. for printing a section per IFD.
+    ///
+    /// Entries of sub-IFDs (e.g. the Exif & GPS sub-IFDs) are grouped into
+    /// their parent IFD, use [`ParsedExifEntry::ifd_kind`] to tell them
+    /// apart.
+    ///
+    /// **Note**: This is less lazy than iterating the entries directly, since
+    /// all entries of an IFD are parsed & buffered before its group is
+    /// yielded.
+    ///
+    /// ```rust
+    /// use nom_exif::*;
+    ///
+    /// let mut parser = MediaParser::new();
+    /// let ms = MediaSource::file_path("./testdata/exif.jpg").unwrap();
+    /// let iter: ExifIter = parser.parse(ms).unwrap();
+    ///
+    /// for (ifd, entries) in iter.grouped() {
+    ///     println!("IFD{ifd}: {} entries", entries.len());
+    ///     assert!(entries.iter().all(|x| x.ifd_index() == ifd));
+    /// }
+    /// ```
+    pub fn grouped(mut self) -> impl Iterator<Item = (usize, Vec<ParsedExifEntry>)> {
+        std::iter::from_fn(move || {
+            let first = self.next()?;
+            let ifd = first.ifd_index();
+            let mut group = vec![first];
+            while self.peek().is_some_and(|x| x.ifd_index() == ifd) {
+                group.extend(self.next());
+            }
+            Some((ifd, group))
+        })
     }
 
     /// Clone and rewind the iterator's index.
@@ -130,14 +468,308 @@ impl ExifIter {
     /// through Arc.
     pub fn clone_and_rewind(&self) -> Self {
         let ifd0 = self.ifd0.clone_and_rewind();
-        let ifds = vec![ifd0.clone()];
+        let sub_ifds = self
+            .sub_ifds
+            .iter()
+            .map(|x| x.clone_and_rewind())
+            .collect::<Vec<_>>();
+        let ifds = initial_ifds(&ifd0, &sub_ifds);
         Self {
             input: self.input.clone(),
             tiff_header: self.tiff_header.clone(),
             tz: self.tz.clone(),
             ifd0,
+            continue_on_error: self.continue_on_error,
+            container_orientation: self.container_orientation,
+            container_dimensions: self.container_dimensions,
+            duplicate_strategy: self.duplicate_strategy,
+            lens_resolver: self.lens_resolver.clone(),
+            sub_ifds,
             ifds,
+            peeked: None,
+        }
+    }
+
+    /// Get the UTC datetime of the GPS fix, which combines `GPSDateStamp` &
+    /// `GPSTimeStamp`, see [`GPSInfo::gps_datetime`].
+    ///
+    /// Calling this method won't affect the iterator's state. Returns `None`
+    /// if there is no GPS info, or either of the tags is absent or invalid.
+    ///
+    /// ```rust
+    /// use nom_exif::*;
+    ///
+    /// let mut parser = MediaParser::new();
+    /// let ms = MediaSource::file_path("./testdata/exif.jpg").unwrap();
+    /// let iter: ExifIter = parser.parse(ms).unwrap();
+    /// assert_eq!(
+    ///     iter.get_gps_datetime().unwrap().to_rfc3339(),
+    ///     "2023-07-09T12:36:33+00:00"
+    /// );
+    /// ```
+    pub fn get_gps_datetime(&self) -> Option<DateTime<Utc>> {
+        self.parse_gps_info().ok()??.gps_datetime()
+    }
+
+    /// Extract the JPEG thumbnail in ifd1, which is located by
+    /// `ThumbnailOffset` & `ThumbnailLength`, e.g. for showing a quick
+    /// preview without decoding the full image.
+    ///
+    /// Calling this method won't affect the iterator's state. Returns `None`
+    /// if there is no thumbnail, the thumbnail isn't a JPEG image (see
+    /// [`Exif::thumbnail_range`](crate::Exif::thumbnail_range)), or it's out
+    /// of the range of the Exif data.
+    ///
+    /// ```rust
+    /// use nom_exif::*;
+    ///
+    /// let mut parser = MediaParser::new();
+    /// let ms = MediaSource::file_path("./testdata/exif.jpg").unwrap();
+    /// let iter: ExifIter = parser.parse(ms).unwrap();
+    /// let thumbnail = iter.extract_thumbnail().unwrap();
+    /// assert!(thumbnail.starts_with(&[0xff, 0xd8]));
+    /// ```
+    pub fn extract_thumbnail(&self) -> Option<Vec<u8>> {
+        let (mut offset, mut length, mut compression) = (None, None, None);
+        for mut entry in self.clone_and_rewind() {
+            if entry.ifd_index() != 1 {
+                continue;
+            }
+            let slot = match entry.tag() {
+                Some(ExifTag::ThumbnailOffset) => &mut offset,
+                Some(ExifTag::ThumbnailLength) => &mut length,
+                Some(ExifTag::Compression) => &mut compression,
+                _ => continue,
+            };
+            *slot = entry.take_value();
+        }
+        let range = thumbnail_range(offset.as_ref(), length.as_ref(), compression.as_ref())?;
+
+        // IFD0 is located at `ifd0.offset` of the TIFF data
+        let input = &self.ifd0.input;
+        let tiff_start = input.range.start.checked_sub(self.ifd0.offset as usize)?;
+        let start = tiff_start.checked_add(range.start)?;
+        let end = tiff_start.checked_add(range.end)?;
+        if end > self.input.range.end {
+            tracing::warn!(?range, "thumbnail is out of range");
+            return None;
+        }
+        let data = input.data.get(start..end)?;
+        // JPEG SOI marker
+        if !data.starts_with(&[0xff, 0xd8]) {
+            tracing::warn!(?range, "invalid JPEG thumbnail");
+            return None;
+        }
+        Some(data.to_vec())
+    }
+
+    /// Get the numeric lens type stored in the maker note, which maps to a
+    /// lens name via a vendor database, see [`Self::lens_name`].
+    ///
+    /// Only the maker notes of Canon (`LensType` in `CameraSettings`) & Sony
+    /// (`LensType`) are supported. Calling this method won't affect the
+    /// iterator's state.
+    ///
+    /// The value is also known as [`ExifTag::LensType`] (or
+    /// [`ExifTag::LensID`]), but it's stored in the maker note rather than a
+    /// standard IFD, so it's only reachable through this method.
+    ///
+    /// ```rust
+    /// use nom_exif::*;
+    ///
+    /// let mut parser = MediaParser::new();
+    /// let ms = MediaSource::file_path("./testdata/exif.jpg").unwrap();
+    /// let iter: ExifIter = parser.parse(ms).unwrap();
+    /// // Maker notes of vivo are not supported
+    /// assert!(iter.lens_type().is_none());
+    /// ```
+    pub fn lens_type(&self) -> Option<u32> {
+        self.make_and_lens_type().map(|x| x.1)
+    }
+
+    /// Resolve the lens name from [`Self::lens_type`] with the resolver set by
+    /// [`MediaParser::set_lens_resolver`](crate::MediaParser::set_lens_resolver).
+    ///
+    /// Returns `None` if no resolver is set, there is no lens type, or the
+    /// resolver doesn't know the lens.
+    pub fn lens_name(&self) -> Option<String> {
+        let resolver = self.lens_resolver.as_ref()?;
+        let (make, lens_type) = self.make_and_lens_type()?;
+        resolver(&make, lens_type)
+    }
+
+    fn ifd0_make(&self) -> Option<EntryValue> {
+        self.clone_and_rewind().find_map(|mut entry| {
+            (entry.ifd_index() == 0 && entry.tag() == Some(ExifTag::Make))
+                .then(|| entry.take_value())
+                .flatten()
+        })
+    }
+
+    fn make_and_lens_type(&self) -> Option<(String, u32)> {
+        let make = self.ifd0_make()?;
+        let make = make.as_str()?.trim();
+        let lens_type = if makernote::is_canon(make) {
+            let note = self.parse_maker_note_canon().ok()??;
+            note.lens_type()?.into()
+        } else if makernote::is_sony(make) {
+            self.sony_lens_type()?
+        } else {
+            return None;
+        };
+        Some((make.to_owned(), lens_type))
+    }
+
+    /// A Sony maker note is a plain IFD, which is optionally preceded by a
+    /// header, and its value offsets are relative to the TIFF header.
+    fn sony_lens_type(&self) -> Option<u32> {
+        let (offset, range) = self.maker_note_range().ok()??;
+        let input = &self.ifd0.input;
+        let header = makernote::sony_header_len(&input.data[range.clone()]); // Safe-slice
+        let ifd = &input.data[range.start + header..self.input.range.end]; // Safe-slice
+        let iter = IfdIter::try_new(
+            0,
+            input.partial(ifd),
+            offset + header as u32,
+            self.tiff_header.endian,
+            None,
+        )
+        .ok()?
+        .max_components(self.ifd0.max_components)
+        // Ignore the next IFD offset
+        .tag_code(ExifTag::MakerNote.code());
+        makernote::sony_lens_type(&maker_note_entries(iter))
+    }
+
+    /// Parse the Apple (iPhone/iPad) maker note, which is detected by its
+    /// `Apple iOS\0` signature, e.g. to get the HDR headroom of an image.
+    ///
+    /// Calling this method won't affect the iterator's state.
+    ///
+    /// **Note**: The value offsets of an Apple maker note are relative to the
+    /// start of the maker note (i.e. the signature), rather than the TIFF
+    /// header, so the maker note is parsed as a self-contained IFD with that
+    /// base.
+    ///
+    /// Returns:
+    ///
+    /// - An `Ok<Some<AppleMakerNote>>` if an Apple maker note is found and
+    ///   parsed successfully.
+    /// - An `Ok<None>` if there is no maker note, or it isn't an Apple one.
+    /// - An `Err` if the maker note is out of range or its IFD is broken.
+    ///
+    /// ```rust
+    /// use nom_exif::*;
+    ///
+    /// let mut parser = MediaParser::new();
+    /// let ms = MediaSource::file_path("./testdata/exif.heic").unwrap();
+    /// let iter: ExifIter = parser.parse(ms).unwrap();
+    /// let note = iter.parse_maker_note().unwrap().unwrap();
+    /// assert!(note.hdr_headroom().unwrap() > 1.0);
+    /// ```
+    pub fn parse_maker_note(&self) -> crate::Result<Option<AppleMakerNote>> {
+        let Some((_, range)) = self.maker_note_range()? else {
+            return Ok(None);
+        };
+        let input = &self.ifd0.input;
+        let data = &input.data[range]; // Safe-slice
+        let Some(endian) = makernote::apple_endian(data) else {
+            return Ok(None);
+        };
+
+        let ifd = &data[makernote::APPLE_HEADER_SIZE..]; // Safe-slice
+        let iter = IfdIter::try_new(
+            0,
+            input.partial(ifd),
+            makernote::APPLE_HEADER_SIZE as u32,
+            endian,
+            None,
+        )?
+        .max_components(self.ifd0.max_components)
+        // Ignore the next IFD offset
+        .tag_code(ExifTag::MakerNote.code());
+        Ok(Some(AppleMakerNote::new(maker_note_entries(iter))))
+    }
+
+    /// Parse the Canon maker note, if `Make` starts with "Canon".
+    ///
+    /// Calling this method won't affect the iterator's state.
+    ///
+    /// A Canon maker note is a plain IFD without any header, which uses the
+    /// byte order of the Exif data, and its value offsets are relative to the
+    /// TIFF header, the same as the other IFDs. Array values like
+    /// `CameraSettings` are decoded as values rather than offsets, and values
+    /// beyond the Exif data are skipped.
+    ///
+    /// **Note**: The maker note of CR3 files is stored in a separate `CMT3`
+    /// box, which isn't supported yet.
+    ///
+    /// Returns:
+    ///
+    /// - An `Ok<Some<CanonMakerNote>>` if a Canon maker note is found and
+    ///   parsed successfully.
+    /// - An `Ok<None>` if there is no maker note, or the camera isn't a Canon.
+    /// - An `Err` if the maker note is out of range or its IFD is broken.
+    ///
+    /// ```rust
+    /// use nom_exif::*;
+    ///
+    /// let mut parser = MediaParser::new();
+    /// let ms = MediaSource::file_path("./testdata/exif.jpg").unwrap();
+    /// let iter: ExifIter = parser.parse(ms).unwrap();
+    /// // This image was taken by a vivo phone
+    /// assert!(iter.parse_maker_note_canon().unwrap().is_none());
+    /// ```
+    pub fn parse_maker_note_canon(&self) -> crate::Result<Option<CanonMakerNote>> {
+        let is_canon = self
+            .ifd0_make()
+            .is_some_and(|x| x.as_str().is_some_and(makernote::is_canon));
+        if !is_canon {
+            return Ok(None);
+        }
+        let Some((offset, range)) = self.maker_note_range()? else {
+            return Ok(None);
+        };
+
+        // Value offsets are relative to the TIFF header, so the IFD data
+        // spans to the end of the TIFF data.
+        let input = &self.ifd0.input;
+        let ifd = &input.data[range.start..self.input.range.end]; // Safe-slice
+        let iter = IfdIter::try_new(0, input.partial(ifd), offset, self.tiff_header.endian, None)?
+            .max_components(self.ifd0.max_components)
+            // Ignore the next IFD offset
+            .tag_code(ExifTag::MakerNote.code());
+        Ok(Some(CanonMakerNote::new(maker_note_entries(iter))))
+    }
+
+    /// Locates the maker note, returns its offset relative to the TIFF
+    /// header, and its range in the data of `self.ifd0.input`.
+    ///
+    /// Returns `Ok(None)` if there is no maker note, or its value is so small
+    /// that it's stored inline rather than at an offset.
+    fn maker_note_range(&self) -> crate::Result<Option<(u32, Range<usize>)>> {
+        let Some((count, offset)) = self
+            .ifd0
+            .find_exif_iter()
+            .and_then(|x| x.find_raw_entry(ExifTag::MakerNote.code()))
+        else {
+            return Ok(None);
+        };
+        if count <= 4 {
+            return Ok(None);
         }
+
+        // IFD0 is located at `ifd0.offset` of the TIFF data
+        let input = &self.ifd0.input;
+        let range = input
+            .range
+            .start
+            .checked_sub(self.ifd0.offset as usize)
+            .and_then(|x| x.checked_add(offset as usize))
+            .and_then(|start| Some(start..start.checked_add(count as usize)?))
+            .filter(|range| range.end <= self.input.range.end && range.end <= input.data.len())
+            .ok_or_else(|| crate::Error::ParseFailed("maker note is out of range".into()))?;
+        Ok(Some((offset, range)))
     }
 
     /// Try to find and parse gps information.
@@ -151,6 +783,10 @@ impl ExifIter {
     /// - An `Err` if gps info is found but parsing failed.
     #[tracing::instrument(skip_all)]
     pub fn parse_gps_info(&self) -> crate::Result<Option<GPSInfo>> {
+        if let Some(gps) = self.sub_ifds.iter().find(|x| x.kind() == IfdKind::Gps) {
+            return Ok(gps.clone_and_rewind().parse_gps_info());
+        }
+
         let mut iter = self.clone_and_rewind();
         let Some(gps) = iter.find(|x| {
             tracing::info!(?x, "find");
@@ -178,6 +814,11 @@ impl ExifIter {
                 "GPSInfo offset is out of range".into(),
             ));
         }
+        if !iter.ifd0.is_valid_new_ifd_offset(offset) {
+            return Err(crate::Error::ParseFailed(
+                "GPSInfo offset points backward or overlaps IFD0".into(),
+            ));
+        }
 
         let mut gps_subifd = match IfdIter::try_new(
             gps.ifd,
@@ -186,22 +827,42 @@ impl ExifIter {
             iter.tiff_header.endian,
             iter.tz.clone(),
         ) {
-            Ok(ifd0) => ifd0.tag_code(ExifTag::GPSInfo.code()),
+            Ok(ifd) => ifd
+                .max_components(iter.ifd0.max_components)
+                .tag_code(ExifTag::GPSInfo.code()),
             Err(e) => return Err(e),
         };
         Ok(gps_subifd.parse_gps_info())
     }
 
     pub(crate) fn to_owned(&self) -> ExifIter {
-        ExifIter::new(
+        let mut iter = ExifIter::new(
             self.input.to_vec(),
             self.tiff_header.clone(),
             self.tz.clone(),
             self.ifd0.clone_and_rewind(),
         )
+        .continue_on_error(self.continue_on_error);
+        iter.duplicate_strategy = self.duplicate_strategy;
+        iter.lens_resolver = self.lens_resolver.clone();
+        // Sub-IFDs hold their own data
+        iter.sub_ifds = self.sub_ifds.iter().map(|x| x.clone_and_rewind()).collect();
+        iter.ifds = initial_ifds(&iter.ifd0, &iter.sub_ifds);
+        iter
     }
 }
 
+/// IFDs are iterated as a stack, IFD0 is on the top, followed by the
+/// sub-IFDs of separate TIFF blocks.
+fn initial_ifds(ifd0: &IfdIter, sub_ifds: &[IfdIter]) -> Vec<IfdIter> {
+    sub_ifds
+        .iter()
+        .rev()
+        .chain(std::iter::once(ifd0))
+        .cloned()
+        .collect()
+}
+
 #[derive(Debug, Clone, Error)]
 #[error("ifd entry error: {0}")]
 pub struct EntryError(ParseEntryError);
@@ -212,23 +873,74 @@ impl From<EntryError> for crate::Error {
     }
 }
 
+/// The kind of IFD where an entry is located, see
+/// [`ParsedExifEntry::ifd_kind`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum IfdKind {
+    /// IFD0, which describes the main image.
+    Main,
+
+    /// IFD1 (or any subsequent IFD), which usually describes the thumbnail.
+    Thumbnail,
+
+    /// The Exif sub-IFD pointed to by [`ExifTag::ExifOffset`].
+    Exif,
+
+    /// The GPS sub-IFD pointed to by [`ExifTag::GPSInfo`].
+    Gps,
+
+    /// The interoperability sub-IFD pointed to by [`ExifTag::InteropOffset`].
+    Interop,
+
+    /// A sub-IFD pointed to by [`ExifTag::SubIFDs`], which describes another
+    /// image, e.g. the full-size image of a Nikon NEF file.
+    SubIfd,
+}
+
+const GPS_TAG_CODE_MAX: u16 = 0x1f;
+
 /// Represents a parsed IFD entry. Used as iterator items in [`ExifIter`].
 #[derive(Clone)]
 pub struct ParsedExifEntry {
     // 0: ifd0, 1: ifd1
     ifd: usize,
+    ifd_kind: IfdKind,
     tag: ExifTagCode,
-    res: Option<Result<EntryValue, EntryError>>,
+    // For text entries, the value is decoded lazily from `text`.
+    res: OnceLock<Option<Result<EntryValue, EntryError>>>,
+    // Raw data of a text entry, which is shared with the Exif data buffer.
+    text: Option<PartialVec>,
+    data_format: Option<DataFormat>,
 }
 
 impl ParsedExifEntry {
     /// Get the IFD index value where this entry is located.
+    ///
+    /// The index is the position of the IFD in the main chain of IFDs:
     /// - 0: ifd0 (main image)
     /// - 1: ifd1 (thumbnail)
+    /// - 2, 3, ...: any subsequent IFDs, which are rare
+    ///
+    /// Entries of a sub-IFD (e.g. the Exif & GPS sub-IFDs) share the index of
+    /// their parent IFD, use [`Self::ifd_kind`] to tell them apart. Thus an
+    /// index of 1 always refers to IFD1, never a sub-IFD of IFD0.
     pub fn ifd_index(&self) -> usize {
         self.ifd
     }
 
+    /// Get the kind of IFD where this entry is located.
+    ///
+    /// Unlike [`Self::ifd_index`], this tells whether the entry comes from a
+    /// sub-IFD, e.g.: entries of the GPS sub-IFD have an `ifd_index` of 0, but
+    /// an `ifd_kind` of [`IfdKind::Gps`].
+    ///
+    /// **Note**: The entry which points to a sub-IFD (e.g.:
+    /// [`ExifTag::GPSInfo`]) belongs to its parent IFD.
+    pub fn ifd_kind(&self) -> IfdKind {
+        self.ifd_kind
+    }
+
     /// Get recognized Exif tag of this entry, maybe return `None` if the tag
     /// is unrecognized.
     ///
@@ -253,6 +965,49 @@ impl ParsedExifEntry {
         self.tag.code()
     }
 
+    /// Returns the data format the value is stored in, named after the TIFF
+    /// field types, i.e. one of "byte", "ascii", "short", "long",
+    /// "rational", "sbyte", "undefined", "sshort", "slong", "srational",
+    /// "float" & "double".
+    ///
+    /// Returns `None` if the format is invalid.
+    ///
+    /// ```rust
+    /// use nom_exif::*;
+    ///
+    /// let mut parser = MediaParser::new();
+    /// let ms = MediaSource::file_path("./testdata/exif.jpg").unwrap();
+    /// let mut iter: ExifIter = parser.parse(ms).unwrap();
+    ///
+    /// let entry = iter.find(|x| x.tag() == Some(ExifTag::Make)).unwrap();
+    /// assert_eq!(entry.data_format(), Some("ascii"));
+    /// ```
+    pub fn data_format(&self) -> Option<&'static str> {
+        self.data_format.map(|x| x.name())
+    }
+
+    /// Returns true if this is a GPS entry, e.g. [`ExifTag::GPSLatitude`].
+    ///
+    /// GPS tag codes (0x00 ~ 0x1f) overlap with the interoperability tags, so
+    /// only entries located in the GPS sub-IFD are considered.
+    pub fn is_gps_tag(&self) -> bool {
+        self.ifd_kind == IfdKind::Gps && self.tag_code() <= GPS_TAG_CODE_MAX
+    }
+
+    /// Returns true if this is [`ExifTag::ThumbnailOffset`] or
+    /// [`ExifTag::ThumbnailLength`].
+    pub fn is_thumbnail_tag(&self) -> bool {
+        let code = self.tag_code();
+        code == ExifTag::ThumbnailOffset.code() || code == ExifTag::ThumbnailLength.code()
+    }
+
+    /// Returns true if this is [`ExifTag::DateTimeOriginal`],
+    /// [`ExifTag::CreateDate`] or [`ExifTag::ModifyDate`], whose value is
+    /// parsed as an [`EntryValue::Time`].
+    pub fn is_datetime_tag(&self) -> bool {
+        is_date_time_tag(self.tag_code())
+    }
+
     /// Returns true if there is an `EntryValue` in self.
     ///
     /// Both of the following situations may cause this method to return false:
@@ -260,12 +1015,12 @@ impl ParsedExifEntry {
     /// - The value has been taken by calling [`Self::take_value`] or
     ///   [`Self::take_result`] methods.
     pub fn has_value(&self) -> bool {
-        self.res.as_ref().map(|e| e.is_ok()).is_some_and(|b| b)
+        self.text.is_some() || matches!(self.res.get(), Some(Some(Ok(_))))
     }
 
     /// Get the parsed entry value of this entry.
     pub fn get_value(&self) -> Option<&EntryValue> {
-        match self.res.as_ref() {
+        match self.res() {
             Some(Ok(v)) => Some(v),
             Some(Err(_)) | None => None,
         }
@@ -280,7 +1035,7 @@ impl ParsedExifEntry {
     /// calling it again always returns `None`. You may want to check it by
     /// calling [`Self::has_value`] before calling this method.
     pub fn take_value(&mut self) -> Option<EntryValue> {
-        match self.res.take() {
+        match self.take_res() {
             Some(v) => v.ok(),
             None => None,
         }
@@ -295,8 +1050,8 @@ impl ParsedExifEntry {
     ///
     /// - Otherwise, an Ok(&[`EntryValue`]) is returned.
     pub fn get_result(&self) -> Result<&EntryValue, &EntryError> {
-        match self.res {
-            Some(ref v) => v.as_ref(),
+        match self.res() {
+            Some(v) => v.as_ref(),
             None => panic!("take result of entry twice"),
         }
     }
@@ -316,37 +1071,169 @@ impl ParsedExifEntry {
     /// **Note**: This method can ONLY be called once! If you call it twice, it
     /// will **panic** directly!
     pub fn take_result(&mut self) -> Result<EntryValue, EntryError> {
-        match self.res.take() {
+        match self.take_res() {
             Some(v) => v,
             None => panic!("take result of entry twice"),
         }
     }
 
-    fn make_ok(ifd: usize, tag: ExifTagCode, v: EntryValue) -> Self {
+    /// Get the text value of this entry, borrowed from the underlying Exif
+    /// data buffer, without allocating a `String`.
+    ///
+    /// Returns `None` if this is not a text entry, or the value has been taken.
+    ///
+    /// ```rust
+    /// use nom_exif::*;
+    ///
+    /// let mut parser = MediaParser::new();
+    /// let ms = MediaSource::file_path("./testdata/exif.jpg").unwrap();
+    /// let iter: ExifIter = parser.parse(ms).unwrap();
+    /// for entry in &iter {
+    ///     if entry.tag() == Some(ExifTag::Make) {
+    ///         assert_eq!(entry.text_ref(), Some("vivo"));
+    ///     }
+    /// }
+    /// ```
+    pub fn text_ref(&self) -> Option<&str> {
+        match self.text {
+            Some(ref text) => get_cstr_ref(text).ok(),
+            None => self.get_value().and_then(|v| v.as_str()),
+        }
+    }
+
+    /// Get the decoded [`FileSource`] if this is an [`ExifTag::FileSource`]
+    /// entry with a recognized value.
+    pub fn file_source(&self) -> Option<FileSource> {
+        self.enum_value(ExifTag::FileSource)
+    }
+
+    /// Get the decoded [`SceneType`] if this is an [`ExifTag::SceneType`]
+    /// entry with a recognized value.
+    pub fn scene_type(&self) -> Option<SceneType> {
+        self.enum_value(ExifTag::SceneType)
+    }
+
+    /// Get the decoded [`SensingMethod`] if this is an
+    /// [`ExifTag::SensingMethod`] entry with a recognized value.
+    ///
+    /// ```rust
+    /// use nom_exif::*;
+    ///
+    /// let mut parser = MediaParser::new();
+    /// let ms = MediaSource::file_path("./testdata/exif.jpg").unwrap();
+    /// let iter: ExifIter = parser.parse(ms).unwrap();
+    /// let entry = iter
+    ///     .into_iter()
+    ///     .find(|x| x.tag() == Some(ExifTag::SensingMethod))
+    ///     .unwrap();
+    /// assert_eq!(entry.sensing_method(), Some(SensingMethod::OneChipColorArea));
+    /// ```
+    pub fn sensing_method(&self) -> Option<SensingMethod> {
+        self.enum_value(ExifTag::SensingMethod)
+    }
+
+    /// These tags are stored either as a single `Undefined` byte or as a
+    /// short, depending on the tag.
+    fn enum_value<T: TryFrom<u16>>(&self, tag: ExifTag) -> Option<T> {
+        if self.tag_code() != tag.code() {
+            return None;
+        }
+        let code = match self.get_value()? {
+            EntryValue::Undefined(v) => (*v.first()?).into(),
+            v => v.as_u16().or_else(|| v.as_u8().map(u16::from))?,
+        };
+        T::try_from(code).ok()
+    }
+
+    fn res(&self) -> Option<&Result<EntryValue, EntryError>> {
+        self.res
+            .get_or_init(|| self.text.as_ref().map(|x| Ok(decode_text(x))))
+            .as_ref()
+    }
+
+    fn take_res(&mut self) -> Option<Result<EntryValue, EntryError>> {
+        let text = self.text.take();
+        match self.res.take() {
+            Some(res) => res,
+            None => text.map(|x| Ok(decode_text(&x))),
+        }
+    }
+
+    fn make_ok(ifd: usize, ifd_kind: IfdKind, tag: ExifTagCode, v: EntryValue) -> Self {
         Self {
             ifd,
+            ifd_kind,
             tag,
-            res: Some(Ok(v)),
+            res: OnceLock::from(Some(Ok(v))),
+            text: None,
+            data_format: None,
         }
     }
 
-    fn make_err(ifd: usize, tag: ExifTagCode, e: ParseEntryError) -> Self {
+    fn make_err(ifd: usize, ifd_kind: IfdKind, tag: ExifTagCode, e: ParseEntryError) -> Self {
         Self {
             ifd,
+            ifd_kind,
             tag,
-            res: Some(Err(EntryError(e))),
+            res: OnceLock::from(Some(Err(EntryError(e)))),
+            text: None,
+            data_format: None,
         }
     }
+
+    fn make_text(ifd: usize, ifd_kind: IfdKind, tag: ExifTagCode, text: PartialVec) -> Self {
+        Self {
+            ifd,
+            ifd_kind,
+            tag,
+            res: OnceLock::new(),
+            text: Some(text),
+            data_format: None,
+        }
+    }
+
+    fn with_data_format(mut self, data_format: Option<DataFormat>) -> Self {
+        self.data_format = data_format;
+        self
+    }
+}
+
+// The text has been validated when parsing the entry.
+fn decode_text(text: &[u8]) -> EntryValue {
+    EntryValue::Text(get_cstr_ref(text).unwrap_or_default().to_owned())
+}
+
+/// Collects `(tag code, value)` of the entries of a maker note IFD, broken
+/// entries are skipped.
+fn maker_note_entries(iter: IfdIter) -> Vec<(u16, EntryValue)> {
+    iter.filter_map(|(tag, entry)| {
+        let value = match entry {
+            IfdEntry::Entry(v) => v,
+            IfdEntry::Text(v) => decode_text(&v),
+            IfdEntry::Err(e) => {
+                tracing::warn!(?tag, ?e, "parse maker note entry error");
+                return None;
+            }
+            IfdEntry::IfdNew(_) | IfdEntry::SubIfds(..) => return None,
+        };
+        Some((tag?.code(), value))
+    })
+    .collect()
 }
 
 impl Debug for ParsedExifEntry {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let value = match self.get_result() {
-            Ok(v) => format!("{v}"),
+            // Show enum-like values along with their descriptions
+            Ok(v) => match self.tag().and_then(|t| t.describe_value(v)) {
+                Some(desc) => format!("{v} ({desc})"),
+                None => format!("{v}"),
+            },
             Err(e) => format!("{e:?}"),
         };
         f.debug_struct("IfdEntryResult")
             .field("ifd", &format!("ifd{}", self.ifd))
+            .field("ifd_kind", &self.ifd_kind)
             .field("tag", &self.tag)
             .field("value", &value)
             .finish()
@@ -355,11 +1242,26 @@ impl Debug for ParsedExifEntry {
 
 const MAX_IFD_DEPTH: usize = 8;
 
+impl IntoIterator for &ExifIter {
+    type Item = ParsedExifEntry;
+    type IntoIter = ExifIter;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.clone_and_rewind()
+    }
+}
+
 impl Iterator for ExifIter {
     type Item = ParsedExifEntry;
 
-    #[tracing::instrument(skip_all)]
     fn next(&mut self) -> Option<Self::Item> {
+        self.peeked.take().or_else(|| self.advance())
+    }
+}
+
+impl ExifIter {
+    #[cfg_attr(feature = "trace_spans", tracing::instrument(skip_all))]
+    fn advance(&mut self) -> Option<ParsedExifEntry> {
         loop {
             if self.ifds.is_empty() {
                 tracing::debug!(?self, "all IFDs has been parsed");
@@ -377,8 +1279,10 @@ impl Iterator for ExifIter {
 
             let mut ifd = self.ifds.pop()?;
             let cur_ifd_idx = ifd.ifd_idx;
-            match ifd.next() {
-                Some((tag_code, entry)) => {
+            let ifd_kind = ifd.kind();
+            let cur_index = ifd.index;
+            match ifd.next_entry() {
+                Some((tag_code, data_format, entry)) => {
                     // tracing::debug!(ifd = ifd.ifd_idx, ?tag_code, ?entry, "next tag entry");
 
                     match entry {
@@ -389,6 +1293,9 @@ impl Iterator for ExifIter {
                                     ?new_ifd,
                                     "bad new SUB-IFD: offset is smaller than current IFD"
                                 );
+                                if self.continue_on_error {
+                                    self.ifds.push(ifd);
+                                }
                                 continue;
                             }
                             let is_subifd = if new_ifd.ifd_idx == ifd.ifd_idx {
@@ -410,28 +1317,90 @@ impl Iterator for ExifIter {
 
                             if is_subifd {
                                 // Return sub-ifd as an entry
-                                return Some(ParsedExifEntry::make_ok(
-                                    ifd_idx,
-                                    tag_code.unwrap(),
-                                    EntryValue::U32(offset),
-                                ));
+                                return Some(
+                                    ParsedExifEntry::make_ok(
+                                        ifd_idx,
+                                        ifd_kind,
+                                        tag_code.unwrap(),
+                                        EntryValue::U32(offset),
+                                    )
+                                    .with_data_format(data_format),
+                                );
                             }
                         }
+                        IfdEntry::SubIfds(v, sub_ifds) => {
+                            let ifd_idx = ifd.ifd_idx;
+                            self.ifds.push(ifd);
+                            // Keep the depth bounded, the remaining sub-IFDs
+                            // are dropped
+                            let room = MAX_IFD_DEPTH.saturating_sub(self.ifds.len());
+                            if sub_ifds.len() > room {
+                                tracing::warn!(
+                                    num = sub_ifds.len(),
+                                    room,
+                                    "too many SubIFDs, some of them are ignored"
+                                );
+                            }
+                            // The first sub-IFD is on the top
+                            self.ifds.extend(sub_ifds.into_iter().take(room).rev());
+                            return Some(
+                                ParsedExifEntry::make_ok(ifd_idx, ifd_kind, tag_code.unwrap(), v)
+                                    .with_data_format(data_format),
+                            );
+                        }
                         IfdEntry::Entry(v) => {
-                            let res =
-                                Some(ParsedExifEntry::make_ok(ifd.ifd_idx, tag_code.unwrap(), v));
+                            let res = Some(
+                                ParsedExifEntry::make_ok(
+                                    ifd.ifd_idx,
+                                    ifd_kind,
+                                    tag_code.unwrap(),
+                                    v,
+                                )
+                                .with_data_format(data_format),
+                            );
+                            self.ifds.push(ifd);
+                            return res;
+                        }
+                        IfdEntry::Text(text) => {
+                            let res = Some(
+                                ParsedExifEntry::make_text(
+                                    ifd.ifd_idx,
+                                    ifd_kind,
+                                    tag_code.unwrap(),
+                                    text,
+                                )
+                                .with_data_format(data_format),
+                            );
                             self.ifds.push(ifd);
                             return res;
                         }
                         IfdEntry::Err(e) => {
                             tracing::warn!(?tag_code, ?e, "parse ifd entry error");
-                            let res =
-                                Some(ParsedExifEntry::make_err(ifd.ifd_idx, tag_code.unwrap(), e));
+                            let res = Some(
+                                ParsedExifEntry::make_err(
+                                    ifd.ifd_idx,
+                                    ifd_kind,
+                                    tag_code.unwrap(),
+                                    e,
+                                )
+                                .with_data_format(data_format),
+                            );
+                            if self.continue_on_error {
+                                self.ifds.push(ifd);
+                            }
                             return res;
                         }
                     }
                 }
-                None => continue,
+                None => {
+                    // An invalid entry has been skipped, go on parsing the
+                    // remaining entries in recovery mode.
+                    if self.continue_on_error && ifd.index != cur_index {
+                        tracing::warn!(?ifd, "skip invalid ifd entry");
+                        self.ifds.push(ifd);
+                    }
+                    continue;
+                }
             }
         }
     }
@@ -452,6 +1421,10 @@ pub(crate) struct IfdIter {
     endian: Endianness,
     entry_num: u16,
 
+    // Entries with more components are rejected, see
+    // `MediaParser::max_entry_components`
+    max_components: Option<u32>,
+
     // Iterating status
     index: u16,
     pos: usize,
@@ -496,13 +1469,29 @@ impl IfdIter {
         self
     }
 
+    pub fn max_components(mut self, max: Option<u32>) -> Self {
+        self.max_components = max;
+        self
+    }
+
     #[allow(unused)]
     pub fn tag(mut self, tag: ExifTagCode) -> Self {
         self.tag_code = Some(tag);
         self
     }
 
-    #[tracing::instrument(skip(input))]
+    fn kind(&self) -> IfdKind {
+        match self.tag_code.and_then(|x| x.tag()) {
+            Some(ExifTag::ExifOffset) => IfdKind::Exif,
+            Some(ExifTag::GPSInfo) => IfdKind::Gps,
+            Some(ExifTag::InteropOffset) => IfdKind::Interop,
+            Some(ExifTag::SubIFDs) => IfdKind::SubIfd,
+            _ if self.ifd_idx == 0 => IfdKind::Main,
+            _ => IfdKind::Thumbnail,
+        }
+    }
+
+    #[cfg_attr(feature = "trace_spans", tracing::instrument(skip(input)))]
     pub fn try_new(
         ifd_idx: usize,
         input: AssociatedInput,
@@ -526,13 +1515,16 @@ impl IfdIter {
             entry_num,
             tz,
             endian,
+            max_components: None,
             // Skip the first two bytes, which is the entry num
             pos: 2,
             index: 0,
         })
     }
 
-    fn parse_tag_entry(&self, entry_data: &[u8]) -> Option<(u16, IfdEntry)> {
+    /// Returns the tag code, the data format (`None` if it's invalid) & the
+    /// parsed entry.
+    fn parse_tag_entry(&self, entry_data: &[u8]) -> Option<(u16, Option<DataFormat>, IfdEntry)> {
         let endian = self.endian;
         let (_, (tag, data_format, components_num, value_or_offset)) = tuple((
             complete::u16::<_, nom::error::Error<_>>(endian),
@@ -542,7 +1534,8 @@ impl IfdIter {
         ))(entry_data)
         .ok()?;
 
-        if tag == 0 {
+        // Tag 0 is only valid as `GPSVersionID` in the GPS IFD
+        if tag == 0 && self.kind() != IfdKind::Gps {
             return None;
         }
 
@@ -551,11 +1544,11 @@ impl IfdIter {
             Err(e) => {
                 let t: ExifTagCode = tag.into();
                 tracing::warn!(tag = ?t, ?e, "invalid entry data format");
-                return Some((tag, IfdEntry::Err(e)));
+                return Some((tag, None, IfdEntry::Err(e)));
             }
         };
         let (tag, res) = self.parse_entry(tag, df, components_num, entry_data, value_or_offset);
-        Some((tag, res))
+        Some((tag, Some(df), res))
     }
 
     fn get_data_pos(&self, value_or_offset: u32) -> u32 {
@@ -573,13 +1566,27 @@ impl IfdIter {
         // get component_size according to data format
         let component_size = data_format.component_size();
 
-        // get entry data
-        let size = components_num as usize * component_size;
+        if self.max_components.is_some_and(|max| components_num > max) {
+            tracing::warn!(
+                tag,
+                components_num,
+                max = self.max_components,
+                "entry components exceed the limit"
+            );
+            return (tag, IfdEntry::Err(ParseEntryError::EntrySizeTooBig));
+        }
+
+        // get entry data, the size may overflow on 32-bit targets
+        let Some(size) = (components_num as usize).checked_mul(component_size) else {
+            return (tag, IfdEntry::Err(ParseEntryError::EntrySizeTooBig));
+        };
         let data = if size <= 4 {
             &entry_data[8..8 + size] // Safe-slice
         } else {
             let start = self.get_data_pos(value_or_offset) as usize;
-            let end = start + size;
+            let Some(end) = start.checked_add(size) else {
+                return (tag, IfdEntry::Err(ParseEntryError::EntrySizeTooBig));
+            };
             let Some(data) = self.input.slice_checked(start..end) else {
                 tracing::warn!(
                     "entry data overflow, self.offset: {:08x} tag: {:04x} start: {:08x} end: {:08x} ifd data len {:08x}",
@@ -600,6 +1607,25 @@ impl IfdIter {
                 return (tag, value);
             }
         }
+        let sub_ifds = if tag == ExifTag::SubIFDs.code()
+            && matches!(data_format, DataFormat::U32 | DataFormat::Ifd)
+        {
+            self.new_sub_ifd_iters(data)
+        } else {
+            Vec::new()
+        };
+
+        // Text values are decoded lazily, so that they can be borrowed from the
+        // Exif data buffer.
+        if data_format == DataFormat::Text && !data.is_empty() && !is_date_time_tag(tag) {
+            return match get_cstr_ref(data) {
+                Ok(_) => (tag, IfdEntry::Text(self.input.partial(data))),
+                Err(e) => (
+                    tag,
+                    IfdEntry::Err(ParseEntryError::InvalidData(e.to_string())),
+                ),
+            };
+        }
 
         let entry = EntryData {
             endian: self.endian,
@@ -609,17 +1635,44 @@ impl IfdIter {
             components_num,
         };
         match EntryValue::parse(&entry, &self.tz) {
+            Ok(v) if !sub_ifds.is_empty() => (tag, IfdEntry::SubIfds(v, sub_ifds)),
             Ok(v) => (tag, IfdEntry::Entry(v)),
             Err(e) => (tag, IfdEntry::Err(e)),
         }
     }
 
+    /// `SubIFDs` holds an array of offsets, e.g. the full-size image & the
+    /// previews of a NEF file. Invalid offsets are skipped.
+    fn new_sub_ifd_iters(&self, data: &[u8]) -> Vec<IfdIter> {
+        let tag = Some(ExifTag::SubIFDs.code());
+        data.chunks_exact(4)
+            .filter_map(|x| {
+                let (_, offset) = complete::u32::<_, nom::error::Error<_>>(self.endian)(x).ok()?;
+                match self.new_ifd_iter(self.ifd_idx, offset, tag)? {
+                    IfdEntry::IfdNew(iter) => Some(iter),
+                    _ => None,
+                }
+            })
+            .collect()
+    }
+
     fn new_ifd_iter(
         &self,
         ifd_idx: usize,
         value_or_offset: u32,
         tag: Option<u16>,
     ) -> Option<IfdEntry> {
+        if !self.is_valid_new_ifd_offset(value_or_offset) {
+            tracing::warn!(
+                ?tag,
+                offset = value_or_offset,
+                self.offset,
+                self.entry_num,
+                "bad next/sub IFD: offset points backward or overlaps current IFD"
+            );
+            return None;
+        }
+
         let pos = self.get_data_pos(value_or_offset) as usize;
         if pos < self.input.len() {
             match IfdIter::try_new(
@@ -629,7 +1682,10 @@ impl IfdIter {
                 self.endian,
                 self.tz.clone(),
             ) {
-                Ok(iter) => return Some(IfdEntry::IfdNew(iter.tag_code_maybe(tag))),
+                Ok(iter) => {
+                    let iter = iter.max_components(self.max_components).tag_code_maybe(tag);
+                    return Some(IfdEntry::IfdNew(iter));
+                }
                 Err(e) => {
                     tracing::warn!(?tag, ?e, "Create next/sub IFD failed");
                 }
@@ -646,6 +1702,14 @@ impl IfdIter {
         None
     }
 
+    /// A next/sub IFD must be located after the entries of the current IFD,
+    /// otherwise it points backward or overlaps the current IFD, which may
+    /// result in endless loops or garbage entries.
+    fn is_valid_new_ifd_offset(&self, offset: u32) -> bool {
+        let entries_end = self.offset as u64 + 2 + self.entry_num as u64 * IFD_ENTRY_SIZE as u64;
+        offset as u64 >= entries_end
+    }
+
     pub fn find_exif_iter(&self) -> Option<IfdIter> {
         let endian = self.endian;
         // find ExifOffset
@@ -655,19 +1719,44 @@ impl IfdIter {
                 complete::u16::<_, nom::error::Error<_>>(endian)(&self.input[pos..]).ok()?;
             if tag == ExifTag::ExifOffset.code() {
                 let entry_data = self.input.slice_checked(pos..pos + IFD_ENTRY_SIZE)?;
-                let (_, entry) = self.parse_tag_entry(entry_data)?;
+                let (_, _, entry) = self.parse_tag_entry(entry_data)?;
                 match entry {
                     IfdEntry::IfdNew(iter) => return Some(iter),
-                    IfdEntry::Entry(_) | IfdEntry::Err(_) => return None,
+                    IfdEntry::SubIfds(..)
+                    | IfdEntry::Entry(_)
+                    | IfdEntry::Text(_)
+                    | IfdEntry::Err(_) => return None,
                 }
             }
         }
         None
     }
 
+    /// Returns the raw `(components num, value or offset)` of the entry with
+    /// `tag`, without parsing its value.
+    pub fn find_raw_entry(&self, tag: u16) -> Option<(u32, u32)> {
+        let endian = self.endian;
+        for i in 0..self.entry_num {
+            let pos = self.pos + i as usize * IFD_ENTRY_SIZE;
+            let entry_data = self.input.slice_checked(pos..pos + IFD_ENTRY_SIZE)?;
+            let (_, (code, _, components_num, value_or_offset)) = tuple((
+                complete::u16::<_, nom::error::Error<_>>(endian),
+                complete::u16(endian),
+                complete::u32(endian),
+                complete::u32(endian),
+            ))(entry_data)
+            .ok()?;
+            if code == tag {
+                return Some((components_num, value_or_offset));
+            }
+        }
+        None
+    }
+
     pub fn find_tz_offset(&self) -> Option<String> {
         let iter = self.find_exif_iter()?;
         let mut offset = None;
+        let mut legacy_offset = None;
         for entry in iter {
             let Some(tag) = entry.0 else {
                 continue;
@@ -678,10 +1767,14 @@ impl IfdIter {
                 return entry.1.as_str().map(|x| x.to_owned());
             } else if tag.code() == ExifTag::OffsetTime.code() {
                 offset = entry.1.as_str().map(|x| x.to_owned());
+            } else if tag.code() == ExifTag::TimeZoneOffset.code() {
+                legacy_offset = entry.1.as_tz_offset_hours().map(format_tz_offset_hours);
             }
         }
 
-        offset
+        // Fall back to the legacy `TimeZoneOffset` tag, which was used by
+        // older cameras before the `OffsetTime*` tags were introduced.
+        offset.or(legacy_offset)
     }
 
     // Assume the current ifd is GPSInfo subifd.
@@ -705,7 +1798,7 @@ impl IfdIter {
                     }
                 }
                 ExifTag::GPSAltitudeRef => {
-                    if let Some(c) = entry.as_u8() {
+                    if let Some(c) = entry.as_altitude_ref() {
                         gps.altitude_ref = c;
                     }
                 }
@@ -742,6 +1835,69 @@ impl IfdIter {
                         gps.speed = Some((*v).into());
                     }
                 }
+                ExifTag::GPSDestLatitudeRef => {
+                    if let Some(c) = entry.as_char() {
+                        gps.dest_latitude_ref = Some(c);
+                    }
+                }
+                ExifTag::GPSDestLatitude => {
+                    if let Some(v) = entry.as_urational_array() {
+                        gps.dest_latitude = v.clone().try_into().ok();
+                    } else if let Some(v) = entry.as_irational_array() {
+                        gps.dest_latitude = (v.len() >= 3).then(|| v.iter().collect());
+                    }
+                }
+                ExifTag::GPSDestLongitudeRef => {
+                    if let Some(c) = entry.as_char() {
+                        gps.dest_longitude_ref = Some(c);
+                    }
+                }
+                ExifTag::GPSDestLongitude => {
+                    if let Some(v) = entry.as_urational_array() {
+                        gps.dest_longitude = v.clone().try_into().ok();
+                    } else if let Some(v) = entry.as_irational_array() {
+                        gps.dest_longitude = (v.len() >= 3).then(|| v.iter().collect());
+                    }
+                }
+                ExifTag::GPSDestBearingRef => {
+                    if let Some(c) = entry.as_char() {
+                        gps.dest_bearing_ref = Some(c);
+                    }
+                }
+                ExifTag::GPSDestBearing => {
+                    if let Some(v) = entry.as_urational() {
+                        gps.dest_bearing = Some(*v);
+                    } else if let Some(v) = entry.as_irational() {
+                        gps.dest_bearing = Some((*v).into());
+                    }
+                }
+                ExifTag::GPSDestDistanceRef => {
+                    if let Some(c) = entry.as_char() {
+                        gps.dest_distance_ref = Some(c);
+                    }
+                }
+                ExifTag::GPSDestDistance => {
+                    if let Some(v) = entry.as_urational() {
+                        gps.dest_distance = Some(*v);
+                    } else if let Some(v) = entry.as_irational() {
+                        gps.dest_distance = Some((*v).into());
+                    }
+                }
+                ExifTag::GPSVersionID => {
+                    if let Some(v) = entry.as_u8_slice() {
+                        gps.version_id = v.try_into().ok();
+                    }
+                }
+                ExifTag::GPSTimeStamp => {
+                    if let Some(v) = entry.as_urational_array() {
+                        gps.time_stamp = v.as_slice().try_into().ok();
+                    }
+                }
+                ExifTag::GPSDateStamp => {
+                    if let Some(v) = entry.as_str() {
+                        gps.date_stamp = Some(v.to_string());
+                    }
+                }
                 _ => (),
             }
         }
@@ -765,25 +1921,17 @@ impl IfdIter {
 #[derive(Debug)]
 pub(crate) enum IfdEntry {
     IfdNew(IfdIter), // ifd index
+    // Value of the `SubIFDs` entry, and the sub-IFDs it points to
+    SubIfds(EntryValue, Vec<IfdIter>),
     Entry(EntryValue),
+    // Raw data of a text value, not decoded yet
+    Text(PartialVec),
     Err(ParseEntryError),
 }
 
 impl IfdEntry {
-    pub fn as_u8(&self) -> Option<u8> {
-        if let IfdEntry::Entry(EntryValue::U8(v)) = self {
-            Some(*v)
-        } else {
-            None
-        }
-    }
-
     pub fn as_char(&self) -> Option<char> {
-        if let IfdEntry::Entry(EntryValue::Text(s)) = self {
-            s.chars().next()
-        } else {
-            None
-        }
+        self.as_str().and_then(|s| s.chars().next())
     }
 
     fn as_irational(&self) -> Option<&IRational> {
@@ -818,13 +1966,54 @@ impl IfdEntry {
         }
     }
 
-    fn as_str(&self) -> Option<&str> {
-        if let IfdEntry::Entry(e) = self {
-            e.as_str()
+    // `GPSAltitudeRef` is defined as a BYTE in 0..=3, but some writers use
+    // other integer types or even a rational.
+    fn as_altitude_ref(&self) -> Option<u8> {
+        let v = match self {
+            IfdEntry::Entry(EntryValue::U8(v)) => *v as u32,
+            IfdEntry::Entry(EntryValue::U16(v)) => *v as u32,
+            IfdEntry::Entry(EntryValue::U32(v)) => *v,
+            IfdEntry::Entry(EntryValue::URational(v)) if v.1 != 0 && v.0 % v.1 == 0 => v.0 / v.1,
+            _ => *self.as_u8_slice()?.first()? as u32,
+        };
+        if v <= 3 {
+            Some(v as u8)
         } else {
+            tracing::warn!(v, "invalid GPSAltitudeRef");
             None
         }
     }
+
+    fn as_u8_slice(&self) -> Option<&[u8]> {
+        match self {
+            IfdEntry::Entry(EntryValue::U8Array(v) | EntryValue::Undefined(v)) => Some(v),
+            _ => None,
+        }
+    }
+
+    // `TimeZoneOffset` has 1 or 2 components, the first one is the offset (in
+    // hours) of DateTimeOriginal.
+    fn as_tz_offset_hours(&self) -> Option<i16> {
+        let hours = match self {
+            IfdEntry::Entry(EntryValue::I16(v)) => *v,
+            IfdEntry::Entry(EntryValue::I16Array(v)) => *v.first()?,
+            _ => return None,
+        };
+        (-12..=14).contains(&hours).then_some(hours)
+    }
+
+    fn as_str(&self) -> Option<&str> {
+        match self {
+            IfdEntry::Entry(e) => e.as_str(),
+            IfdEntry::Text(text) => get_cstr_ref(text).ok(),
+            _ => None,
+        }
+    }
+}
+
+fn format_tz_offset_hours(hours: i16) -> String {
+    let sign = if hours < 0 { '-' } else { '+' };
+    format!("{sign}{:02}:00", hours.abs())
 }
 
 pub(crate) const SUBIFD_TAGS: &[u16] = &[ExifTag::ExifOffset.code(), ExifTag::GPSInfo.code()];
@@ -832,8 +2021,16 @@ pub(crate) const SUBIFD_TAGS: &[u16] = &[ExifTag::ExifOffset.code(), ExifTag::GP
 impl Iterator for IfdIter {
     type Item = (Option<ExifTagCode>, IfdEntry);
 
-    #[tracing::instrument(skip(self))]
     fn next(&mut self) -> Option<Self::Item> {
+        self.next_entry().map(|(tag, _, entry)| (tag, entry))
+    }
+}
+
+impl IfdIter {
+    /// Like [`Iterator::next`], but returns the data format of the entry as
+    /// well, which is `None` for the next IFD link & invalid data formats.
+    #[cfg_attr(feature = "trace_spans", tracing::instrument(skip(self)))]
+    fn next_entry(&mut self) -> Option<(Option<ExifTagCode>, Option<DataFormat>, IfdEntry)> {
         // tracing::debug!(
         //     ifd = self.ifd_idx,
         //     index = self.index,
@@ -868,20 +2065,47 @@ impl Iterator for IfdIter {
                 return None;
             }
 
+            // Only IFDs of the main chain (IFD0 -> IFD1 -> ...) are linked,
+            // a sub-IFD's next IFD offset is ignored, otherwise the linked
+            // IFD would be numbered as IFD1 and collide with the thumbnail.
+            if self.tag_code.is_some() {
+                tracing::warn!(?self, offset, "ignore next IFD offset of sub-IFD");
+                return None;
+            }
+
             return self
                 .new_ifd_iter(self.ifd_idx + 1, offset, None)
-                .map(|x| (None, x));
+                .map(|x| (None, None, x));
         }
 
-        let entry_data = self
-            .input
-            .slice_checked(self.pos..self.pos + IFD_ENTRY_SIZE)?;
-        self.index += 1;
-        self.pos += IFD_ENTRY_SIZE;
+        let entry_data = loop {
+            let entry_data = self
+                .input
+                .slice_checked(self.pos..self.pos + IFD_ENTRY_SIZE)?;
+            self.index += 1;
+            self.pos += IFD_ENTRY_SIZE;
+
+            // An entry without any component has no value at all, skip it
+            // rather than reporting a misleading zero value.
+            let (_, components_num) =
+                complete::u32::<_, nom::error::Error<_>>(endian)(&entry_data[4..]).ok()?; // Safe-slice
+            if components_num != 0 {
+                break entry_data;
+            }
+            tracing::debug!(
+                ifd = self.ifd_idx,
+                index = self.index - 1,
+                "skip empty entry"
+            );
+            if self.index == self.entry_num {
+                // Go on with the next IFD
+                return self.next_entry();
+            }
+        };
 
-        let (tag, res) = self.parse_tag_entry(entry_data)?;
+        let (tag, data_format, res) = self.parse_tag_entry(entry_data)?;
 
-        Some((Some(tag.into()), res)) // Safe-slice
+        Some((Some(tag.into()), data_format, res))
     }
 }
 
@@ -892,7 +2116,12 @@ mod tests {
     use crate::exif::input_into_iter;
     use crate::file::MimeImage;
     use crate::slice::SubsliceRange;
-    use crate::testkit::read_sample;
+    use crate::testkit::{read_sample, TiffBuilder, TiffValue};
+    use crate::EntryValue;
+    use crate::ExifTag::{self, *};
+    use crate::IfdKind;
+    use crate::ParsedExifEntry;
+    use nom::number::Endianness;
     use test_case::test_case;
 
     #[test_case("exif.jpg", "+08:00", MimeImage::Jpeg)]
@@ -904,12 +2133,838 @@ mod tests {
         let buf = read_sample(path).unwrap();
         let (data, _) = extract_exif_with_mime(img_type, &buf, None).unwrap();
         let subslice_in_range = data.and_then(|x| buf.subslice_in_range(x)).unwrap();
-        let iter = input_into_iter((buf, subslice_in_range), None).unwrap();
+        let iter = input_into_iter((buf, subslice_in_range), None, false).unwrap();
         let expect = if tz.is_empty() {
             None
         } else {
             Some(tz.to_string())
         };
-        assert_eq!(iter.tz, expect);
+        assert_eq!(iter.timezone(), expect.as_deref());
+    }
+
+    #[test]
+    fn exif_iter_ifd_kind() {
+        let _ = tracing_subscriber::fmt().with_test_writer().try_init();
+
+        let buf = read_sample("exif.jpg").unwrap();
+        let (data, _) = extract_exif_with_mime(MimeImage::Jpeg, &buf, None).unwrap();
+        let range = data.and_then(|x| buf.subslice_in_range(x)).unwrap();
+        let iter = input_into_iter((buf, range), None, false).unwrap();
+
+        let kinds: Vec<(ExifTag, IfdKind)> = iter
+            .filter_map(|x| x.tag().map(|t| (t, x.ifd_kind())))
+            .collect();
+        for (tag, kind) in [
+            (Make, IfdKind::Main),
+            (ExifOffset, IfdKind::Main),
+            (GPSInfo, IfdKind::Main),
+            (ExposureTime, IfdKind::Exif),
+            (DateTimeOriginal, IfdKind::Exif),
+            (GPSLatitudeRef, IfdKind::Gps),
+            (GPSAltitude, IfdKind::Gps),
+            (ThumbnailOffset, IfdKind::Thumbnail),
+        ] {
+            assert!(kinds.contains(&(tag, kind)), "{tag} {kind:?}");
+        }
+    }
+
+    #[test]
+    fn parsed_entry_tag_predicates() {
+        let _ = tracing_subscriber::fmt().with_test_writer().try_init();
+
+        let buf = read_sample("exif.jpg").unwrap();
+        let (data, _) = extract_exif_with_mime(MimeImage::Jpeg, &buf, None).unwrap();
+        let range = data.and_then(|x| buf.subslice_in_range(x)).unwrap();
+        let iter = input_into_iter((buf, range), None, false).unwrap();
+
+        let tags = |f: fn(&ParsedExifEntry) -> bool| {
+            let mut tags: Vec<ExifTag> = iter.clone().filter(f).filter_map(|x| x.tag()).collect();
+            tags.sort_by_key(|x| x.code());
+            tags
+        };
+        assert_eq!(
+            tags(ParsedExifEntry::is_gps_tag),
+            [
+                GPSLatitudeRef,
+                GPSLatitude,
+                GPSLongitudeRef,
+                GPSLongitude,
+                GPSAltitudeRef,
+                GPSAltitude,
+                GPSTimeStamp,
+                GPSProcessingMethod,
+                GPSDateStamp,
+            ]
+        );
+        assert_eq!(
+            tags(ParsedExifEntry::is_thumbnail_tag),
+            [ThumbnailOffset, ThumbnailLength]
+        );
+        assert_eq!(
+            tags(ParsedExifEntry::is_datetime_tag),
+            [ModifyDate, DateTimeOriginal, CreateDate]
+        );
+    }
+
+    fn make_corrupt_tiff() -> Vec<u8> {
+        let mut tiff = TiffBuilder::new(Endianness::Little);
+        let ifd1 = tiff.ifd();
+        tiff.short(0, 0x0100, &[100])
+            // Make: data offset is out of range
+            .entry(0, 0x010f, 2, 100, TiffValue::Field(0xfff0))
+            // invalid tag
+            .short(0, 0, &[0])
+            .text(0, 0x0110, "ab")
+            .link(0, ifd1)
+            .short(ifd1, 0x0100, &[16]);
+        tiff.build()
+    }
+
+    #[test_case(false, &[(0, ImageWidth), (0, Make)])]
+    #[test_case(true, &[(0, ImageWidth), (0, Make), (0, Model), (1, ImageWidth)])]
+    fn exif_iter_continue_on_error(enable: bool, expect: &[(usize, ExifTag)]) {
+        let _ = tracing_subscriber::fmt().with_test_writer().try_init();
+
+        let iter = input_into_iter(make_corrupt_tiff(), None, false)
+            .unwrap()
+            .continue_on_error(enable);
+        let entries: Vec<_> = iter.clone().collect();
+        assert_eq!(
+            entries
+                .iter()
+                .map(|x| (x.ifd_index(), x.tag().unwrap()))
+                .collect::<Vec<_>>(),
+            expect
+        );
+        assert!(entries[1].get_result().is_err());
+
+        let exif: crate::Exif = iter.into();
+        assert!(exif.get(Make).is_none());
+        if enable {
+            assert_eq!(exif.get(Model).unwrap().as_str().unwrap(), "ab");
+            assert_eq!(exif.get_by_ifd_tag_code(1, 0x0100).unwrap(), &16u16.into());
+        } else {
+            assert!(exif.get(Model).is_none());
+        }
+    }
+
+    #[test]
+    fn exif_iter_ifd_numbering() {
+        let _ = tracing_subscriber::fmt().with_test_writer().try_init();
+
+        let buf = read_sample("exif.jpg").unwrap();
+        let (data, _) = extract_exif_with_mime(MimeImage::Jpeg, &buf, None).unwrap();
+        let range = data.and_then(|x| buf.subslice_in_range(x)).unwrap();
+        let iter = input_into_iter((buf, range), None, false).unwrap();
+        let mut ifds: Vec<(usize, IfdKind)> = Vec::new();
+        for entry in iter {
+            if !ifds.contains(&(entry.ifd_index(), entry.ifd_kind())) {
+                ifds.push((entry.ifd_index(), entry.ifd_kind()));
+            }
+        }
+        assert_eq!(
+            ifds,
+            [
+                (0, IfdKind::Main),
+                (0, IfdKind::Exif),
+                (0, IfdKind::Gps),
+                (1, IfdKind::Thumbnail),
+            ]
+        );
+
+        // Both IFD0 & the Exif sub-IFD link to IFD1, only the former is
+        // followed.
+        let mut tiff = TiffBuilder::new(Endianness::Little);
+        let exif = tiff.ifd();
+        let ifd1 = tiff.ifd();
+        tiff.sub_ifd(0, 0x8769, exif)
+            .short(0, 0x0100, &[100])
+            .link(0, ifd1)
+            .short(exif, 0x8827, &[200])
+            .link(exif, ifd1)
+            .short(ifd1, 0x0100, &[16]);
+
+        let iter = input_into_iter(tiff.build(), None, false).unwrap();
+        let entries: Vec<_> = iter
+            .map(|x| (x.ifd_index(), x.ifd_kind(), x.tag().unwrap()))
+            .collect();
+        assert_eq!(
+            entries,
+            [
+                (0, IfdKind::Main, ExifOffset),
+                (0, IfdKind::Exif, ISOSpeedRatings),
+                (0, IfdKind::Main, ImageWidth),
+                (1, IfdKind::Thumbnail, ImageWidth),
+            ]
+        );
+    }
+
+    /// Builds a little endian TIFF block, IFD0 at offset 8 holds ExifOffset
+    /// (`exif_offset`) & ImageWidth, the Exif sub-IFD at offset 38 holds
+    /// ISOSpeedRatings.
+    fn make_tiff(exif_offset: u32) -> Vec<u8> {
+        let mut tiff = TiffBuilder::new(Endianness::Little);
+        let exif = tiff.ifd();
+        tiff.entry(0, 0x8769, 4, 1, TiffValue::Field(exif_offset))
+            .short(0, 0x0100, &[100])
+            .short(exif, 0x8827, &[200]);
+        assert_eq!(tiff.ifd_offset(exif), 38);
+        tiff.build()
+    }
+
+    /// Builds a little endian TIFF block, whose ifd1 holds `Compression`,
+    /// `ThumbnailOffset` & `ThumbnailLength`, followed by a 16-byte fake JPEG
+    /// at offset 68.
+    fn make_tiff_with_thumbnail(compression: u16, offset: u32, length: u32) -> Vec<u8> {
+        let mut tiff = TiffBuilder::new(Endianness::Little);
+        let ifd1 = tiff.ifd();
+        let mut jpeg = vec![0xff, 0xd8];
+        jpeg.extend_from_slice(&[0x55; 12]);
+        jpeg.extend_from_slice(&[0xff, 0xd9]);
+        let jpeg = tiff.blob(&jpeg);
+        tiff.short(0, 0x0100, &[100])
+            .link(0, ifd1)
+            .short(ifd1, 0x0103, &[compression])
+            .long(ifd1, 0x0201, &[offset])
+            .long(ifd1, 0x0202, &[length]);
+        assert_eq!(tiff.blob_offset(jpeg), 68);
+        tiff.build()
+    }
+
+    #[test_case(6, 68, 16, true; "jpeg")]
+    #[test_case(1, 68, 16, false; "uncompressed")]
+    #[test_case(6, 68, 17, false; "out of range")]
+    #[test_case(6, u32::MAX, 16, false; "offset overflow")]
+    #[test_case(6, 70, 10, false; "not a jpeg")]
+    #[test_case(6, 68, 0, false; "empty")]
+    fn extract_thumbnail(compression: u16, offset: u32, length: u32, found: bool) {
+        let _ = tracing_subscriber::fmt().with_test_writer().try_init();
+
+        let buf = make_tiff_with_thumbnail(compression, offset, length);
+        let expect = found.then(|| buf[68..].to_vec());
+        let iter = input_into_iter(buf, None, false).unwrap();
+        assert_eq!(iter.extract_thumbnail(), expect);
+
+        let exif: crate::Exif = iter.into();
+        let range = exif.thumbnail_range();
+        assert_eq!(range.is_some(), compression == 6 && length > 0);
+    }
+
+    #[test]
+    fn extract_thumbnail_from_jpeg() {
+        let _ = tracing_subscriber::fmt().with_test_writer().try_init();
+
+        let buf = read_sample("exif.jpg").unwrap();
+        let (data, _) = extract_exif_with_mime(MimeImage::Jpeg, &buf, None).unwrap();
+        let data = data.unwrap();
+        let range = buf.subslice_in_range(data).unwrap();
+        let iter = input_into_iter((buf.clone(), range.clone()), None, false).unwrap();
+
+        let thumbnail = iter.extract_thumbnail().unwrap();
+        let exif: crate::Exif = iter.into();
+        let thumbnail_range = exif.thumbnail_range().unwrap();
+        let start = range.start + thumbnail_range.start;
+        assert_eq!(thumbnail, buf[start..start + thumbnail_range.len()]);
+        assert!(thumbnail.ends_with(&[0xff, 0xd9]));
+    }
+
+    #[test_case("exif.heic", MimeImage::Heic, true)]
+    #[test_case("exif.jpg", MimeImage::Jpeg, false)]
+    fn parse_apple_maker_note(path: &str, img_type: MimeImage, found: bool) {
+        let _ = tracing_subscriber::fmt().with_test_writer().try_init();
+
+        let buf = read_sample(path).unwrap();
+        let (data, _) = extract_exif_with_mime(img_type, &buf, None).unwrap();
+        let range = data.and_then(|x| buf.subslice_in_range(x)).unwrap();
+        let iter = input_into_iter((buf, range), None, false).unwrap();
+
+        let note = iter.parse_maker_note().unwrap();
+        let Some(note) = note else {
+            assert!(!found);
+            return;
+        };
+        assert!(found);
+        // `0x0023` is skipped, since arrays of signed longs are unsupported
+        assert_eq!(note.len(), 44);
+        assert!(note.get(0x0023).is_none());
+        assert_eq!(note.get(0x0001), Some(&EntryValue::I32(14)));
+        assert_eq!(note.get(0x002e), Some(&EntryValue::I32(1)));
+
+        // Value offsets are relative to the start of the maker note
+        assert_eq!(
+            note.content_identifier(),
+            Some("242684F6-0839-44E0-8E39-A8FE43182C29")
+        );
+        assert_eq!(note.hdr_headroom(), Some(27871.0 / 23463.0));
+        let range = note.focus_distance_range().unwrap();
+        assert_eq!(
+            range.iter().map(|x| x.as_float()).collect::<Vec<_>>(),
+            [4.5, 807.0 / 256.0]
+        );
+        assert_eq!(note.run_time(), Some(155_635.867_414_25));
+    }
+
+    #[test]
+    fn skip_empty_entries() {
+        let _ = tracing_subscriber::fmt().with_test_writer().try_init();
+
+        // Model & Orientation have no components
+        let mut tiff = TiffBuilder::new(Endianness::Little);
+        let ifd1 = tiff.ifd();
+        tiff.entry(0, 0x0110, 2, 0, TiffValue::Field(0))
+            .short(0, 0x0100, &[100])
+            .entry(0, 0x0112, 3, 0, TiffValue::Field(0))
+            .link(0, ifd1)
+            .short(ifd1, 0x0101, &[50]);
+        let buf = tiff.build();
+
+        let iter = input_into_iter(buf, None, false).unwrap();
+        let entries: Vec<_> = iter
+            .clone_and_rewind()
+            .map(|mut x| (x.ifd_index(), x.tag().unwrap(), x.take_value().unwrap()))
+            .collect();
+        assert_eq!(
+            entries,
+            [
+                (0, ImageWidth, EntryValue::U16(100)),
+                (1, ImageHeight, EntryValue::U16(50)),
+            ]
+        );
+
+        let exif: crate::Exif = iter.into();
+        assert_eq!(exif.get(Orientation), None);
+        assert_eq!(exif.get(Model), None);
+    }
+
+    #[test]
+    fn max_entry_components() {
+        let _ = tracing_subscriber::fmt().with_test_writer().try_init();
+
+        let mut tiff = TiffBuilder::new(Endianness::Little);
+        tiff.short(0, 0x0100, &[100])
+            .short(0, 0x0102, &[8, 8, 8])
+            // Far more components than the data holds
+            .entry(0, 0x0111, 4, 0x4000_0000, TiffValue::Field(8));
+        let buf = tiff.build();
+
+        let oks = |max| {
+            let mut iter = input_into_iter(buf.clone(), None, false).unwrap();
+            iter.set_max_entry_components(max);
+            iter.continue_on_error(true)
+                .map(|x| (x.tag().unwrap(), x.get_result().is_ok()))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            oks(None),
+            [
+                (ImageWidth, true),
+                (BitsPerSample, true),
+                (StripOffsets, false)
+            ]
+        );
+        assert_eq!(
+            oks(Some(2)),
+            [
+                (ImageWidth, true),
+                (BitsPerSample, false),
+                (StripOffsets, false)
+            ]
+        );
+    }
+
+    #[test]
+    fn entry_data_format() {
+        let _ = tracing_subscriber::fmt().with_test_writer().try_init();
+
+        let buf = read_sample("exif.jpg").unwrap();
+        let (data, _) = extract_exif_with_mime(MimeImage::Jpeg, &buf, None).unwrap();
+        let iter = input_into_iter(data.unwrap().to_vec(), None, false).unwrap();
+        let formats: Vec<_> = iter
+            .filter(|x| {
+                matches!(
+                    x.tag(),
+                    Some(Make | ExifOffset | ExposureTime | ISOSpeedRatings | GPSLatitude)
+                )
+            })
+            .map(|x| (x.tag().unwrap(), x.data_format()))
+            .collect();
+        assert_eq!(
+            formats,
+            [
+                (ExifOffset, Some("long")),
+                (ISOSpeedRatings, Some("short")),
+                (ExposureTime, Some("rational")),
+                // Should be "rational" per the spec
+                (GPSLatitude, Some("srational")),
+                (Make, Some("ascii")),
+            ]
+        );
+    }
+
+    #[test]
+    fn take_tags() {
+        let _ = tracing_subscriber::fmt().with_test_writer().try_init();
+
+        let buf = read_sample("exif.jpg").unwrap();
+        let (data, _) = extract_exif_with_mime(MimeImage::Jpeg, &buf, None).unwrap();
+        let iter = input_into_iter(data.unwrap().to_vec(), None, false).unwrap();
+        let exif = crate::Exif::from(iter.clone_and_rewind());
+
+        let tags = [
+            Make,
+            DateTimeOriginal,
+            Make,
+            GPSLatitudeRef,
+            ImageDescription,
+        ];
+        let values = iter.clone_and_rewind().take_tags(&tags);
+        // ImageDescription is absent
+        assert_eq!(values.len(), 3);
+        for tag in [Make, DateTimeOriginal, GPSLatitudeRef] {
+            assert_eq!(values.get(&tag), exif.get(tag), "{tag}");
+        }
+
+        assert!(iter.take_tags(&[]).is_empty());
+    }
+
+    /// Builds a NEF like TIFF, IFD0 holds ImageWidth (160), Make & a SubIFDs
+    /// entry (with data `format`) pointing to one sub-IFD per item of
+    /// `widths`, each of which holds an ImageWidth. The sub-IFDs follow
+    /// `padding` bytes.
+    fn make_nef(widths: &[u16], format: u16, padding: u32) -> Vec<u8> {
+        let mut tiff = TiffBuilder::new(Endianness::Little);
+        tiff.blob(&vec![0; padding as usize]);
+        let sub_ifds = widths
+            .iter()
+            .map(|width| {
+                let ifd = tiff.ifd();
+                tiff.short(ifd, 0x0100, &[*width]);
+                ifd
+            })
+            .collect::<Vec<_>>();
+        tiff.short(0, 0x0100, &[160])
+            .text(0, 0x010f, "NIKON CORPORATION")
+            .entry(
+                0,
+                0x014a,
+                format,
+                widths.len() as u32,
+                TiffValue::Ifds(sub_ifds),
+            );
+        tiff.build()
+    }
+
+    #[test_case(&[8256])]
+    #[test_case(&[8256, 1024])]
+    #[test_case(&[8256, 1024, 640, 320, 160, 80, 40, 20, 10, 5])]
+    fn sub_ifds(widths: &[u16]) {
+        let _ = tracing_subscriber::fmt().with_test_writer().try_init();
+
+        let nef = make_nef(widths, 4, 0);
+        let mime = crate::file::Mime::try_from(&nef[..]).unwrap();
+        assert_eq!(mime, crate::file::Mime::Image(MimeImage::Tiff));
+
+        let iter = input_into_iter(nef, None, false).unwrap();
+        let entries: Vec<_> = iter
+            .clone_and_rewind()
+            .map(|x| (x.ifd_kind(), x.tag().unwrap(), x.get_value().cloned()))
+            .collect();
+
+        // The depth is bounded by `MAX_IFD_DEPTH`, IFD0 takes one level
+        let num = widths.len().min(super::MAX_IFD_DEPTH - 1);
+        let mut expect = vec![
+            (IfdKind::Main, ImageWidth, Some(EntryValue::U16(160))),
+            (
+                IfdKind::Main,
+                Make,
+                Some(EntryValue::Text("NIKON CORPORATION".into())),
+            ),
+        ];
+        // IFD0 & Make take 60 bytes, the offsets follow unless it's inline
+        let first = match widths.len() {
+            1 => 68,
+            n => 68 + n as u32 * 4,
+        };
+        let offsets: Vec<u32> = (0..widths.len() as u32).map(|i| first + i * 18).collect();
+        let offsets = match offsets[..] {
+            [offset] => EntryValue::U32(offset),
+            _ => EntryValue::U32Array(offsets),
+        };
+        expect.push((IfdKind::Main, SubIFDs, Some(offsets)));
+        expect.extend(
+            widths[..num]
+                .iter()
+                .map(|w| (IfdKind::SubIfd, ImageWidth, Some(EntryValue::U16(*w)))),
+        );
+        assert_eq!(entries, expect);
+
+        // Sub-IFD entries don't override the ones of IFD0
+        let exif = crate::Exif::from(iter);
+        assert_eq!(
+            exif.get(Make).and_then(|x| x.as_str()),
+            Some("NIKON CORPORATION")
+        );
+        assert_eq!(exif.get(ImageWidth), Some(&EntryValue::U16(160)));
+    }
+
+    #[test_case(4)]
+    #[test_case(13; "ifd format")]
+    fn sub_ifds_media_parser(format: u16) {
+        let _ = tracing_subscriber::fmt().with_test_writer().try_init();
+
+        // The sub-IFDs are located beyond the first read of the parser
+        let nef = make_nef(&[8256, 1024], format, 16 * 1024);
+        let mut parser = crate::MediaParser::new();
+        let ms = crate::MediaSource::seekable(std::io::Cursor::new(nef)).unwrap();
+        let iter: super::ExifIter = parser.parse(ms).unwrap();
+        let widths: Vec<_> = iter
+            .filter(|x| x.ifd_kind() == IfdKind::SubIfd)
+            .map(|x| (x.tag().unwrap(), x.get_value().cloned()))
+            .collect();
+        assert_eq!(
+            widths,
+            [
+                (ImageWidth, Some(EntryValue::U16(8256))),
+                (ImageWidth, Some(EntryValue::U16(1024)))
+            ]
+        );
+    }
+
+    #[test_case(38, &[ExifOffset, ISOSpeedRatings, ImageWidth])]
+    #[test_case(8, &[ExifOffset, ImageWidth])]
+    #[test_case(4, &[ExifOffset, ImageWidth])]
+    #[test_case(10, &[ExifOffset, ImageWidth])]
+    #[test_case(30, &[ExifOffset, ImageWidth])]
+    fn exif_iter_bad_subifd_offset(exif_offset: u32, expect: &[ExifTag]) {
+        let _ = tracing_subscriber::fmt().with_test_writer().try_init();
+
+        let buf = make_tiff(exif_offset);
+        let iter = input_into_iter(buf, None, false).unwrap();
+        let tags: Vec<ExifTag> = iter.map(|x| x.tag().unwrap()).collect();
+        assert_eq!(tags, expect);
+    }
+
+    #[test_case(false, None)]
+    #[test_case(true, Some(&[ExifOffset, ISOSpeedRatings, ImageWidth]))]
+    fn exif_iter_endian_mismatch_header(swap: bool, expect: Option<&[ExifTag]>) {
+        let _ = tracing_subscriber::fmt().with_test_writer().try_init();
+
+        // Big-endian marker followed by a fully little-endian header & body
+        let mut buf = make_tiff(38);
+        buf[..2].copy_from_slice(b"MM");
+
+        let res = input_into_iter(buf, None, swap);
+        match expect {
+            Some(expect) => {
+                let iter = res.unwrap();
+                assert_eq!(iter.tiff_header.endian, Endianness::Little);
+                let tags: Vec<ExifTag> = iter.map(|x| x.tag().unwrap()).collect();
+                assert_eq!(tags, expect);
+            }
+            None => {
+                res.unwrap_err();
+            }
+        }
+    }
+
+    #[test_case(false, None)]
+    #[test_case(true, Some(&[ExifOffset, ISOSpeedRatings, ImageWidth]))]
+    fn exif_iter_endian_mismatch(swap: bool, expect: Option<&[ExifTag]>) {
+        let _ = tracing_subscriber::fmt().with_test_writer().try_init();
+
+        // Big-endian header followed by little-endian IFD data
+        let mut buf = make_tiff(38);
+        buf[..8].copy_from_slice(b"MM\0*\0\0\0\x08");
+
+        let res = input_into_iter(buf, None, swap);
+        match expect {
+            Some(expect) => {
+                let tags: Vec<ExifTag> = res.unwrap().map(|x| x.tag().unwrap()).collect();
+                assert_eq!(tags, expect);
+            }
+            None => {
+                res.unwrap_err();
+            }
+        }
+    }
+
+    #[test]
+    fn exif_iter_into_iter_ref() {
+        let buf = make_tiff(38);
+        let mut iter = input_into_iter(buf, None, false).unwrap();
+        assert_eq!(iter.next().unwrap().tag(), Some(ExifOffset));
+
+        let tags: Vec<ExifTag> = (&iter).into_iter().map(|x| x.tag().unwrap()).collect();
+        assert_eq!(tags, [ExifOffset, ISOSpeedRatings, ImageWidth]);
+
+        let mut count = 0;
+        for _ in &iter {
+            count += 1;
+        }
+        assert_eq!(count, 3);
+
+        // The original's iteration index is untouched
+        assert_eq!(iter.next().unwrap().tag(), Some(ISOSpeedRatings));
+    }
+
+    #[test]
+    fn exif_iter_gps_dest() {
+        let _ = tracing_subscriber::fmt().with_test_writer().try_init();
+
+        let mut tiff = TiffBuilder::new(Endianness::Little);
+        let gps = tiff.ifd();
+        tiff.sub_ifd(0, 0x8825, gps)
+            .text(gps, 0x0017, "T")
+            .rational(gps, 0x0018, &[(18050, 100)]);
+        let buf = tiff.build();
+
+        let iter = input_into_iter(buf, None, false).unwrap();
+        let gps = iter.parse_gps_info().unwrap().unwrap();
+        assert_eq!(gps.dest_bearing_ref, Some('T'));
+        assert_eq!(gps.dest_bearing_decimal(), Some(180.5));
+        assert_eq!(gps.dest_latitude, None);
+    }
+
+    #[test]
+    fn exif_iter_peek() {
+        let _ = tracing_subscriber::fmt().with_test_writer().try_init();
+
+        let buf = read_sample("exif.jpg").unwrap();
+        let (data, _) = extract_exif_with_mime(MimeImage::Jpeg, &buf, None).unwrap();
+        let range = data.and_then(|x| buf.subslice_in_range(x)).unwrap();
+        let iter = input_into_iter((buf, range), None, false).unwrap();
+        let key = |x: &ParsedExifEntry| (x.ifd_index(), x.tag_code());
+        let expect = iter.clone().map(|x| key(&x)).collect::<Vec<_>>();
+
+        let mut iter = iter;
+        let mut res = Vec::new();
+        while let Some(peeked) = iter.peek().map(key) {
+            assert_eq!(iter.peek().map(key), Some(peeked));
+            let entry = iter.next().unwrap();
+            assert_eq!(key(&entry), peeked);
+            res.push(peeked);
+        }
+        assert!(iter.peek().is_none());
+        assert!(iter.next().is_none());
+        assert_eq!(res, expect);
+
+        // Clones are rewound, while the original keeps its peeked entry
+        let mut iter = iter.clone_and_rewind();
+        iter.peek();
+        assert_eq!(iter.clone().count(), expect.len());
+        assert_eq!(iter.count(), expect.len());
+    }
+
+    #[test_case("exif.jpg", &[0, 1])]
+    #[test_case("exif.heic", &[0])]
+    fn exif_iter_grouped(path: &str, ifds: &[usize]) {
+        let _ = tracing_subscriber::fmt().with_test_writer().try_init();
+
+        let buf = read_sample(path).unwrap();
+        let mime = if path.ends_with(".jpg") {
+            MimeImage::Jpeg
+        } else {
+            MimeImage::Heic
+        };
+        let (data, _) = extract_exif_with_mime(mime, &buf, None).unwrap();
+        let range = data.and_then(|x| buf.subslice_in_range(x)).unwrap();
+        let iter = input_into_iter((buf, range), None, false).unwrap();
+        let key = |x: &ParsedExifEntry| (x.ifd_index(), x.tag_code());
+        let expect = iter.clone().map(|x| key(&x)).collect::<Vec<_>>();
+
+        let groups = iter.grouped().collect::<Vec<_>>();
+        assert_eq!(groups.iter().map(|x| x.0).collect::<Vec<_>>(), ifds);
+        for (ifd, entries) in groups.iter() {
+            assert!(!entries.is_empty());
+            assert!(entries.iter().all(|x| x.ifd_index() == *ifd));
+        }
+        let res = groups
+            .iter()
+            .flat_map(|x| x.1.iter().map(key))
+            .collect::<Vec<_>>();
+        assert_eq!(res, expect);
+    }
+
+    #[test]
+    fn exif_iter_tiff_descriptive_tags() {
+        let _ = tracing_subscriber::fmt().with_test_writer().try_init();
+
+        // Artist & DocumentName are inline text values
+        let mut tiff = TiffBuilder::new(Endianness::Little);
+        tiff.text(0, 0x013b, "Ann").text(0, 0x010d, "doc");
+        let buf = tiff.build();
+
+        let iter = input_into_iter(buf, None, false).unwrap();
+        let entries = iter
+            .map(|x| {
+                (
+                    x.tag(),
+                    x.get_value().and_then(|v| v.as_str()).map(str::to_owned),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            entries,
+            [
+                (Some(Artist), Some("Ann".to_string())),
+                (Some(DocumentName), Some("doc".to_string())),
+            ]
+        );
+        assert_eq!(Artist.to_string(), "Artist");
+    }
+
+    #[test]
+    fn exif_iter_gps_version() {
+        let _ = tracing_subscriber::fmt().with_test_writer().try_init();
+
+        let mut tiff = TiffBuilder::new(Endianness::Little);
+        let gps = tiff.ifd();
+        tiff.sub_ifd(0, 0x8825, gps)
+            .byte(gps, 0x0000, &[2, 3, 0, 0]);
+        let buf = tiff.build();
+
+        let iter = input_into_iter(buf, None, false).unwrap();
+        let gps = iter.parse_gps_info().unwrap().unwrap();
+        assert_eq!(gps.version_id, Some([2, 3, 0, 0]));
+        assert_eq!(gps.version().as_deref(), Some("2.3.0.0"));
+
+        let entry = iter.into_iter().find(|x| x.is_gps_tag()).unwrap();
+        assert_eq!(entry.tag(), Some(GPSVersionID));
+        assert_eq!(
+            entry.get_value(),
+            Some(&EntryValue::U8Array(vec![2, 3, 0, 0]))
+        );
+    }
+
+    #[test_case(1, &[1], 1, Some(-100.0))]
+    #[test_case(1, &[2], 2, Some(100.0))]
+    #[test_case(3, &[3, 0], 3, Some(-100.0))]
+    #[test_case(4, &[1, 0, 0, 0], 1, Some(-100.0))]
+    #[test_case(5, &[3, 0, 0, 0, 1, 0, 0, 0], 3, Some(-100.0))]
+    #[test_case(7, &[2], 2, Some(100.0))]
+    #[test_case(1, &[9], 0, Some(100.0))]
+    fn exif_iter_gps_altitude_ref(
+        format: u16,
+        value: &[u8],
+        altitude_ref: u8,
+        altitude: Option<f64>,
+    ) {
+        let _ = tracing_subscriber::fmt().with_test_writer().try_init();
+
+        // GPSAltitudeRef of `format`, whose only component is `value`
+        let mut tiff = TiffBuilder::new(Endianness::Little);
+        let gps = tiff.ifd();
+        tiff.sub_ifd(0, 0x8825, gps)
+            .entry(gps, 0x0005, format, 1, TiffValue::Data(value.to_vec()))
+            .rational(gps, 0x0006, &[(100, 1)]);
+        let buf = tiff.build();
+
+        let iter = input_into_iter(buf, None, false).unwrap();
+        let gps = iter.parse_gps_info().unwrap().unwrap();
+        assert_eq!(gps.altitude_ref, altitude_ref);
+        assert_eq!(gps.altitude_meters(), altitude);
+    }
+
+    #[test]
+    fn parsed_entry_enum_values() {
+        let _ = tracing_subscriber::fmt().with_test_writer().try_init();
+
+        let buf = read_sample("exif.jpg").unwrap();
+        let (data, _) = extract_exif_with_mime(MimeImage::Jpeg, &buf, None).unwrap();
+        let range = data.and_then(|x| buf.subslice_in_range(x)).unwrap();
+        let iter = input_into_iter((buf, range), None, false).unwrap();
+
+        let find = |tag| iter.clone().find(|x| x.tag() == Some(tag)).unwrap();
+        let entry = find(SceneType);
+        assert_eq!(
+            entry.scene_type(),
+            Some(crate::SceneType::DirectlyPhotographed)
+        );
+        assert_eq!(entry.sensing_method(), None);
+        let entry = find(SensingMethod);
+        assert_eq!(
+            entry.sensing_method(),
+            Some(crate::SensingMethod::OneChipColorArea)
+        );
+        assert_eq!(entry.scene_type(), None);
+        assert_eq!(find(Make).file_source(), None);
+
+        let entry = ParsedExifEntry::make_ok(
+            0,
+            IfdKind::Exif,
+            super::ExifTagCode::Tag(FileSource),
+            EntryValue::Undefined(vec![3]),
+        );
+        assert_eq!(entry.file_source(), Some(crate::FileSource::Dsc));
+        assert!(format!("{entry:?}").contains("(Digital Camera)"));
+        assert_eq!(entry.file_source().unwrap().to_string(), "Digital Camera");
+
+        let entry = ParsedExifEntry::make_ok(
+            0,
+            IfdKind::Exif,
+            super::ExifTagCode::Tag(FileSource),
+            EntryValue::Undefined(vec![9]),
+        );
+        assert_eq!(entry.file_source(), None);
+    }
+
+    #[test]
+    fn parsed_entry_text_ref() {
+        let _ = tracing_subscriber::fmt().with_test_writer().try_init();
+
+        let buf = read_sample("exif.jpg").unwrap();
+        let (data, _) = extract_exif_with_mime(MimeImage::Jpeg, &buf, None).unwrap();
+        let range = data.and_then(|x| buf.subslice_in_range(x)).unwrap();
+        let iter = input_into_iter((buf, range), None, false).unwrap();
+
+        let mut make = iter
+            .clone()
+            .find(|x| x.tag() == Some(ExifTag::Make))
+            .unwrap();
+        assert!(make.has_value());
+        assert_eq!(make.text_ref(), Some("vivo"));
+        assert_eq!(make.get_value(), Some(&"vivo".into()));
+        assert_eq!(make.text_ref(), Some("vivo"));
+        assert_eq!(make.take_value(), Some("vivo".into()));
+        assert!(!make.has_value());
+        assert_eq!(make.text_ref(), None);
+        assert_eq!(make.take_value(), None);
+
+        let mut model = iter
+            .clone()
+            .find(|x| x.tag() == Some(ExifTag::Model))
+            .unwrap();
+        assert_eq!(model.take_result().unwrap(), "vivo X90 Pro+".into());
+
+        let width = iter
+            .clone()
+            .find(|x| x.tag() == Some(ExifTag::ImageWidth))
+            .unwrap();
+        assert!(width.has_value());
+        assert_eq!(width.text_ref(), None);
+    }
+
+    #[test_case(&[-5, 0], Some("-05:00"))]
+    #[test_case(&[8], Some("+08:00"))]
+    #[test_case(&[0x7fff], None)]
+    fn exif_iter_legacy_tz_offset(hours: &[i16], tz: Option<&str>) {
+        let _ = tracing_subscriber::fmt().with_test_writer().try_init();
+
+        let mut tiff = TiffBuilder::new(Endianness::Little);
+        let exif = tiff.ifd();
+        tiff.sub_ifd(0, 0x8769, exif)
+            .sshort(exif, 0x882a, hours)
+            .text(exif, 0x9003, "2020:01:02 03:04:05");
+        let buf = tiff.build();
+
+        let iter = input_into_iter(buf, None, false).unwrap();
+        assert_eq!(iter.timezone(), tz);
+
+        let exif: crate::Exif = iter.into();
+        let t = exif.get(DateTimeOriginal).unwrap().as_time().unwrap();
+        assert_eq!(t.naive_local().to_string(), "2020-01-02 03:04:05");
+        if let Some(tz) = tz {
+            assert_eq!(t.offset().to_string(), tz);
+        }
     }
 }
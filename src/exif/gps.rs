@@ -1,12 +1,21 @@
 use std::str::FromStr;
 
+use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
 use iso6709parse::{parse_string_representation, ISO6709Coord};
+#[cfg(feature = "json_dump")]
+use serde::{Deserialize, Serialize};
 
 use crate::values::{IRational, URational};
 
 /// Represents gps information stored in [`GPSInfo`](crate::ExifTag::GPSInfo)
 /// subIFD.
+///
+/// More fields may be added as more GPS tags are supported, so it can't be
+/// constructed with a struct literal outside of this crate, please start
+/// from [`GPSInfo::default`] and assign the fields instead.
+#[cfg_attr(feature = "json_dump", derive(Serialize, Deserialize))]
 #[derive(Debug, Default, Clone, PartialEq, Eq)]
+#[non_exhaustive]
 pub struct GPSInfo {
     /// N, S
     pub latitude_ref: char,
@@ -18,8 +27,12 @@ pub struct GPSInfo {
     /// degree, minute, second,
     pub longitude: LatLng,
 
-    /// 0: Above Sea Level
-    /// 1: Below Sea Level
+    /// - 0: Above Sea Level (Exif 3.0: positive ellipsoidal height)
+    /// - 1: Below Sea Level (Exif 3.0: negative ellipsoidal height)
+    /// - 2: Positive sea level value (Exif 3.0)
+    /// - 3: Negative sea level value (Exif 3.0)
+    ///
+    /// See [`Self::altitude_meters`] for the signed altitude.
     pub altitude_ref: u8,
     /// meters
     pub altitude: URational,
@@ -30,9 +43,42 @@ pub struct GPSInfo {
     /// - N: knots
     pub speed_ref: Option<char>,
     pub speed: Option<URational>,
+
+    /// Destination point, N, S
+    pub dest_latitude_ref: Option<char>,
+    /// Destination point, degree, minute, second,
+    pub dest_latitude: Option<LatLng>,
+
+    /// Destination point, E, W
+    pub dest_longitude_ref: Option<char>,
+    /// Destination point, degree, minute, second,
+    pub dest_longitude: Option<LatLng>,
+
+    /// Reference for bearing of destination
+    /// - T: true direction
+    /// - M: magnetic direction
+    pub dest_bearing_ref: Option<char>,
+    /// Bearing of destination, degrees (0.00 ~ 359.99)
+    pub dest_bearing: Option<URational>,
+
+    /// Distance unit of destination
+    /// - K: kilometers
+    /// - M: miles
+    /// - N: nautical miles
+    pub dest_distance_ref: Option<char>,
+    pub dest_distance: Option<URational>,
+
+    /// Version of the GPS IFD, e.g. `[2, 3, 0, 0]` for "2.3.0.0"
+    pub version_id: Option<[u8; 4]>,
+
+    /// UTC time of the GPS fix, hour, minute, second
+    pub time_stamp: Option<[URational; 3]>,
+    /// UTC date of the GPS fix, in the format of "YYYY:MM:DD"
+    pub date_stamp: Option<String>,
 }
 
 /// degree, minute, second,
+#[cfg_attr(feature = "json_dump", derive(Serialize, Deserialize))]
 #[derive(Debug, Clone, PartialEq, Eq, Default)]
 pub struct LatLng(pub URational, pub URational, pub URational);
 
@@ -56,13 +102,260 @@ impl GPSInfo {
             } else {
                 format!(
                     "{}{}CRSWGS_84",
-                    if self.altitude_ref == 0 { "+" } else { "-" },
+                    if self.is_altitude_negative() {
+                        "-"
+                    } else {
+                        "+"
+                    },
                     Self::format_float(altitude)
                 )
             }
         )
     }
 
+    /// Returns the version of the GPS IFD as a dotted string, e.g. "2.3.0.0".
+    pub fn version(&self) -> Option<String> {
+        self.version_id.map(|v| {
+            v.iter()
+                .map(|x| x.to_string())
+                .collect::<Vec<_>>()
+                .join(".")
+        })
+    }
+
+    /// Returns the signed altitude in meters, i.e. `altitude` negated if
+    /// `altitude_ref` is 1 or 3, which mean below the reference (sea level
+    /// or the ellipsoid). Returns `None` if the denominator is zero.
+    ///
+    /// Values 2 and 3 of `altitude_ref` were introduced by Exif 3.0 to
+    /// distinguish sea level values from ellipsoidal heights, the sign is
+    /// decided the same way for both references.
+    ///
+    /// ```rust
+    /// use nom_exif::*;
+    ///
+    /// let mut gps = GPSInfo::default();
+    /// gps.altitude_ref = 1;
+    /// gps.altitude = (1235, 10).into();
+    /// assert_eq!(gps.altitude_meters(), Some(-123.5));
+    /// ```
+    pub fn altitude_meters(&self) -> Option<f64> {
+        if self.altitude.1 == 0 {
+            return None;
+        }
+        let v = self.altitude.as_float();
+        Some(if self.is_altitude_negative() { -v } else { v })
+    }
+
+    fn is_altitude_negative(&self) -> bool {
+        matches!(self.altitude_ref, 1 | 3)
+    }
+
+    /// Returns `(latitude, longitude)` in decimal degrees, negative values
+    /// mean south & west respectively. See [`LatLng::as_degrees`].
+    ///
+    /// ```rust
+    /// use nom_exif::*;
+    ///
+    /// let mut gps = GPSInfo::default();
+    /// gps.latitude_ref = 'S';
+    /// gps.latitude = [(33, 1), (51, 1), (54, 1)].into();
+    /// gps.longitude_ref = 'E';
+    /// gps.longitude = [(151, 1), (12, 1), (36, 1)].into();
+    /// let (lat, lng) = gps.to_decimal();
+    /// assert_eq!(format!("{lat:.5} {lng:.5}"), "-33.86500 151.21000");
+    /// ```
+    pub fn to_decimal(&self) -> (f64, f64) {
+        (self.latitude_decimal(), self.longitude_decimal())
+    }
+
+    /// Returns the latitude in decimal degrees, negative values mean south.
+    pub fn latitude_decimal(&self) -> f64 {
+        let v = self.latitude.as_degrees();
+        if self.latitude_ref == 'S' {
+            -v
+        } else {
+            v
+        }
+    }
+
+    /// Returns the longitude in decimal degrees, negative values mean west.
+    pub fn longitude_decimal(&self) -> f64 {
+        let v = self.longitude.as_degrees();
+        if self.longitude_ref == 'W' {
+            -v
+        } else {
+            v
+        }
+    }
+
+    /// Returns false if the coordinates are obviously corrupted, i.e.:
+    ///
+    /// - `latitude_ref`/`longitude_ref` is not one of 'N'/'S' and 'E'/'W'
+    /// - any denominator of `latitude`/`longitude` is zero
+    /// - the latitude is out of [-90, 90], or the longitude is out of
+    ///   [-180, 180]
+    ///
+    /// Please note that (0, 0) is considered valid, since it's a real point.
+    pub fn is_valid(&self) -> bool {
+        matches!(self.latitude_ref, 'N' | 'S')
+            && matches!(self.longitude_ref, 'E' | 'W')
+            && self.latitude.has_valid_denominators()
+            && self.longitude.has_valid_denominators()
+            && (-90.0..=90.0).contains(&self.latitude_decimal())
+            && (-180.0..=180.0).contains(&self.longitude_decimal())
+    }
+
+    /// Encodes the coordinates as a [geohash](https://en.wikipedia.org/wiki/Geohash)
+    /// string of `precision` characters, e.g. for proximity queries by
+    /// prefix. Each character narrows the cell down, 5 characters are about
+    /// 5km, 9 characters are about 5m.
+    ///
+    /// Returns `None` if the coordinates are [invalid](Self::is_valid), or
+    /// `precision` is 0.
+    ///
+    /// ```rust
+    /// use nom_exif::*;
+    ///
+    /// let mut gps = GPSInfo::default();
+    /// gps.latitude_ref = 'N';
+    /// gps.latitude = [(57, 1), (38, 1), (5676, 100)].into();
+    /// gps.longitude_ref = 'E';
+    /// gps.longitude = [(10, 1), (24, 1), (2678, 100)].into();
+    /// assert_eq!(gps.geohash(6).as_deref(), Some("u4pruy"));
+    /// ```
+    pub fn geohash(&self, precision: usize) -> Option<String> {
+        const BASE32: &[u8] = b"0123456789bcdefghjkmnpqrstuvwxyz";
+
+        if !self.is_valid() || precision == 0 {
+            return None;
+        }
+
+        let mut lat = (-90.0, 90.0);
+        let mut lng = (-180.0, 180.0);
+        let (lat_v, lng_v) = (self.latitude_decimal(), self.longitude_decimal());
+
+        // Bits are interleaved starting with the longitude, 5 bits per char
+        let mut hash = String::with_capacity(precision);
+        let mut even = true;
+        while hash.len() < precision {
+            let mut idx = 0;
+            for _ in 0..5 {
+                let (range, v) = if even {
+                    (&mut lng, lng_v)
+                } else {
+                    (&mut lat, lat_v)
+                };
+                let mid = (range.0 + range.1) / 2.0;
+                idx <<= 1;
+                if v >= mid {
+                    idx |= 1;
+                    range.0 = mid;
+                } else {
+                    range.1 = mid;
+                }
+                even = !even;
+            }
+            hash.push(BASE32[idx] as char);
+        }
+        Some(hash)
+    }
+
+    /// Returns the destination latitude in decimal degrees, negative values
+    /// mean south.
+    pub fn dest_latitude_decimal(&self) -> Option<f64> {
+        let v = self.dest_latitude.as_ref()?.as_degrees();
+        Some(if self.dest_latitude_ref == Some('S') {
+            -v
+        } else {
+            v
+        })
+    }
+
+    /// Returns the destination longitude in decimal degrees, negative values
+    /// mean west.
+    pub fn dest_longitude_decimal(&self) -> Option<f64> {
+        let v = self.dest_longitude.as_ref()?.as_degrees();
+        Some(if self.dest_longitude_ref == Some('W') {
+            -v
+        } else {
+            v
+        })
+    }
+
+    /// Returns the bearing of destination in decimal degrees.
+    pub fn dest_bearing_decimal(&self) -> Option<f64> {
+        self.dest_bearing.map(|x| x.as_float())
+    }
+
+    /// Returns the distance to destination as a decimal, in the unit indicated
+    /// by `dest_distance_ref`.
+    pub fn dest_distance_decimal(&self) -> Option<f64> {
+        self.dest_distance.map(|x| x.as_float())
+    }
+
+    /// Combines `date_stamp` & `time_stamp` into an ISO 8601 UTC datetime
+    /// string, e.g. "2024-02-02T08:09:57Z". Fractional seconds are kept with
+    /// millisecond precision, e.g. "2024-02-02T08:09:57.250Z".
+    ///
+    /// Returns `None` if either of them is absent or invalid.
+    ///
+    /// ```rust
+    /// use nom_exif::*;
+    ///
+    /// let mut gps = GPSInfo::default();
+    /// gps.time_stamp = Some([(8, 1).into(), (9, 1).into(), (57, 1).into()]);
+    /// gps.date_stamp = Some("2024:02:02".to_string());
+    /// assert_eq!(
+    ///     gps.format_gps_datetime_iso8601().as_deref(),
+    ///     Some("2024-02-02T08:09:57Z")
+    /// );
+    /// ```
+    pub fn format_gps_datetime_iso8601(&self) -> Option<String> {
+        let datetime = self.gps_datetime()?;
+        let format = if datetime.timestamp_subsec_millis() == 0 {
+            "%Y-%m-%dT%H:%M:%SZ"
+        } else {
+            "%Y-%m-%dT%H:%M:%S%.3fZ"
+        };
+        Some(datetime.format(format).to_string())
+    }
+
+    /// Combines `date_stamp` & `time_stamp` into a UTC datetime. Fractional
+    /// seconds are kept with millisecond precision.
+    ///
+    /// Returns `None` if either of them is absent or invalid.
+    ///
+    /// ```rust
+    /// use nom_exif::*;
+    /// use chrono::{TimeZone, Utc};
+    ///
+    /// let mut gps = GPSInfo::default();
+    /// gps.time_stamp = Some([(8, 1).into(), (9, 1).into(), (5725, 100).into()]);
+    /// gps.date_stamp = Some("2024:02:02".to_string());
+    /// let expect = Utc.with_ymd_and_hms(2024, 2, 2, 8, 9, 57).unwrap()
+    ///     + chrono::Duration::milliseconds(250);
+    /// assert_eq!(gps.gps_datetime(), Some(expect));
+    /// ```
+    pub fn gps_datetime(&self) -> Option<DateTime<Utc>> {
+        let date =
+            NaiveDate::parse_from_str(self.date_stamp.as_deref()?.trim(), "%Y:%m:%d").ok()?;
+
+        let [hour, minute, second] = self.time_stamp?;
+        if [hour, minute, second].iter().any(|x| x.1 == 0) {
+            return None;
+        }
+        let second = second.as_float();
+        if !(0.0..60.0).contains(&second) {
+            return None;
+        }
+        let time = NaiveTime::from_hms_opt(hour.as_float() as u32, minute.as_float() as u32, 0)?;
+        // Rounding may yield 60000 ms (e.g. 59.9996 s), let the overflow
+        // carry into the minute (and possibly the day) instead of failing.
+        let millis = (second * 1000.0).round() as i64;
+        Some(date.and_time(time).and_utc() + chrono::Duration::milliseconds(millis))
+    }
+
     fn format_float(f: f64) -> String {
         if f.fract() == 0.0 {
             f.to_string()
@@ -80,6 +373,27 @@ impl GPSInfo {
     }
 }
 
+impl LatLng {
+    /// Returns the unsigned magnitude in decimal degrees, i.e. `degree +
+    /// minute / 60 + second / 3600`. A component with a zero denominator
+    /// counts as 0.
+    ///
+    /// ```rust
+    /// use nom_exif::*;
+    ///
+    /// let v = LatLng::from([(40, 1), (41, 1), (21, 1)]);
+    /// assert_eq!(format!("{:.5}", v.as_degrees()), "40.68917");
+    /// ```
+    pub fn as_degrees(&self) -> f64 {
+        let v = |x: &URational| if x.1 == 0 { 0.0 } else { x.as_float() };
+        v(&self.0) + v(&self.1) / 60.0 + v(&self.2) / 3600.0
+    }
+
+    fn has_valid_denominators(&self) -> bool {
+        [self.0, self.1, self.2].iter().all(|x| x.1 != 0)
+    }
+}
+
 impl From<[(u32, u32); 3]> for LatLng {
     fn from(value: [(u32, u32); 3]) -> Self {
         let res: [URational; 3] = value.map(|x| x.into());
@@ -219,6 +533,40 @@ mod tests {
     use crate::values::Rational;
 
     use super::*;
+    use test_case::test_case;
+
+    #[test_case(57.64911, 10.40744, 11, Some("u4pruydqqvj"))]
+    #[test_case(22.53113, 114.02148, 9, Some("ws104tevm"))]
+    #[test_case(-33.8568, 151.2153, 7, Some("r3gx2ux"))]
+    #[test_case(0.0, 0.0, 5, Some("s0000"))]
+    #[test_case(90.0, 180.0, 4, Some("zzzz"))]
+    #[test_case(-90.0, -180.0, 4, Some("0000"))]
+    #[test_case(22.53113, 114.02148, 0, None)]
+    fn gps_geohash(lat: f64, lng: f64, precision: usize, expect: Option<&str>) {
+        let _ = tracing_subscriber::fmt().with_test_writer().try_init();
+
+        let degrees =
+            |v: f64| LatLng::from([((v.abs() * 1e5).round() as u32, 100_000), (0, 1), (0, 1)]);
+        let gps = GPSInfo {
+            latitude_ref: if lat < 0.0 { 'S' } else { 'N' },
+            latitude: degrees(lat),
+            longitude_ref: if lng < 0.0 { 'W' } else { 'E' },
+            longitude: degrees(lng),
+            ..Default::default()
+        };
+        assert_eq!(gps.geohash(precision).as_deref(), expect);
+    }
+
+    #[test]
+    fn gps_geohash_invalid() {
+        let _ = tracing_subscriber::fmt().with_test_writer().try_init();
+
+        let gps = GPSInfo {
+            latitude_ref: 'X',
+            ..Default::default()
+        };
+        assert_eq!(gps.geohash(5), None);
+    }
 
     #[test]
     fn gps_iso6709() {
@@ -322,4 +670,155 @@ mod tests {
             "+40.68917-074.04444-33.333CRSWGS_84/"
         );
     }
+
+    #[test]
+    fn gps_dest() {
+        let info = GPSInfo {
+            dest_latitude_ref: Some('S'),
+            dest_latitude: Some([(40, 1), (41, 1), (21, 1)].into()),
+            dest_longitude_ref: Some('E'),
+            dest_longitude: Some([(74, 1), (2, 1), (40, 1)].into()),
+            dest_bearing_ref: Some('T'),
+            dest_bearing: Some((18050, 100).into()),
+            ..Default::default()
+        };
+        assert_eq!(
+            format!("{:.5}", info.dest_latitude_decimal().unwrap()),
+            "-40.68917"
+        );
+        assert_eq!(
+            format!("{:.5}", info.dest_longitude_decimal().unwrap()),
+            "74.04444"
+        );
+        assert_eq!(info.dest_bearing_decimal(), Some(180.5));
+        assert_eq!(info.dest_distance_decimal(), None);
+    }
+
+    #[test_case(Some("2024:02:02"), Some([(8, 1), (9, 1), (57, 1)]), Some("2024-02-02T08:09:57Z"))]
+    #[test_case(Some("2024:02:02"), Some([(8, 1), (9, 1), (5725, 100)]), Some("2024-02-02T08:09:57.250Z"))]
+    #[test_case(Some("2024:02:02 "), Some([(23, 1), (59, 1), (59, 1)]), Some("2024-02-02T23:59:59Z"))]
+    #[test_case(Some("2024:02:02"), Some([(8, 1), (9, 1), (599996, 10000)]), Some("2024-02-02T08:10:00Z"))]
+    #[test_case(Some("2024:02:02"), Some([(23, 1), (59, 1), (599996, 10000)]), Some("2024-02-03T00:00:00Z"))]
+    #[test_case(Some("2024:02:02"), Some([(8, 1), (9, 1), (60, 1)]), None)]
+    #[test_case(None, Some([(8, 1), (9, 1), (57, 1)]), None)]
+    #[test_case(Some("2024:02:02"), None, None)]
+    #[test_case(Some("2024-02-02"), Some([(8, 1), (9, 1), (57, 1)]), None)]
+    #[test_case(Some("2024:02:02"), Some([(8, 1), (9, 0), (57, 1)]), None)]
+    #[test_case(Some("2024:02:02"), Some([(25, 1), (9, 1), (57, 1)]), None)]
+    fn gps_datetime_iso8601(
+        date_stamp: Option<&str>,
+        time_stamp: Option<[(u32, u32); 3]>,
+        expect: Option<&str>,
+    ) {
+        let gps = GPSInfo {
+            date_stamp: date_stamp.map(|x| x.to_string()),
+            time_stamp: time_stamp.map(|x| x.map(|x| x.into())),
+            ..Default::default()
+        };
+        assert_eq!(gps.format_gps_datetime_iso8601().as_deref(), expect);
+        assert_eq!(
+            gps.gps_datetime()
+                .map(|x| x.to_rfc3339_opts(chrono::SecondsFormat::AutoSi, true)),
+            expect.map(|x| x.to_string())
+        );
+    }
+
+    #[test]
+    fn lat_lng_as_degrees() {
+        let v = LatLng::from([(40, 1), (41, 1), (21, 1)]);
+        assert!((v.as_degrees() - 40.68917).abs() < 1e-5);
+
+        // Zero denominators count as 0 rather than NaN
+        let v = LatLng::from([(40, 1), (30, 0), (0, 0)]);
+        assert_eq!(v.as_degrees(), 40.0);
+        let gps = GPSInfo {
+            latitude_ref: 'S',
+            latitude: v,
+            longitude_ref: 'W',
+            longitude: [(0, 0), (30, 1), (0, 1)].into(),
+            ..Default::default()
+        };
+        assert_eq!(gps.to_decimal(), (-40.0, -0.5));
+        assert!(!gps.is_valid());
+    }
+
+    #[test]
+    fn altitude_meters() {
+        let gps = |altitude_ref, altitude: (u32, u32)| GPSInfo {
+            altitude_ref,
+            altitude: altitude.into(),
+            ..Default::default()
+        };
+        assert_eq!(gps(0, (123, 2)).altitude_meters(), Some(61.5));
+        assert_eq!(gps(1, (123, 2)).altitude_meters(), Some(-61.5));
+        assert_eq!(gps(2, (10, 1)).altitude_meters(), Some(10.0));
+        assert_eq!(gps(3, (10, 1)).altitude_meters(), Some(-10.0));
+        assert_eq!(gps(0, (10, 0)).altitude_meters(), None);
+    }
+
+    #[test]
+    fn gps_is_valid() {
+        let gps = |lat_ref, lat: [(u32, u32); 3], lng_ref, lng: [(u32, u32); 3]| GPSInfo {
+            latitude_ref: lat_ref,
+            latitude: lat.into(),
+            longitude_ref: lng_ref,
+            longitude: lng.into(),
+            ..Default::default()
+        };
+
+        let liberty = gps(
+            'N',
+            [(40, 1), (41, 1), (21, 1)],
+            'W',
+            [(74, 1), (2, 1), (40, 1)],
+        );
+        assert!(liberty.is_valid());
+        assert!((liberty.latitude_decimal() - 40.68917).abs() < 1e-5);
+        assert!((liberty.longitude_decimal() + 74.04444).abs() < 1e-5);
+
+        assert!(gps(
+            'S',
+            [(90, 1), (0, 1), (0, 1)],
+            'E',
+            [(180, 1), (0, 1), (0, 1)]
+        )
+        .is_valid());
+        assert!(gps('N', [(0, 1), (0, 1), (0, 1)], 'E', [(0, 1), (0, 1), (0, 1)]).is_valid());
+
+        // out of range
+        assert!(!gps(
+            'N',
+            [(91, 1), (0, 1), (0, 1)],
+            'E',
+            [(0, 1), (0, 1), (0, 1)]
+        )
+        .is_valid());
+        assert!(!gps(
+            'N',
+            [(90, 1), (1, 1), (0, 1)],
+            'E',
+            [(0, 1), (0, 1), (0, 1)]
+        )
+        .is_valid());
+        assert!(!gps(
+            'N',
+            [(0, 1), (0, 1), (0, 1)],
+            'W',
+            [(181, 1), (0, 1), (0, 1)]
+        )
+        .is_valid());
+        // invalid refs
+        assert!(!gps(
+            '\0',
+            [(0, 1), (0, 1), (0, 1)],
+            'E',
+            [(0, 1), (0, 1), (0, 1)]
+        )
+        .is_valid());
+        assert!(!gps('N', [(0, 1), (0, 1), (0, 1)], 'X', [(0, 1), (0, 1), (0, 1)]).is_valid());
+        // zero denominators
+        assert!(!gps('N', [(1, 0), (0, 1), (0, 1)], 'E', [(0, 1), (0, 1), (0, 1)]).is_valid());
+        assert!(!gps('N', [(0, 1), (0, 1), (0, 1)], 'E', [(0, 1), (0, 1), (0, 0)]).is_valid());
+        assert!(!GPSInfo::default().is_valid());
+    }
 }
@@ -0,0 +1,441 @@
+use std::{ops::Deref, sync::Arc};
+
+use nom::number::Endianness;
+
+use crate::{EntryValue, ExifTag, IRational};
+
+/// Resolves a lens name from `(Make, lens type)`, see
+/// [`MediaParser::set_lens_resolver`](crate::MediaParser::set_lens_resolver).
+pub(crate) type LensResolver = Arc<dyn Fn(&str, u32) -> Option<String> + Send + Sync>;
+
+/// A user supplied lens name resolver, see
+/// [`MediaParser::set_lens_resolver`](crate::MediaParser::set_lens_resolver).
+pub type LensResolverFn = Box<dyn Fn(&str, u32) -> Option<String> + Send + Sync>;
+
+/// Canon `CameraSettings` maker note tag, an array of u16 values.
+const CANON_CAMERA_SETTINGS: u16 = 0x0001;
+
+/// Index of `LensType` in Canon `CameraSettings`.
+const CANON_LENS_TYPE_INDEX: usize = 22;
+
+/// Sony `LensType` maker note tag.
+const SONY_LENS_TYPE: u16 = ExifTag::LensType.code();
+
+/// Headers which may precede the IFD of a Sony maker note.
+const SONY_HEADERS: &[&[u8]] = &[b"SONY DSC \0\0\0", b"SONY CAM \0\0\0"];
+
+/// Returns the size of the header preceding the IFD of a Sony maker note,
+/// 0 if there is none.
+pub(crate) fn sony_header_len(maker_note: &[u8]) -> usize {
+    SONY_HEADERS
+        .iter()
+        .find(|h| maker_note.starts_with(h))
+        .map_or(0, |h| h.len())
+}
+
+/// Returns the `LensType` of the Sony maker note `entries`.
+pub(crate) fn sony_lens_type(entries: &[(u16, EntryValue)]) -> Option<u32> {
+    let (_, value) = entries.iter().find(|x| x.0 == SONY_LENS_TYPE)?;
+    value.as_u32().or_else(|| value.as_u16().map(u32::from))
+}
+
+pub(crate) fn is_sony(make: &str) -> bool {
+    make.trim().to_ascii_lowercase().starts_with("sony")
+}
+
+pub(crate) fn is_canon(make: &str) -> bool {
+    make.trim().to_ascii_lowercase().starts_with("canon")
+}
+
+/// Raw entries of a maker note, keyed by the vendor's own tag codes, which
+/// are unrelated to [`ExifTag`](crate::ExifTag).
+///
+/// The vendor specific maker notes, e.g. [`CanonMakerNote`], dereference to
+/// it, and provide accessors for the well known entries.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MakerNoteEntries {
+    entries: Vec<(u16, EntryValue)>,
+}
+
+impl MakerNoteEntries {
+    /// Returns the value of the entry with the raw tag `code`.
+    pub fn get(&self, code: u16) -> Option<&EntryValue> {
+        self.entries.iter().find(|x| x.0 == code).map(|x| &x.1)
+    }
+
+    /// Iterates `(tag code, value)` of all the entries, in the stored order.
+    pub fn iter(&self) -> impl Iterator<Item = (u16, &EntryValue)> {
+        self.entries.iter().map(|(code, v)| (*code, v))
+    }
+
+    /// Returns the number of the entries.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if there is no entry at all.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+const CANON_FIRMWARE_VERSION: u16 = 0x0007;
+const CANON_FILE_NUMBER: u16 = 0x0008;
+const CANON_SERIAL_NUMBER: u16 = 0x000c;
+const CANON_LENS_MODEL: u16 = 0x0095;
+
+/// Entries of a Canon maker note, parsed by
+/// [`ExifIter::parse_maker_note_canon`](crate::ExifIter::parse_maker_note_canon).
+///
+/// It dereferences to [`MakerNoteEntries`] for the raw entries, and
+/// provides accessors for the well known ones.
+///
+/// The shutter count isn't provided, since it's stored in model specific
+/// (and partly encrypted) blocks.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CanonMakerNote(MakerNoteEntries);
+
+impl Deref for CanonMakerNote {
+    type Target = MakerNoteEntries;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl CanonMakerNote {
+    pub(crate) fn new(entries: Vec<(u16, EntryValue)>) -> Self {
+        Self(MakerNoteEntries { entries })
+    }
+
+    /// Returns the raw `CameraSettings` (`0x0001`) array, e.g. the macro
+    /// mode, quality & focus mode, indexed by Canon's field numbers.
+    pub fn camera_settings(&self) -> Option<&[u16]> {
+        self.get(CANON_CAMERA_SETTINGS)?.as_u16_array()
+    }
+
+    /// Returns the numeric `LensType` in `CameraSettings`, which maps to a
+    /// lens name via a vendor database.
+    pub fn lens_type(&self) -> Option<u16> {
+        self.camera_settings()?.get(CANON_LENS_TYPE_INDEX).copied()
+    }
+
+    /// Returns the `LensModel` (`0x0095`).
+    pub fn lens_model(&self) -> Option<&str> {
+        self.get(CANON_LENS_MODEL)?.as_str()
+    }
+
+    /// Returns the `FirmwareVersion` (`0x0007`).
+    pub fn firmware_version(&self) -> Option<&str> {
+        self.get(CANON_FIRMWARE_VERSION)?.as_str()
+    }
+
+    /// Returns the `FileNumber` (`0x0008`), e.g. 1001234 for `IMG_1234`
+    /// in the directory `100CANON`.
+    pub fn file_number(&self) -> Option<u32> {
+        self.get(CANON_FILE_NUMBER)?.as_u32()
+    }
+
+    /// Returns the `SerialNumber` (`0x000c`) of the camera body.
+    pub fn serial_number(&self) -> Option<u32> {
+        self.get(CANON_SERIAL_NUMBER)?.as_u32()
+    }
+}
+
+/// Signature at the start of an Apple maker note.
+const APPLE_SIGNATURE: &[u8] = b"Apple iOS\0";
+
+/// Size of the Apple maker note header: the signature, a u16 version & the
+/// byte order mark. The IFD follows the header.
+pub(crate) const APPLE_HEADER_SIZE: usize = 14;
+
+const APPLE_RUN_TIME: u16 = 0x0003;
+const APPLE_FOCUS_DISTANCE_RANGE: u16 = 0x000c;
+const APPLE_CONTENT_IDENTIFIER: u16 = 0x0011;
+const APPLE_HDR_HEADROOM: u16 = 0x0021;
+const APPLE_HDR_GAIN: u16 = 0x0030;
+
+/// Entries of an Apple (iPhone/iPad) maker note, parsed by
+/// [`ExifIter::parse_maker_note`](crate::ExifIter::parse_maker_note).
+///
+/// It dereferences to [`MakerNoteEntries`] for the raw entries, and
+/// provides accessors for the well known ones.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AppleMakerNote(MakerNoteEntries);
+
+impl Deref for AppleMakerNote {
+    type Target = MakerNoteEntries;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl AppleMakerNote {
+    pub(crate) fn new(entries: Vec<(u16, EntryValue)>) -> Self {
+        Self(MakerNoteEntries { entries })
+    }
+
+    /// Returns the `RunTime` (`0x0003`) in seconds, i.e. the time since the
+    /// device booted, which is stored as a `CMTime` in a binary plist.
+    pub fn run_time(&self) -> Option<f64> {
+        let data = match self.get(APPLE_RUN_TIME)? {
+            EntryValue::Undefined(v) | EntryValue::U8Array(v) => v,
+            _ => return None,
+        };
+        let dict = parse_bplist_int_dict(data)?;
+        let get = |key: &str| dict.iter().find(|x| x.0 == key).map(|x| x.1);
+        let (value, timescale) = (get("value")?, get("timescale")?);
+        (timescale != 0).then(|| value as f64 / timescale as f64)
+    }
+
+    /// Returns the `FocusDistanceRange` (`0x000c`) in meters.
+    pub fn focus_distance_range(&self) -> Option<&[IRational]> {
+        self.get(APPLE_FOCUS_DISTANCE_RANGE)?.as_irational_array()
+    }
+
+    /// Returns the `ContentIdentifier` (`0x0011`), which pairs the image with
+    /// the video of a Live Photo.
+    pub fn content_identifier(&self) -> Option<&str> {
+        self.get(APPLE_CONTENT_IDENTIFIER)?.as_str()
+    }
+
+    /// Returns the `HDRHeadroom` (`0x0021`).
+    pub fn hdr_headroom(&self) -> Option<f64> {
+        self.get(APPLE_HDR_HEADROOM)?.as_f64()
+    }
+
+    /// Returns the `HDRGain` (`0x0030`).
+    pub fn hdr_gain(&self) -> Option<f64> {
+        self.get(APPLE_HDR_GAIN)?.as_f64()
+    }
+}
+
+/// Checks the signature of an Apple maker note, and returns the byte order
+/// of its IFD.
+pub(crate) fn apple_endian(maker_note: &[u8]) -> Option<Endianness> {
+    if !maker_note.starts_with(APPLE_SIGNATURE) {
+        return None;
+    }
+    match maker_note.get(APPLE_SIGNATURE.len() + 2..APPLE_HEADER_SIZE)? {
+        b"MM" => Some(Endianness::Big),
+        b"II" => Some(Endianness::Little),
+        _ => None,
+    }
+}
+
+/// Parses a binary plist whose top object is a dictionary, and returns its
+/// entries with ASCII string keys & integer values. Other entries are
+/// skipped.
+fn parse_bplist_int_dict(data: &[u8]) -> Option<Vec<(String, i64)>> {
+    if !data.starts_with(b"bplist00") {
+        return None;
+    }
+    let trailer = data.get(data.len().checked_sub(32)?..)?;
+    let offset_size = trailer[6] as usize;
+    let ref_size = trailer[7] as usize;
+    let read_uint = |data: &[u8], pos: usize, size: usize| -> Option<u64> {
+        let bytes = data.get(pos..pos.checked_add(size)?)?;
+        (size <= 8).then(|| bytes.iter().fold(0, |acc, x| (acc << 8) | *x as u64))
+    };
+    let objects_num = read_uint(trailer, 8, 8)?;
+    let top = read_uint(trailer, 16, 8)?;
+    let table = read_uint(trailer, 24, 8)? as usize;
+
+    let object = |idx: u64| -> Option<(u8, &[u8])> {
+        if idx >= objects_num {
+            return None;
+        }
+        let pos = table.checked_add((idx as usize).checked_mul(offset_size)?)?;
+        let pos = read_uint(data, pos, offset_size)? as usize;
+        let marker = *data.get(pos)?;
+        Some((marker, data.get(pos + 1..)?))
+    };
+
+    let (marker, body) = object(top)?;
+    // Dictionary with less than 15 entries
+    if marker & 0xf0 != 0xd0 || marker & 0x0f == 0x0f {
+        return None;
+    }
+    let n = (marker & 0x0f) as usize;
+    let entries = (0..n)
+        .filter_map(|i| {
+            let key = read_uint(body, i * ref_size, ref_size)?;
+            let value = read_uint(body, (n + i) * ref_size, ref_size)?;
+            let key = match object(key)? {
+                // ASCII string with less than 15 characters
+                (m, s) if m & 0xf0 == 0x50 && m & 0x0f != 0x0f => {
+                    std::str::from_utf8(s.get(..(m & 0x0f) as usize)?).ok()?
+                }
+                _ => return None,
+            };
+            let value = match object(value)? {
+                // Integer of 2^n bytes, only 8 bytes integers are signed
+                (m, v) if m & 0xf0 == 0x10 && m & 0x0f <= 3 => {
+                    let size = 1 << (m & 0x0f);
+                    read_uint(v, 0, size)? as i64
+                }
+                _ => return None,
+            };
+            Some((key.to_owned(), value))
+        })
+        .collect();
+    Some(entries)
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::testkit::{TiffBuilder, TiffValue};
+    use test_case::test_case;
+
+    /// Offset of the maker note in the TIFF data built by [`make_tiff`].
+    const MAKER_NOTE_OFFSET: u32 = 62;
+
+    /// Builds little endian TIFF data with a `Make` in IFD0 and a
+    /// `MakerNote` in the Exif IFD, which is located at [`MAKER_NOTE_OFFSET`].
+    pub(crate) fn make_tiff(make: &str, maker_note: &[u8]) -> Vec<u8> {
+        let mut tiff = TiffBuilder::new(Endianness::Little);
+        let exif = tiff.ifd();
+        let note = tiff.blob(maker_note);
+        tiff.text(0, 0x010f, make).sub_ifd(0, 0x8769, exif).entry(
+            exif,
+            0x927c,
+            7,
+            maker_note.len() as u32,
+            TiffValue::Blob(note),
+        );
+        assert_eq!(tiff.blob_offset(note), MAKER_NOTE_OFFSET);
+        tiff.build()
+    }
+
+    fn make_sony_maker_note(header: &[u8], lens_type: u32) -> Vec<u8> {
+        let mut buf = header.to_vec();
+        buf.extend_from_slice(&1u16.to_le_bytes());
+        buf.extend_from_slice(&SONY_LENS_TYPE.to_le_bytes());
+        buf.extend_from_slice(&4u16.to_le_bytes());
+        buf.extend_from_slice(&1u32.to_le_bytes());
+        buf.extend_from_slice(&lens_type.to_le_bytes());
+        buf.extend_from_slice(&0u32.to_le_bytes());
+        buf
+    }
+
+    /// Builds a Canon maker note for [`make_tiff`] with `CameraSettings`,
+    /// `FileNumber`, an out of range entry & `LensModel`.
+    pub(crate) fn make_canon_maker_note(lens_type: u16) -> Vec<u8> {
+        let entry = |buf: &mut Vec<u8>, tag: u16, format: u16, count: u32, value: u32| {
+            buf.extend_from_slice(&tag.to_le_bytes());
+            buf.extend_from_slice(&format.to_le_bytes());
+            buf.extend_from_slice(&count.to_le_bytes());
+            buf.extend_from_slice(&value.to_le_bytes());
+        };
+        let mut lens_model = b"EF50mm f/1.8 STM".to_vec();
+        lens_model.push(0);
+
+        // Values follow the IFD, offsets are relative to the TIFF header
+        let settings = MAKER_NOTE_OFFSET + 2 + 4 * 12 + 4;
+        let mut buf = 4u16.to_le_bytes().to_vec();
+        entry(&mut buf, CANON_CAMERA_SETTINGS, 3, 23, settings);
+        entry(&mut buf, CANON_FILE_NUMBER, 4, 1, 1001234);
+        // Out of range
+        entry(&mut buf, 0x0010, 4, 4, 0xffff);
+        entry(
+            &mut buf,
+            CANON_LENS_MODEL,
+            2,
+            lens_model.len() as u32,
+            settings + 46,
+        );
+        buf.extend_from_slice(&0u32.to_le_bytes());
+        for i in 0..23u16 {
+            let v = if i == 22 { lens_type } else { i };
+            buf.extend_from_slice(&v.to_le_bytes());
+        }
+        buf.extend_from_slice(&lens_model);
+        buf
+    }
+
+    #[test]
+    fn canon_maker_note() {
+        let _ = tracing_subscriber::fmt().with_test_writer().try_init();
+
+        let tiff = make_tiff("Canon", &make_canon_maker_note(254));
+        let iter = super::super::exif_iter::input_into_iter(tiff, None, false).unwrap();
+        let note = iter.parse_maker_note_canon().unwrap().unwrap();
+        assert_eq!(note.len(), 3);
+        assert_eq!(note.camera_settings().unwrap().len(), 23);
+        assert_eq!(note.camera_settings().unwrap()[1], 1);
+        assert_eq!(note.lens_type(), Some(254));
+        assert_eq!(note.file_number(), Some(1001234));
+        assert_eq!(note.lens_model(), Some("EF50mm f/1.8 STM"));
+        assert!(note.get(0x0010).is_none());
+        assert!(note.firmware_version().is_none());
+        assert!(iter.parse_maker_note().unwrap().is_none());
+    }
+
+    #[test_case("NIKON", make_canon_maker_note(254))]
+    #[test_case("Canon", b"\x01\x00".to_vec(); "inline")]
+    fn canon_maker_note_none(make: &str, maker_note: Vec<u8>) {
+        let _ = tracing_subscriber::fmt().with_test_writer().try_init();
+
+        let tiff = make_tiff(make, &maker_note);
+        let iter = super::super::exif_iter::input_into_iter(tiff, None, false).unwrap();
+        assert!(iter.parse_maker_note_canon().unwrap().is_none());
+    }
+
+    #[test_case(b"Apple iOS\0\0\x01MM\0\0", Some(Endianness::Big))]
+    #[test_case(b"Apple iOS\0\0\x01II\0\0", Some(Endianness::Little))]
+    #[test_case(b"Apple iOS\0\0\x01", None; "truncated")]
+    #[test_case(b"Nikon\0\x02\x10\0\0MM\0\x2a", None; "nikon")]
+    fn apple_maker_note_endian(data: &[u8], expect: Option<Endianness>) {
+        assert_eq!(apple_endian(data), expect);
+    }
+
+    #[test]
+    fn bplist_int_dict() {
+        let _ = tracing_subscriber::fmt().with_test_writer().try_init();
+
+        // {"flags": 1, "value": 155635867414250, "timescale": 1000000000, "epoch": 0}
+        let mut data = b"bplist00\xd4\x01\x02\x03\x04\x05\x06\x07\x08".to_vec();
+        data.extend_from_slice(b"\x55flags\x55value\x59timescale\x55epoch");
+        data.extend_from_slice(b"\x10\x01\x13\x00\x00\x8d\x8c\xcc\x97\x6e\xea");
+        data.extend_from_slice(b"\x12\x3b\x9a\xca\x00\x10\x00");
+        let table = data.len() as u64;
+        data.extend_from_slice(b"\x08\x11\x17\x1d\x27\x2d\x2f\x38\x3d");
+        data.extend_from_slice(&[0, 0, 0, 0, 0, 0, 1, 1]);
+        data.extend_from_slice(&9u64.to_be_bytes());
+        data.extend_from_slice(&0u64.to_be_bytes());
+        data.extend_from_slice(&table.to_be_bytes());
+
+        let dict = parse_bplist_int_dict(&data).unwrap();
+        assert_eq!(
+            dict,
+            [
+                ("flags".to_owned(), 1),
+                ("value".to_owned(), 155_635_867_414_250),
+                ("timescale".to_owned(), 1_000_000_000),
+                ("epoch".to_owned(), 0),
+            ]
+        );
+
+        let note = AppleMakerNote::new(vec![(APPLE_RUN_TIME, EntryValue::Undefined(data))]);
+        assert_eq!(note.run_time(), Some(155_635.867_414_25));
+
+        assert!(parse_bplist_int_dict(b"bplist00").is_none());
+        assert!(parse_bplist_int_dict(&[0; 40]).is_none());
+    }
+
+    #[test_case("Canon", make_canon_maker_note(254), Some(254))]
+    #[test_case("SONY", make_sony_maker_note(b"SONY DSC \0\0\0", 32850), Some(32850))]
+    #[test_case("SONY", make_sony_maker_note(b"", 65535), Some(65535))]
+    #[test_case("NIKON", make_canon_maker_note(254), None)]
+    #[test_case("Canon", make_sony_maker_note(b"", 1), None)]
+    #[test_case("Canon", b"\x01\x00".to_vec(), None; "truncated")]
+    fn maker_note_lens_type(make: &str, maker_note: Vec<u8>, expect: Option<u32>) {
+        let _ = tracing_subscriber::fmt().with_test_writer().try_init();
+
+        let tiff = make_tiff(make, &maker_note);
+        let iter = super::super::exif_iter::input_into_iter(tiff, None, false).unwrap();
+        assert_eq!(iter.lens_type(), expect);
+    }
+}
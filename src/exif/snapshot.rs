@@ -0,0 +1,212 @@
+use std::collections::BTreeMap;
+
+use chrono::DateTime;
+use serde::{Deserialize, Serialize};
+
+use crate::{values::Rational, EntryValue, Exif, GPSInfo};
+
+use super::ifd::ParsedImageFileDirectory;
+
+/// A serializable representation of an [`Exif`], for caching the parsed
+/// metadata and rebuilding the `Exif` later without re-reading the file.
+///
+/// Unlike the `Serialize` implementation of [`EntryValue`], which converts
+/// values into strings, the snapshot keeps the type of each value, so the
+/// conversion is lossless.
+///
+/// ```rust
+/// use nom_exif::*;
+///
+/// let mut parser = MediaParser::new();
+/// let ms = MediaSource::file_path("./testdata/exif.jpg").unwrap();
+/// let iter: ExifIter = parser.parse(ms).unwrap();
+/// let exif: Exif = iter.into();
+///
+/// let json = serde_json::to_string(&ExifSnapshot::from(&exif)).unwrap();
+/// let snapshot: ExifSnapshot = serde_json::from_str(&json).unwrap();
+/// assert_eq!(Exif::try_from(snapshot).unwrap(), exif);
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExifSnapshot {
+    /// IFD index => tag code => value
+    ifds: Vec<BTreeMap<u16, SnapshotValue>>,
+    gps_info: Option<GPSInfo>,
+    container_orientation: Option<u16>,
+    container_dimensions: Option<(u32, u32)>,
+}
+
+/// A typed version of [`EntryValue`], times are stored as RFC 3339 strings.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "value")]
+enum SnapshotValue {
+    Text(String),
+    URational(Rational<u32>),
+    IRational(Rational<i32>),
+    U8(u8),
+    U16(u16),
+    U32(u32),
+    U64(u64),
+    I8(i8),
+    I16(i16),
+    I32(i32),
+    I64(i64),
+    F32(f32),
+    F64(f64),
+    Time(String),
+    Undefined(Vec<u8>),
+    URationalArray(Vec<Rational<u32>>),
+    IRationalArray(Vec<Rational<i32>>),
+    U8Array(Vec<u8>),
+    U16Array(Vec<u16>),
+    U32Array(Vec<u32>),
+    I16Array(Vec<i16>),
+}
+
+impl From<&EntryValue> for SnapshotValue {
+    fn from(value: &EntryValue) -> Self {
+        match value.clone() {
+            EntryValue::Text(v) => Self::Text(v),
+            EntryValue::URational(v) => Self::URational(v),
+            EntryValue::IRational(v) => Self::IRational(v),
+            EntryValue::U8(v) => Self::U8(v),
+            EntryValue::U16(v) => Self::U16(v),
+            EntryValue::U32(v) => Self::U32(v),
+            EntryValue::U64(v) => Self::U64(v),
+            EntryValue::I8(v) => Self::I8(v),
+            EntryValue::I16(v) => Self::I16(v),
+            EntryValue::I32(v) => Self::I32(v),
+            EntryValue::I64(v) => Self::I64(v),
+            EntryValue::F32(v) => Self::F32(v),
+            EntryValue::F64(v) => Self::F64(v),
+            EntryValue::Time(v) => Self::Time(v.to_rfc3339()),
+            EntryValue::Undefined(v) => Self::Undefined(v),
+            EntryValue::URationalArray(v) => Self::URationalArray(v),
+            EntryValue::IRationalArray(v) => Self::IRationalArray(v),
+            EntryValue::U8Array(v) => Self::U8Array(v),
+            EntryValue::U16Array(v) => Self::U16Array(v),
+            EntryValue::U32Array(v) => Self::U32Array(v),
+            EntryValue::I16Array(v) => Self::I16Array(v),
+        }
+    }
+}
+
+impl TryFrom<SnapshotValue> for EntryValue {
+    type Error = crate::Error;
+
+    fn try_from(value: SnapshotValue) -> Result<Self, Self::Error> {
+        let value = match value {
+            SnapshotValue::Text(v) => Self::Text(v),
+            SnapshotValue::URational(v) => Self::URational(v),
+            SnapshotValue::IRational(v) => Self::IRational(v),
+            SnapshotValue::U8(v) => Self::U8(v),
+            SnapshotValue::U16(v) => Self::U16(v),
+            SnapshotValue::U32(v) => Self::U32(v),
+            SnapshotValue::U64(v) => Self::U64(v),
+            SnapshotValue::I8(v) => Self::I8(v),
+            SnapshotValue::I16(v) => Self::I16(v),
+            SnapshotValue::I32(v) => Self::I32(v),
+            SnapshotValue::I64(v) => Self::I64(v),
+            SnapshotValue::F32(v) => Self::F32(v),
+            SnapshotValue::F64(v) => Self::F64(v),
+            SnapshotValue::Time(v) => Self::Time(
+                DateTime::parse_from_rfc3339(&v)
+                    .map_err(|e| format!("invalid time {v:?} in Exif snapshot: {e}"))?,
+            ),
+            SnapshotValue::Undefined(v) => Self::Undefined(v),
+            SnapshotValue::URationalArray(v) => Self::URationalArray(v),
+            SnapshotValue::IRationalArray(v) => Self::IRationalArray(v),
+            SnapshotValue::U8Array(v) => Self::U8Array(v),
+            SnapshotValue::U16Array(v) => Self::U16Array(v),
+            SnapshotValue::U32Array(v) => Self::U32Array(v),
+            SnapshotValue::I16Array(v) => Self::I16Array(v),
+        };
+        Ok(value)
+    }
+}
+
+impl From<&Exif> for ExifSnapshot {
+    fn from(exif: &Exif) -> Self {
+        let ifds = exif
+            .ifds
+            .iter()
+            .map(|ifd| {
+                ifd.entries
+                    .iter()
+                    .map(|(tag, entry)| (*tag, (&entry.value).into()))
+                    .collect()
+            })
+            .collect();
+        Self {
+            ifds,
+            gps_info: exif.gps_info.clone(),
+            container_orientation: exif.container_orientation,
+            container_dimensions: exif.container_dimensions,
+        }
+    }
+}
+
+impl TryFrom<ExifSnapshot> for Exif {
+    type Error = crate::Error;
+
+    /// Rebuilds the `Exif`, fails if a time value in the snapshot is invalid.
+    fn try_from(snapshot: ExifSnapshot) -> Result<Self, Self::Error> {
+        let mut ifds = Vec::with_capacity(snapshot.ifds.len());
+        for entries in snapshot.ifds {
+            let mut ifd = ParsedImageFileDirectory::new();
+            for (tag, value) in entries {
+                ifd.put(tag, value.try_into()?);
+            }
+            ifds.push(ifd);
+        }
+        Ok(Exif {
+            ifds,
+            gps_info: snapshot.gps_info,
+            container_orientation: snapshot.container_orientation,
+            container_dimensions: snapshot.container_dimensions,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{testkit::open_sample, ExifIter, MediaParser, MediaSource};
+
+    use super::*;
+    use test_case::test_case;
+
+    #[test_case("exif.jpg")]
+    #[test_case("exif.heic")]
+    #[test_case("tif.tif")]
+    #[test_case("fujifilm_x_t1_01.raf.meta")]
+    fn round_trip(path: &str) {
+        let _ = tracing_subscriber::fmt().with_test_writer().try_init();
+
+        let mut parser = MediaParser::new();
+        let ms = MediaSource::file(open_sample(path).unwrap()).unwrap();
+        let iter: ExifIter = parser.parse(ms).unwrap();
+        let exif: Exif = iter.into();
+
+        let snapshot = ExifSnapshot::from(&exif);
+        let json = serde_json::to_string(&snapshot).unwrap();
+        let snapshot: ExifSnapshot = serde_json::from_str(&json).unwrap();
+        assert_eq!(Exif::try_from(snapshot).unwrap(), exif);
+    }
+
+    #[test]
+    fn typed_values() {
+        let _ = tracing_subscriber::fmt().with_test_writer().try_init();
+
+        let value = serde_json::to_value(SnapshotValue::from(&EntryValue::URational(
+            (175, 100).into(),
+        )))
+        .unwrap();
+        assert_eq!(
+            value,
+            serde_json::json!({"type": "URational", "value": [175, 100]})
+        );
+
+        let value: SnapshotValue =
+            serde_json::from_str(r#"{"type": "Time", "value": "2023-07-09T20:36:33"}"#).unwrap();
+        EntryValue::try_from(value).unwrap_err();
+    }
+}
@@ -3,6 +3,8 @@
 
 use std::fmt::{Debug, Display};
 
+use crate::EntryValue;
+
 #[cfg(feature = "json_dump")]
 use serde::{Deserialize, Serialize};
 
@@ -109,6 +111,7 @@ pub enum ExifTag {
     // sub ifd
     ExifOffset = 0x0000_8769,
     GPSInfo = 0x0000_8825,
+    SubIFDs = 0x0000_014a,
 
     ImageDescription = 0x0000_010e,
     XResolution = 0x0000_011a,
@@ -116,6 +119,9 @@ pub enum ExifTag {
     ResolutionUnit = 0x0000_0128,
     Software = 0x0000_0131,
     HostComputer = 0x0000_013c,
+    Artist = 0x0000_013b,
+    DocumentName = 0x0000_010d,
+    PageName = 0x0000_011d,
     WhitePoint = 0x0000_013e,
     PrimaryChromaticities = 0x0000_013f,
     YCbCrCoefficients = 0x0000_0211,
@@ -125,6 +131,7 @@ pub enum ExifTag {
     ExposureProgram = 0x0000_8822,
     SpectralSensitivity = 0x0000_8824,
     OECF = 0x0000_8828,
+    TimeZoneOffset = 0x0000_882a,
     SensitivityType = 0x0000_8830,
     ExifVersion = 0x0000_9000,
     ApertureValue = 0x0000_9202,
@@ -169,6 +176,7 @@ pub enum ExifTag {
     LensMake = 0x0000_a433,
     LensModel = 0x0000_a434,
     Gamma = 0x0000_a500,
+    OffsetSchema = 0x0000_ea1d,
 
     GPSTimeStamp = 0x00007,
     GPSSatellites = 0x00008,
@@ -210,12 +218,216 @@ pub enum ExifTag {
     SamplesPerPixel = 0x0000_0115,
     RowsPerStrip = 0x0000_0116,
     PlanarConfiguration = 0x0000_011c,
+    StripOffsets = 0x0000_0111,
+    StripByteCounts = 0x0000_0117,
+    TileOffsets = 0x0000_0144,
+    TileByteCounts = 0x0000_0145,
+
+    /// The numeric lens type in a maker note, which maps to a lens name via
+    /// a vendor database, see [`ExifIter::lens_type`](crate::ExifIter::lens_type).
+    ///
+    /// It's the Sony/Minolta maker note tag code; it isn't stored in the
+    /// standard IFDs.
+    LensType = 0x0000_b027,
 }
 
 impl ExifTag {
+    /// Alias of [`ExifTag::LensType`], the name used by some tools (e.g.
+    /// ExifTool).
+    #[allow(non_upper_case_globals)]
+    pub const LensID: ExifTag = ExifTag::LensType;
+
     pub const fn code(self) -> u16 {
         self as u16
     }
+
+    /// Describes the value of an enum-like tag in a human-readable way, e.g.
+    /// `Orientation` 6 is "Rotate 90 CW", `ExposureProgram` 2 is "Program
+    /// AE". The raw value is left unchanged.
+    ///
+    /// Returns `None` if the tag isn't an enum-like one, or the value isn't
+    /// recognized.
+    ///
+    /// ```rust
+    /// use nom_exif::*;
+    ///
+    /// assert_eq!(
+    ///     ExifTag::Orientation.describe_value(&EntryValue::U16(6)).as_deref(),
+    ///     Some("Rotate 90 CW")
+    /// );
+    /// assert_eq!(
+    ///     ExifTag::Flash.describe_value(&EntryValue::U16(0x19)).as_deref(),
+    ///     Some("Flash fired, auto mode")
+    /// );
+    /// assert_eq!(ExifTag::Make.describe_value(&"vivo".into()), None);
+    /// ```
+    pub fn describe_value(self, v: &EntryValue) -> Option<String> {
+        // Some of these tags are stored as a single `Undefined` byte
+        let code = match v {
+            EntryValue::Undefined(v) => (*v.first()?).into(),
+            v => v.as_u16().or_else(|| v.as_u8().map(u16::from))?,
+        };
+        let s = match self {
+            ExifTag::Flash => return Some(describe_flash(code)),
+            ExifTag::FileSource => return FileSource::try_from(code).ok().map(|x| x.to_string()),
+            ExifTag::SceneType => return SceneType::try_from(code).ok().map(|x| x.to_string()),
+            ExifTag::SensingMethod => {
+                return SensingMethod::try_from(code).ok().map(|x| x.to_string())
+            }
+            ExifTag::Orientation => match code {
+                1 => "Horizontal (normal)",
+                2 => "Mirror horizontal",
+                3 => "Rotate 180",
+                4 => "Mirror vertical",
+                5 => "Mirror horizontal and rotate 270 CW",
+                6 => "Rotate 90 CW",
+                7 => "Mirror horizontal and rotate 90 CW",
+                8 => "Rotate 270 CW",
+                _ => return None,
+            },
+            ExifTag::ExposureProgram => match code {
+                0 => "Not defined",
+                1 => "Manual",
+                2 => "Program AE",
+                3 => "Aperture-priority AE",
+                4 => "Shutter speed priority AE",
+                5 => "Creative (slow speed)",
+                6 => "Action (high speed)",
+                7 => "Portrait",
+                8 => "Landscape",
+                9 => "Bulb",
+                _ => return None,
+            },
+            ExifTag::MeteringMode => match code {
+                0 => "Unknown",
+                1 => "Average",
+                2 => "Center-weighted average",
+                3 => "Spot",
+                4 => "Multi-spot",
+                5 => "Multi-segment",
+                6 => "Partial",
+                255 => "Other",
+                _ => return None,
+            },
+            ExifTag::LightSource => match code {
+                0 => "Unknown",
+                1 => "Daylight",
+                2 => "Fluorescent",
+                3 => "Tungsten (incandescent)",
+                4 => "Flash",
+                9 => "Fine weather",
+                10 => "Cloudy",
+                11 => "Shade",
+                12 => "Daylight fluorescent",
+                13 => "Day white fluorescent",
+                14 => "Cool white fluorescent",
+                15 => "White fluorescent",
+                16 => "Warm white fluorescent",
+                17 => "Standard light A",
+                18 => "Standard light B",
+                19 => "Standard light C",
+                20 => "D55",
+                21 => "D65",
+                22 => "D75",
+                23 => "D50",
+                24 => "ISO studio tungsten",
+                255 => "Other",
+                _ => return None,
+            },
+            ExifTag::WhiteBalanceMode => match code {
+                0 => "Auto",
+                1 => "Manual",
+                _ => return None,
+            },
+            ExifTag::ExposureMode => match code {
+                0 => "Auto",
+                1 => "Manual",
+                2 => "Auto bracket",
+                _ => return None,
+            },
+            ExifTag::SceneCaptureType => match code {
+                0 => "Standard",
+                1 => "Landscape",
+                2 => "Portrait",
+                3 => "Night",
+                _ => return None,
+            },
+            ExifTag::ColorSpace => match code {
+                1 => "sRGB",
+                2 => "Adobe RGB",
+                0xffff => "Uncalibrated",
+                _ => return None,
+            },
+            ExifTag::ResolutionUnit | ExifTag::FocalPlaneResolutionUnit => match code {
+                1 => "None",
+                2 => "inches",
+                3 => "cm",
+                _ => return None,
+            },
+            ExifTag::CustomRendered => match code {
+                0 => "Normal",
+                1 => "Custom",
+                _ => return None,
+            },
+            ExifTag::GainControl => match code {
+                0 => "None",
+                1 => "Low gain up",
+                2 => "High gain up",
+                3 => "Low gain down",
+                4 => "High gain down",
+                _ => return None,
+            },
+            ExifTag::Contrast | ExifTag::Sharpness => match code {
+                0 => "Normal",
+                1 => "Soft",
+                2 => "Hard",
+                _ => return None,
+            },
+            ExifTag::Saturation => match code {
+                0 => "Normal",
+                1 => "Low",
+                2 => "High",
+                _ => return None,
+            },
+            ExifTag::SubjectDistanceRange => match code {
+                0 => "Unknown",
+                1 => "Macro",
+                2 => "Close",
+                3 => "Distant",
+                _ => return None,
+            },
+            _ => return None,
+        };
+        Some(s.to_string())
+    }
+}
+
+/// Decodes the bit fields of an [`ExifTag::Flash`] value, e.g. 0x10 is "Flash
+/// did not fire, compulsory flash suppression mode".
+fn describe_flash(code: u16) -> String {
+    if code & 0x20 != 0 {
+        return "No flash function".to_string();
+    }
+    let mut parts = vec![if code & 0x01 != 0 {
+        "Flash fired"
+    } else {
+        "Flash did not fire"
+    }];
+    match (code >> 3) & 0x03 {
+        1 => parts.push("compulsory flash firing mode"),
+        2 => parts.push("compulsory flash suppression mode"),
+        3 => parts.push("auto mode"),
+        _ => (),
+    }
+    match (code >> 1) & 0x03 {
+        2 => parts.push("return light not detected"),
+        3 => parts.push("return light detected"),
+        _ => (),
+    }
+    if code & 0x40 != 0 {
+        parts.push("red-eye reduction mode");
+    }
+    parts.join(", ")
 }
 
 impl Display for ExifTag {
@@ -254,12 +466,16 @@ impl From<ExifTag> for &str {
             ExifTag::GPSVersionID => "GPSVersionID",
             ExifTag::ExifOffset => "ExifOffset",
             ExifTag::GPSInfo => "GPSInfo",
+            ExifTag::SubIFDs => "SubIFDs",
             ExifTag::ImageDescription => "ImageDescription",
             ExifTag::XResolution => "XResolution",
             ExifTag::YResolution => "YResolution",
             ExifTag::ResolutionUnit => "ResolutionUnit",
             ExifTag::Software => "Software",
             ExifTag::HostComputer => "HostComputer",
+            ExifTag::Artist => "Artist",
+            ExifTag::DocumentName => "DocumentName",
+            ExifTag::PageName => "PageName",
             ExifTag::WhitePoint => "WhitePoint",
             ExifTag::PrimaryChromaticities => "PrimaryChromaticities",
             ExifTag::YCbCrCoefficients => "YCbCrCoefficients",
@@ -268,6 +484,7 @@ impl From<ExifTag> for &str {
             ExifTag::ExposureProgram => "ExposureProgram",
             ExifTag::SpectralSensitivity => "SpectralSensitivity",
             ExifTag::OECF => "OECF",
+            ExifTag::TimeZoneOffset => "TimeZoneOffset",
             ExifTag::SensitivityType => "SensitivityType",
             ExifTag::ExifVersion => "ExifVersion",
             ExifTag::ApertureValue => "ApertureValue",
@@ -312,6 +529,7 @@ impl From<ExifTag> for &str {
             ExifTag::LensMake => "LensMake",
             ExifTag::LensModel => "LensModel",
             ExifTag::Gamma => "Gamma",
+            ExifTag::OffsetSchema => "OffsetSchema",
             ExifTag::GPSTimeStamp => "GPSTimeStamp",
             ExifTag::GPSSatellites => "GPSSatellites",
             ExifTag::GPSStatus => "GPSStatus",
@@ -351,6 +569,11 @@ impl From<ExifTag> for &str {
             ExifTag::SamplesPerPixel => "SamplesPerPixel",
             ExifTag::RowsPerStrip => "RowsPerStrip",
             ExifTag::PlanarConfiguration => "PlanarConfiguration",
+            ExifTag::StripOffsets => "StripOffsets",
+            ExifTag::StripByteCounts => "StripByteCounts",
+            ExifTag::TileOffsets => "TileOffsets",
+            ExifTag::TileByteCounts => "TileByteCounts",
+            ExifTag::LensType => "LensType",
         }
     }
 }
@@ -387,12 +610,16 @@ impl TryFrom<u16> for ExifTag {
             x if x == GPSVersionID.code() => Self::GPSVersionID,
             x if x == ExifOffset.code() => Self::ExifOffset,
             x if x == GPSInfo.code() => Self::GPSInfo,
+            x if x == SubIFDs.code() => Self::SubIFDs,
             x if x == ImageDescription.code() => Self::ImageDescription,
             x if x == XResolution.code() => Self::XResolution,
             x if x == YResolution.code() => Self::YResolution,
             x if x == ResolutionUnit.code() => Self::ResolutionUnit,
             x if x == Software.code() => Self::Software,
             x if x == HostComputer.code() => Self::HostComputer,
+            x if x == Artist.code() => Self::Artist,
+            x if x == DocumentName.code() => Self::DocumentName,
+            x if x == PageName.code() => Self::PageName,
             x if x == WhitePoint.code() => Self::WhitePoint,
             x if x == PrimaryChromaticities.code() => Self::PrimaryChromaticities,
             x if x == YCbCrCoefficients.code() => Self::YCbCrCoefficients,
@@ -401,6 +628,7 @@ impl TryFrom<u16> for ExifTag {
             x if x == ExposureProgram.code() => Self::ExposureProgram,
             x if x == SpectralSensitivity.code() => Self::SpectralSensitivity,
             x if x == OECF.code() => Self::OECF,
+            x if x == TimeZoneOffset.code() => Self::TimeZoneOffset,
             x if x == SensitivityType.code() => Self::SensitivityType,
             x if x == ExifVersion.code() => Self::ExifVersion,
             x if x == ApertureValue.code() => Self::ApertureValue,
@@ -445,6 +673,7 @@ impl TryFrom<u16> for ExifTag {
             x if x == LensMake.code() => Self::LensMake,
             x if x == LensModel.code() => Self::LensModel,
             x if x == Gamma.code() => Self::Gamma,
+            x if x == OffsetSchema.code() => Self::OffsetSchema,
             x if x == GPSTimeStamp.code() => Self::GPSTimeStamp,
             x if x == GPSSatellites.code() => Self::GPSSatellites,
             x if x == GPSStatus.code() => Self::GPSStatus,
@@ -484,6 +713,11 @@ impl TryFrom<u16> for ExifTag {
             x if x == SamplesPerPixel.code() => Self::SamplesPerPixel,
             x if x == RowsPerStrip.code() => Self::RowsPerStrip,
             x if x == PlanarConfiguration.code() => Self::PlanarConfiguration,
+            x if x == StripOffsets.code() => Self::StripOffsets,
+            x if x == StripByteCounts.code() => Self::StripByteCounts,
+            x if x == TileOffsets.code() => Self::TileOffsets,
+            x if x == TileByteCounts.code() => Self::TileByteCounts,
+            x if x == LensType.code() => Self::LensType,
 
             o => return Err(format!("Unrecognized ExifTag 0x{o:04x}").into()),
         };
@@ -503,3 +737,156 @@ pub enum Orientation {
     MirrorHorizontalRotate90,
     Rotate270,
 }
+
+/// Decoded value of [`ExifTag::FileSource`], i.e. the image source.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FileSource {
+    Others,
+    TransparentScanner,
+    ReflexScanner,
+    /// Digital still camera
+    Dsc,
+}
+
+impl TryFrom<u16> for FileSource {
+    type Error = crate::Error;
+    fn try_from(v: u16) -> Result<Self, Self::Error> {
+        let x = match v {
+            0 => Self::Others,
+            1 => Self::TransparentScanner,
+            2 => Self::ReflexScanner,
+            3 => Self::Dsc,
+            o => return Err(format!("Unrecognized FileSource {o}").into()),
+        };
+        Ok(x)
+    }
+}
+
+impl Display for FileSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            FileSource::Others => "Others",
+            FileSource::TransparentScanner => "Film Scanner",
+            FileSource::ReflexScanner => "Reflection Print Scanner",
+            FileSource::Dsc => "Digital Camera",
+        };
+        f.write_str(s)
+    }
+}
+
+/// Decoded value of [`ExifTag::SceneType`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SceneType {
+    DirectlyPhotographed,
+}
+
+impl TryFrom<u16> for SceneType {
+    type Error = crate::Error;
+    fn try_from(v: u16) -> Result<Self, Self::Error> {
+        match v {
+            1 => Ok(Self::DirectlyPhotographed),
+            o => Err(format!("Unrecognized SceneType {o}").into()),
+        }
+    }
+}
+
+impl Display for SceneType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SceneType::DirectlyPhotographed => f.write_str("Directly photographed"),
+        }
+    }
+}
+
+/// Decoded value of [`ExifTag::SensingMethod`], i.e. the image sensor type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SensingMethod {
+    NotDefined,
+    OneChipColorArea,
+    TwoChipColorArea,
+    ThreeChipColorArea,
+    ColorSequentialArea,
+    Trilinear,
+    ColorSequentialLinear,
+}
+
+impl TryFrom<u16> for SensingMethod {
+    type Error = crate::Error;
+    fn try_from(v: u16) -> Result<Self, Self::Error> {
+        let x = match v {
+            1 => Self::NotDefined,
+            2 => Self::OneChipColorArea,
+            3 => Self::TwoChipColorArea,
+            4 => Self::ThreeChipColorArea,
+            5 => Self::ColorSequentialArea,
+            7 => Self::Trilinear,
+            8 => Self::ColorSequentialLinear,
+            o => return Err(format!("Unrecognized SensingMethod {o}").into()),
+        };
+        Ok(x)
+    }
+}
+
+impl Display for SensingMethod {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            SensingMethod::NotDefined => "Not defined",
+            SensingMethod::OneChipColorArea => "One-chip color area",
+            SensingMethod::TwoChipColorArea => "Two-chip color area",
+            SensingMethod::ThreeChipColorArea => "Three-chip color area",
+            SensingMethod::ColorSequentialArea => "Color sequential area",
+            SensingMethod::Trilinear => "Trilinear",
+            SensingMethod::ColorSequentialLinear => "Color sequential linear",
+        };
+        f.write_str(s)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    #[test_case(ExifTag::Orientation, EntryValue::U16(1), Some("Horizontal (normal)"))]
+    #[test_case(ExifTag::Orientation, EntryValue::U16(6), Some("Rotate 90 CW"))]
+    #[test_case(ExifTag::Orientation, EntryValue::U16(9), None)]
+    #[test_case(ExifTag::ExposureProgram, EntryValue::U16(2), Some("Program AE"))]
+    #[test_case(ExifTag::MeteringMode, EntryValue::U16(5), Some("Multi-segment"))]
+    #[test_case(ExifTag::WhiteBalanceMode, EntryValue::U16(0), Some("Auto"))]
+    #[test_case(ExifTag::ColorSpace, EntryValue::U16(0xffff), Some("Uncalibrated"))]
+    #[test_case(ExifTag::Flash, EntryValue::U16(0), Some("Flash did not fire"))]
+    #[test_case(
+        ExifTag::Flash,
+        EntryValue::U16(0x10),
+        Some("Flash did not fire, compulsory flash suppression mode")
+    )]
+    #[test_case(
+        ExifTag::Flash,
+        EntryValue::U16(0x1f),
+        Some("Flash fired, auto mode, return light detected")
+    )]
+    #[test_case(
+        ExifTag::Flash,
+        EntryValue::U16(0x41),
+        Some("Flash fired, red-eye reduction mode")
+    )]
+    #[test_case(ExifTag::Flash, EntryValue::U16(0x20), Some("No flash function"))]
+    #[test_case(ExifTag::FileSource, EntryValue::Undefined(vec![3]), Some("Digital Camera"))]
+    #[test_case(
+        ExifTag::SensingMethod,
+        EntryValue::U16(2),
+        Some("One-chip color area")
+    )]
+    #[test_case(ExifTag::Make, EntryValue::U16(1), None)]
+    #[test_case(ExifTag::Orientation, EntryValue::Text("6".into()), None)]
+    fn describe_value(tag: ExifTag, v: EntryValue, expect: Option<&str>) {
+        assert_eq!(tag.describe_value(&v).as_deref(), expect);
+    }
+
+    #[test]
+    fn lens_type() {
+        assert_eq!(ExifTag::try_from(0xb027).unwrap(), ExifTag::LensType);
+        assert_eq!(ExifTag::LensID, ExifTag::LensType);
+        assert_eq!(ExifTag::LensID.to_string(), "LensType");
+    }
+}
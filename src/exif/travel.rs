@@ -8,6 +8,10 @@ use crate::{error::ParsingError, exif::TiffHeader, values::DataFormat, ExifTag};
 
 use super::{exif_exif::IFD_ENTRY_SIZE, exif_iter::SUBIFD_TAGS};
 
+/// `SubIFDs` beyond this are not loaded, the same as the ones dropped by
+/// [`ExifIter`](crate::ExifIter) to keep the depth bounded.
+const MAX_SUB_IFDS: usize = 8;
+
 /// Only iterates headers, don't parse entries.
 ///
 /// Currently only used to extract Exif data for *.tiff files
@@ -23,6 +27,7 @@ pub(crate) struct IfdHeaderTravel<'a> {
 
 #[derive(Debug, Clone)]
 pub(crate) struct EntryInfo<'a> {
+    #[allow(unused)]
     pub tag: u16,
     #[allow(unused)]
     pub data: &'a [u8],
@@ -30,7 +35,8 @@ pub(crate) struct EntryInfo<'a> {
     pub data_format: DataFormat,
     #[allow(unused)]
     pub data_offset: Option<u32>,
-    pub sub_ifd_offset: Option<u32>,
+    /// Offsets of the sub-IFDs relative to the current IFD
+    pub sub_ifd_offsets: Vec<u32>,
 }
 
 impl<'a> IfdHeaderTravel<'a> {
@@ -92,14 +98,25 @@ impl<'a> IfdHeaderTravel<'a> {
             }
             (&self.ifd_data[start..end], Some(start as u32))
         } else {
-            (entry_data, None)
+            (&entry_data[8..8 + size], None) // Safe-slice
         };
 
-        let sub_ifd_offset = if SUBIFD_TAGS.contains(&tag) {
-            let offset = self.get_data_pos(value_or_offset);
-            Some(offset)
+        let sub_ifd_offsets = if SUBIFD_TAGS.contains(&tag) {
+            vec![self.get_data_pos(value_or_offset)]
+        } else if tag == ExifTag::SubIFDs.code()
+            && matches!(data_format, DataFormat::U32 | DataFormat::Ifd)
+        {
+            // An array of offsets, e.g. the full-size image & the previews of
+            // a NEF file
+            data.chunks_exact(4)
+                .filter_map(|x| {
+                    let (_, offset) = streaming::u32::<_, nom::error::Error<_>>(endian)(x).ok()?;
+                    Some(self.get_data_pos(offset))
+                })
+                .take(MAX_SUB_IFDS)
+                .collect()
         } else {
-            None
+            Vec::new()
         };
 
         let entry = EntryInfo {
@@ -107,7 +124,7 @@ impl<'a> IfdHeaderTravel<'a> {
             data,
             data_format,
             data_offset,
-            sub_ifd_offset,
+            sub_ifd_offsets,
         };
         Ok((&[][..], Some(entry)))
     }
@@ -116,27 +133,29 @@ impl<'a> IfdHeaderTravel<'a> {
         value_or_offset.saturating_sub(self.offset)
     }
 
-    fn parse_ifd_entry_header(&self, pos: u32) -> IResult<&[u8], Option<IfdHeaderTravel<'a>>> {
+    fn parse_ifd_entry_header(&self, pos: u32) -> IResult<&[u8], Vec<IfdHeaderTravel<'a>>> {
         let (_, entry_data) =
             nom::bytes::streaming::take(IFD_ENTRY_SIZE)(&self.ifd_data[pos as usize..])?;
 
         let (remain, entry) = self.parse_tag_entry_header(entry_data)?;
 
-        if let Some(entry) = entry {
-            // if !cb(&entry) {
-            //     return Ok((&[][..], ()));
-            // }
-
-            if let Some(offset) = entry.sub_ifd_offset {
-                let tag: ExifTag = entry.tag.try_into().unwrap();
-                println!("sub-ifd: {:?}", tag);
-                let sub_ifd =
-                    IfdHeaderTravel::new(&self.ifd_data[offset as usize..], offset, self.endian);
-                return Ok((remain, Some(sub_ifd)));
-            }
+        let mut sub_ifds = Vec::new();
+        for offset in entry.map(|x| x.sub_ifd_offsets).unwrap_or_default() {
+            tracing::debug!(offset, "sub-ifd");
+            let Some(data) = self.ifd_data.get(offset as usize..) else {
+                let need = offset as usize - self.ifd_data.len() + 2;
+                return Err(nom::Err::Incomplete(Needed::new(need)));
+            };
+            // `offset` is relative to the current IFD, while the sub-IFD
+            // offset is relative to the TIFF header like the others
+            sub_ifds.push(IfdHeaderTravel::new(
+                data,
+                self.offset + offset,
+                self.endian,
+            ));
         }
 
-        Ok((remain, None))
+        Ok((remain, sub_ifds))
     }
 
     #[tracing::instrument(skip(self))]
@@ -155,10 +174,10 @@ impl<'a> IfdHeaderTravel<'a> {
 
         // parse entries
         for _ in 0..entry_num {
-            let (_, sub_ifd) = self.parse_ifd_entry_header(pos as u32)?;
+            let (_, entry_sub_ifds) = self.parse_ifd_entry_header(pos as u32)?;
             pos += IFD_ENTRY_SIZE;
 
-            if let Some(ifd) = sub_ifd {
+            for ifd in entry_sub_ifds {
                 if ifd.offset <= self.offset {
                     tracing::error!(
                         current_ifd_offset = self.offset,
@@ -0,0 +1,351 @@
+use std::{
+    fmt::Display,
+    io::{self, Read, Seek, SeekFrom},
+};
+
+use crate::{heif::read_box_header, ExifIter, MediaParser, MediaSource, MediaType, TrackInfo};
+
+/// Max number of boxes/segments listed in a [`ParseExplanation`].
+const MAX_LISTED_ITEMS: usize = 64;
+
+/// A human-readable report of how a file is parsed, see
+/// [`MediaParser::explain`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ParseExplanation {
+    /// The detected media type, `None` if the format is unrecognized
+    pub media_type: Option<MediaType>,
+    /// The diagnostic steps, in the order they were taken
+    pub steps: Vec<String>,
+    /// Whether the Exif data or the track info was located successfully
+    pub success: bool,
+}
+
+impl ParseExplanation {
+    fn step(&mut self, step: impl Into<String>) {
+        self.steps.push(step.into());
+    }
+}
+
+impl Display for ParseExplanation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (i, step) in self.steps.iter().enumerate() {
+            writeln!(f, "{:>2}. {step}", i + 1)?;
+        }
+        Ok(())
+    }
+}
+
+impl MediaParser {
+    /// Explains how the media in `reader` is parsed, for diagnosing files
+    /// which fail to parse, e.g. with `UnrecognizedFileFormat` or
+    /// "Exif not found" errors.
+    ///
+    /// The report contains the detected media type, the top level boxes or
+    /// segments found, and where the Exif data/track info is located, or why
+    /// it's not. Exif entries are not decoded.
+    ///
+    /// Unlike [`Self::parse`], a raw reader rather than a [`MediaSource`] is
+    /// accepted, since creating a `MediaSource` fails for unrecognized
+    /// formats, which is one of the cases to be explained.
+    ///
+    /// ```rust
+    /// use nom_exif::*;
+    /// use std::fs::File;
+    ///
+    /// let mut parser = MediaParser::new();
+    /// let explanation = parser.explain(File::open("./testdata/no-exif.jpg").unwrap());
+    /// assert_eq!(explanation.media_type, Some(MediaType::Jpeg));
+    /// assert!(!explanation.success);
+    /// println!("{explanation}");
+    /// ```
+    pub fn explain<R: Read + Seek>(&mut self, mut reader: R) -> ParseExplanation {
+        let mut ex = ParseExplanation::default();
+        if let Err(e) = self.do_explain(&mut reader, &mut ex) {
+            ex.step(format!("I/O error: {e}"));
+        }
+        ex
+    }
+
+    fn do_explain<R: Read + Seek>(
+        &mut self,
+        reader: &mut R,
+        ex: &mut ParseExplanation,
+    ) -> io::Result<()> {
+        let start = reader.stream_position()?;
+
+        let ms = match MediaSource::seekable(&mut *reader) {
+            Ok(ms) => ms,
+            Err(e) => {
+                ex.step(format!("media type detection failed: {e}"));
+                reader.seek(SeekFrom::Start(start))?;
+                explain_unrecognized(reader, ex)?;
+                return Ok(());
+            }
+        };
+        let media_type = ms.media_type();
+        ex.media_type = Some(media_type);
+        ex.step(format!(
+            "detected media type: {media_type:?} (from the first {} bytes)",
+            ms.buf.len()
+        ));
+        drop(ms);
+
+        reader.seek(SeekFrom::Start(start))?;
+        match media_type {
+            MediaType::Jpeg => list_jpeg_segments(reader, ex)?,
+            MediaType::Heic
+            | MediaType::Heif
+            | MediaType::Avif
+            | MediaType::Cr3
+            | MediaType::Jp2
+            | MediaType::QuickTime
+            | MediaType::Mp4
+            | MediaType::ThreeGpp => list_boxes(reader, start, ex)?,
+            MediaType::Tiff
+            | MediaType::Raf
+            | MediaType::Png
+            | MediaType::WebP
+            | MediaType::Webm
+            | MediaType::Matroska => ex.step(format!(
+                "structure listing is not supported for {media_type:?}"
+            )),
+        }
+
+        reader.seek(SeekFrom::Start(start))?;
+        let ms = match MediaSource::seekable(&mut *reader) {
+            Ok(ms) => ms,
+            Err(e) => {
+                ex.step(format!("media type detection failed: {e}"));
+                return Ok(());
+            }
+        };
+        if media_type.is_image() {
+            match self.parse::<_, _, ExifIter>(ms) {
+                Ok(iter) => {
+                    ex.success = true;
+                    ex.step(format!("Exif data located ({} bytes)", iter.data_len()));
+                    if let Some(o) = iter.container_orientation() {
+                        ex.step(format!("container declares orientation {o}"));
+                    }
+                }
+                Err(e) => ex.step(format!("Exif data not located: {e}")),
+            }
+        } else {
+            match self.parse::<_, _, TrackInfo>(ms) {
+                Ok(info) => {
+                    ex.success = true;
+                    ex.step(format!(
+                        "track info parsed ({} entries)",
+                        info.iter().count()
+                    ));
+                }
+                Err(e) => ex.step(format!("track info not parsed: {e}")),
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Describes the first bytes of an unrecognized file.
+fn explain_unrecognized<R: Read>(reader: &mut R, ex: &mut ParseExplanation) -> io::Result<()> {
+    let mut header = Vec::new();
+    reader.take(16).read_to_end(&mut header)?;
+    if header.is_empty() {
+        ex.step("the file is empty");
+        return Ok(());
+    }
+    ex.step(format!("first bytes: {}", hex(&header)));
+
+    if header.len() >= 12 && &header[4..8] == b"ftyp" {
+        ex.step(format!(
+            "looks like an ISOBMFF file, but the major brand {:?} is not supported",
+            String::from_utf8_lossy(&header[8..12])
+        ));
+    } else if header.len() >= 8 && header[4..8].iter().all(u8::is_ascii_alphanumeric) {
+        ex.step(format!(
+            "looks like an ISOBMFF box {:?}, but there is no `ftyp` box",
+            String::from_utf8_lossy(&header[4..8])
+        ));
+    } else if header.starts_with(&[0xff, 0xd8]) {
+        ex.step("looks like a JPEG file, but the first segment is invalid");
+    }
+    Ok(())
+}
+
+fn list_boxes<R: Read + Seek>(
+    reader: &mut R,
+    start: u64,
+    ex: &mut ParseExplanation,
+) -> io::Result<()> {
+    let end = reader.seek(SeekFrom::End(0))?;
+    let mut pos = start;
+    for _ in 0..MAX_LISTED_ITEMS {
+        let Some((box_type, size, _)) = read_box_header(reader, pos, end)? else {
+            break;
+        };
+        ex.step(format!(
+            "box {:?} at offset {} ({size} bytes)",
+            String::from_utf8_lossy(&box_type),
+            pos - start
+        ));
+        pos += size;
+    }
+    if pos < end {
+        ex.step(format!(
+            "stopped listing boxes at offset {}, {} bytes remain",
+            pos - start,
+            end - pos
+        ));
+    }
+    Ok(())
+}
+
+fn list_jpeg_segments<R: Read + Seek>(reader: &mut R, ex: &mut ParseExplanation) -> io::Result<()> {
+    // Skip SOI
+    reader.seek(SeekFrom::Current(2))?;
+    let mut offset = 2;
+    for _ in 0..MAX_LISTED_ITEMS {
+        let mut header = [0; 4];
+        if reader.read_exact(&mut header).is_err() || header[0] != 0xff {
+            ex.step(format!("no valid segment at offset {offset}"));
+            return Ok(());
+        }
+        let marker = header[1];
+        let size = u16::from_be_bytes([header[2], header[3]]) as u64;
+        // The length includes its own two bytes
+        if size < 2 {
+            ex.step(format!(
+                "invalid segment {} at offset {offset} (length {size})",
+                marker_name(marker)
+            ));
+            return Ok(());
+        }
+
+        let mut step = format!(
+            "segment {} at offset {offset} ({size} bytes)",
+            marker_name(marker)
+        );
+        if marker == 0xe1 {
+            let mut ident = [0; 6];
+            if reader.read_exact(&mut ident).is_ok() {
+                reader.seek(SeekFrom::Current(-6))?;
+                if &ident == b"Exif\0\0" {
+                    step.push_str(", contains Exif data");
+                }
+            }
+        }
+        ex.step(step);
+
+        // Image data follows the SOS segment, no more metadata after it
+        if marker == 0xda {
+            return Ok(());
+        }
+        reader.seek(SeekFrom::Current(size as i64 - 2))?;
+        offset += size + 2;
+    }
+    Ok(())
+}
+
+fn marker_name(marker: u8) -> String {
+    match marker {
+        0xe0..=0xef => format!("APP{}", marker - 0xe0),
+        0xc0..=0xc3 => format!("SOF{}", marker - 0xc0),
+        0xc4 => "DHT".to_string(),
+        0xda => "SOS".to_string(),
+        0xdb => "DQT".to_string(),
+        0xdd => "DRI".to_string(),
+        0xfe => "COM".to_string(),
+        x => format!("0xFF{x:02X}"),
+    }
+}
+
+fn hex(data: &[u8]) -> String {
+    data.iter()
+        .map(|x| format!("{x:02x}"))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use crate::testkit::open_sample;
+
+    use super::*;
+
+    #[test]
+    fn explain_jpeg() {
+        let _ = tracing_subscriber::fmt().with_test_writer().try_init();
+
+        let mut parser = MediaParser::new();
+        let ex = parser.explain(open_sample("exif.jpg").unwrap());
+        assert_eq!(ex.media_type, Some(MediaType::Jpeg));
+        assert!(ex.success);
+        assert!(ex
+            .steps
+            .iter()
+            .any(|x| x.contains("APP1") && x.contains("Exif")));
+        assert!(ex.steps.last().unwrap().starts_with("Exif data located"));
+
+        let ex = parser.explain(open_sample("no-exif.jpg").unwrap());
+        assert!(!ex.success);
+        assert!(ex.steps.last().unwrap().contains("Exif not found"), "{ex}");
+    }
+
+    #[test]
+    fn explain_corrupt_jpeg_segment() {
+        let _ = tracing_subscriber::fmt().with_test_writer().try_init();
+
+        let mut data = vec![0xff, 0xd8, 0xff, 0xe0, 0x00, 0x00];
+        data.extend([0; 32]);
+        let ex = MediaParser::new().explain(Cursor::new(data));
+        assert_eq!(ex.media_type, Some(MediaType::Jpeg));
+        let invalid = ex.steps.iter().filter(|x| x.starts_with("invalid segment"));
+        assert_eq!(
+            invalid.collect::<Vec<_>>(),
+            ["invalid segment APP0 at offset 2 (length 0)"],
+            "{ex}"
+        );
+        assert!(!ex.steps.iter().any(|x| x.starts_with("segment APP0")));
+    }
+
+    #[test]
+    fn explain_bmff() {
+        let _ = tracing_subscriber::fmt().with_test_writer().try_init();
+
+        let mut parser = MediaParser::new();
+        let ex = parser.explain(open_sample("exif.heic").unwrap());
+        assert_eq!(ex.media_type, Some(MediaType::Heic));
+        assert!(ex.success);
+        assert!(ex
+            .steps
+            .contains(&r#"box "ftyp" at offset 0 (36 bytes)"#.to_string()));
+        assert!(ex
+            .steps
+            .contains(&"container declares orientation 6".to_string()));
+
+        let ex = parser.explain(open_sample("meta.mov").unwrap());
+        assert_eq!(ex.media_type, Some(MediaType::QuickTime));
+        assert!(ex.success);
+        assert!(ex.steps.iter().any(|x| x.starts_with(r#"box "moov""#)));
+    }
+
+    #[test]
+    fn explain_unrecognized_format() {
+        let _ = tracing_subscriber::fmt().with_test_writer().try_init();
+
+        let mut parser = MediaParser::new();
+        let ex = parser.explain(open_sample("compatible-brands-fail.heic").unwrap());
+        assert_eq!(ex.media_type, None);
+        assert!(!ex.success);
+        assert!(ex.steps[2].contains("major brand"), "{ex}");
+
+        let ex = parser.explain(Cursor::new(b"hello world".to_vec()));
+        assert_eq!(ex.media_type, None);
+        assert_eq!(ex.steps[1], "first bytes: 68 65 6c 6c 6f 20 77 6f 72 6c 64");
+
+        let ex = parser.explain(Cursor::new(Vec::new()));
+        assert_eq!(ex.steps.last().unwrap(), "the file is empty");
+    }
+}
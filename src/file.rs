@@ -1,18 +1,23 @@
 use nom::{bytes::complete, multi::many0, FindSubstring};
 use std::{
+    cmp::min,
     fmt::Display,
     io::{Cursor, Read},
+    str::FromStr,
 };
 
 use crate::{
-    bbox::{travel_header, BoxHolder},
+    bbox::{travel_header, BoxHeader, BoxHolder},
     ebml::element::parse_ebml_doc_type,
     error::{ParsedError, ParsingError},
     exif::TiffHeader,
+    jp2::check_jp2,
     jpeg::check_jpeg,
     loader::Load,
+    png::check_png,
     raf::RafInfo,
     slice::SubsliceRange,
+    webp::check_webp,
 };
 
 const HEIF_HEIC_BRAND_NAMES: &[&[u8]] = &[
@@ -28,11 +33,17 @@ const HEIF_HEIC_BRAND_NAMES: &[&[u8]] = &[
 
 const HEIC_BRAND_NAMES: &[&[u8]] = &[b"heic", b"heix", b"heim", b"heis"];
 
+/// AVIF images & image sequences, which are HEIF files with AV1 coded items.
+const AVIF_BRAND_NAMES: &[&[u8]] = &[b"avif", b"avis"];
+
+/// Canon RAW v3, an ISOBMFF file with the Exif data in a `moov/uuid` box.
+const CR3_BRAND_NAME: &[u8] = b"crx ";
+
 // TODO: Refer to the information on the website https://www.ftyps.com to add
 // other less common MP4 brands.
 const MP4_BRAND_NAMES: &[&str] = &[
     "3g2a", "3g2b", "3g2c", "3ge6", "3ge7", "3gg6", "3gp4", "3gp5", "3gp6", "3gs7", "avc1", "mp41",
-    "mp42", "iso2", "isom", "vfj1",
+    "mp42", "iso2", "isom", "vfj1", "M4A ",
 ];
 
 const QT_BRAND_NAMES: &[&str] = &["qt  ", "mqt "];
@@ -65,6 +76,11 @@ pub(crate) enum MimeImage {
     Heif,
     Tiff,
     Raf, // Fujifilm RAW, image/x-fuji-raf
+    Jp2, // JPEG 2000, image/jp2
+    Png,
+    WebP,
+    Avif,
+    Cr3, // Canon RAW, image/x-canon-cr3
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Copy)]
@@ -76,10 +92,165 @@ pub(crate) enum MimeVideo {
     _3gpp,
 }
 
+/// The specific media format detected when building a
+/// [`MediaSource`](crate::MediaSource).
+#[derive(Debug, Clone, PartialEq, Eq, Copy, Hash)]
+#[non_exhaustive]
+pub enum MediaType {
+    Jpeg,
+    Heic,
+    Heif,
+    Tiff,
+    /// Fujifilm RAW
+    Raf,
+    /// JPEG 2000
+    Jp2,
+    Png,
+    WebP,
+    Avif,
+    /// Canon RAW
+    Cr3,
+    QuickTime,
+    Mp4,
+    Webm,
+    Matroska,
+    ThreeGpp,
+}
+
+impl MediaType {
+    /// Returns true if the media is an image, i.e. it contains Exif data
+    /// rather than tracks.
+    pub fn is_image(&self) -> bool {
+        matches!(
+            self,
+            Self::Jpeg
+                | Self::Heic
+                | Self::Heif
+                | Self::Tiff
+                | Self::Raf
+                | Self::Jp2
+                | Self::Png
+                | Self::WebP
+                | Self::Avif
+                | Self::Cr3
+        )
+    }
+
+    /// Returns true if the media is a video/audio container, i.e. it contains
+    /// tracks rather than Exif data.
+    pub fn is_video(&self) -> bool {
+        !self.is_image()
+    }
+
+    /// Returns the MIME type string of the media, e.g. `"image/heic"`.
+    ///
+    /// The returned string can be parsed back with [`str::parse`].
+    pub fn mime_type(&self) -> &'static str {
+        match self {
+            Self::Jpeg => "image/jpeg",
+            Self::Heic => "image/heic",
+            Self::Heif => "image/heif",
+            Self::Tiff => "image/tiff",
+            Self::Raf => "image/x-fuji-raf",
+            Self::Jp2 => "image/jp2",
+            Self::Png => "image/png",
+            Self::WebP => "image/webp",
+            Self::Avif => "image/avif",
+            Self::Cr3 => "image/x-canon-cr3",
+            Self::QuickTime => "video/quicktime",
+            Self::Mp4 => "video/mp4",
+            Self::Webm => "video/webm",
+            Self::Matroska => "video/x-matroska",
+            Self::ThreeGpp => "video/3gpp",
+        }
+    }
+}
+
+const MEDIA_TYPES: &[MediaType] = &[
+    MediaType::Jpeg,
+    MediaType::Heic,
+    MediaType::Heif,
+    MediaType::Tiff,
+    MediaType::Raf,
+    MediaType::Jp2,
+    MediaType::Png,
+    MediaType::WebP,
+    MediaType::Avif,
+    MediaType::Cr3,
+    MediaType::QuickTime,
+    MediaType::Mp4,
+    MediaType::Webm,
+    MediaType::Matroska,
+    MediaType::ThreeGpp,
+];
+
+impl Display for MediaType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Jpeg => "JPEG image",
+            Self::Heic => "HEIC image",
+            Self::Heif => "HEIF image",
+            Self::Tiff => "TIFF image",
+            Self::Raf => "Fujifilm RAW image",
+            Self::Jp2 => "JPEG 2000 image",
+            Self::Png => "PNG image",
+            Self::WebP => "WebP image",
+            Self::Avif => "AVIF image",
+            Self::Cr3 => "Canon RAW image",
+            Self::QuickTime => "QuickTime video",
+            Self::Mp4 => "MP4 video",
+            Self::Webm => "WebM video",
+            Self::Matroska => "Matroska video",
+            Self::ThreeGpp => "3GPP video",
+        }
+        .fmt(f)
+    }
+}
+
+/// Parses a MIME type string (case-insensitive), e.g. `"video/mp4"`, as
+/// returned by [`MediaType::mime_type`].
+impl FromStr for MediaType {
+    type Err = crate::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        MEDIA_TYPES
+            .iter()
+            .find(|x| x.mime_type().eq_ignore_ascii_case(s))
+            .copied()
+            .ok_or(crate::Error::UnrecognizedFileFormat)
+    }
+}
+
+impl From<Mime> for MediaType {
+    fn from(mime: Mime) -> Self {
+        match mime {
+            Mime::Image(MimeImage::Jpeg) => Self::Jpeg,
+            Mime::Image(MimeImage::Heic) => Self::Heic,
+            Mime::Image(MimeImage::Heif) => Self::Heif,
+            Mime::Image(MimeImage::Tiff) => Self::Tiff,
+            Mime::Image(MimeImage::Raf) => Self::Raf,
+            Mime::Image(MimeImage::Jp2) => Self::Jp2,
+            Mime::Image(MimeImage::Png) => Self::Png,
+            Mime::Image(MimeImage::WebP) => Self::WebP,
+            Mime::Image(MimeImage::Avif) => Self::Avif,
+            Mime::Image(MimeImage::Cr3) => Self::Cr3,
+            Mime::Video(MimeVideo::QuickTime) => Self::QuickTime,
+            Mime::Video(MimeVideo::Mp4) => Self::Mp4,
+            Mime::Video(MimeVideo::Webm) => Self::Webm,
+            Mime::Video(MimeVideo::Matroska) => Self::Matroska,
+            Mime::Video(MimeVideo::_3gpp) => Self::ThreeGpp,
+        }
+    }
+}
+
 impl TryFrom<&[u8]> for Mime {
     type Error = crate::Error;
     fn try_from(input: &[u8]) -> Result<Self, Self::Error> {
-        let mime = if let Ok(x) = parse_bmff_mime(input) {
+        let mime = if check_jp2(input).is_ok() {
+            // JP2 boxes look like ISOBMFF ones, check it first
+            Mime::Image(MimeImage::Jp2)
+        } else if let Ok(x) = parse_bmff_mime(input) {
             x
         } else if let Ok(x) = get_ebml_doc_type(input) {
             if x == "webm" {
@@ -91,6 +262,10 @@ impl TryFrom<&[u8]> for Mime {
             Mime::Image(MimeImage::Tiff)
         } else if check_jpeg(input).is_ok() {
             Mime::Image(MimeImage::Jpeg)
+        } else if check_png(input).is_ok() {
+            Mime::Image(MimeImage::Png)
+        } else if check_webp(input).is_ok() {
+            Mime::Image(MimeImage::WebP)
         } else if RafInfo::check(input).is_ok() {
             Mime::Image(MimeImage::Raf)
         } else {
@@ -200,11 +375,31 @@ fn parse_bmff_mime(input: &[u8]) -> crate::Result<Mime> {
         return Ok(Mime::Video(MimeVideo::QuickTime));
     }
 
+    if major_brand == CR3_BRAND_NAME {
+        return Ok(Mime::Image(MimeImage::Cr3));
+    }
+
+    // Check if it is an AVIF file, which is a HEIF file as well
+    if AVIF_BRAND_NAMES.contains(&major_brand) {
+        return Ok(Mime::Image(MimeImage::Avif));
+    }
+
+    let compatible_brands = ftyp.body_data();
+    let is_avif_compatible = || {
+        AVIF_BRAND_NAMES
+            .iter()
+            .any(|x| compatible_brands.find_substring(*x).is_some())
+    };
+
     // Check if it is a HEIF file
     if HEIF_HEIC_BRAND_NAMES.contains(&major_brand) {
         if HEIC_BRAND_NAMES.contains(&major_brand) {
             return Ok(Mime::Image(MimeImage::Heic));
         }
+        // e.g. "mif1" with "avif" as a compatible brand
+        if is_avif_compatible() {
+            return Ok(Mime::Image(MimeImage::Avif));
+        }
         return Ok(Mime::Image(MimeImage::Heif));
     }
 
@@ -217,7 +412,6 @@ fn parse_bmff_mime(input: &[u8]) -> crate::Result<Mime> {
     }
 
     // Check compatible brands
-    let compatible_brands = ftyp.body_data();
 
     if QT_BRAND_NAMES
         .iter()
@@ -226,6 +420,10 @@ fn parse_bmff_mime(input: &[u8]) -> crate::Result<Mime> {
         return Ok(Mime::Video(MimeVideo::QuickTime));
     }
 
+    if is_avif_compatible() {
+        return Ok(Mime::Image(MimeImage::Avif));
+    }
+
     if HEIF_HEIC_BRAND_NAMES
         .iter()
         .any(|x| compatible_brands.find_substring(*x).is_some())
@@ -352,6 +550,28 @@ fn get_compatible_brands(body: &[u8]) -> crate::Result<Vec<&[u8]>> {
     Ok(brands)
 }
 
+/// Returns the compatible brands of the `ftyp` box at the beginning of
+/// `input`. Brands beyond the end of `input` are omitted, since `input` may
+/// be only a probe of the file.
+pub(crate) fn parse_compatible_brands(input: &[u8]) -> Vec<String> {
+    let Ok((remain, header)) = BoxHeader::parse(input) else {
+        return Vec::new();
+    };
+    if header.box_type != "ftyp" {
+        return Vec::new();
+    }
+
+    let body = &remain[..min(remain.len() as u64, header.body_size()) as usize]; // Safe-slice
+
+    // Skip major brand & minor version
+    let brands = body.get(8..).unwrap_or_default();
+    get_compatible_brands(brands)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|x| x.iter().map(|b| *b as char).collect())
+        .collect()
+}
+
 #[allow(deprecated)]
 #[cfg(test)]
 mod tests {
@@ -365,6 +585,38 @@ mod tests {
 
     use crate::testkit::{open_sample, read_sample};
 
+    #[test]
+    fn media_type_strings() {
+        let _ = tracing_subscriber::fmt().with_test_writer().try_init();
+
+        for t in MEDIA_TYPES {
+            assert_eq!(t.mime_type().parse::<MediaType>().unwrap(), *t);
+            assert_eq!(
+                t.mime_type().to_uppercase().parse::<MediaType>().unwrap(),
+                *t
+            );
+        }
+        assert_eq!(MediaType::Heic.to_string(), "HEIC image");
+        assert_eq!(MediaType::QuickTime.to_string(), "QuickTime video");
+        assert!("image/gif".parse::<MediaType>().is_err());
+    }
+
+    #[test_case("exif.heic", &["mif1", "MiHE", "miaf", "MiHB", "heic"])]
+    #[test_case("meta.mp4", &["isom", "mp41", "mp42"])]
+    #[test_case("meta.mov", &["qt  "])]
+    #[test_case("exif.jpg", &[])]
+    fn compatible_brands(path: &str, brands: &[&str]) {
+        let _ = tracing_subscriber::fmt().with_test_writer().try_init();
+
+        let buf = read_sample(path).unwrap();
+        assert_eq!(parse_compatible_brands(&buf), brands);
+        // Brands beyond the probe are omitted
+        if !brands.is_empty() {
+            assert_eq!(parse_compatible_brands(&buf[..19]), &[] as &[&str]);
+            assert_eq!(parse_compatible_brands(&buf[..23]), brands[..1]);
+        }
+    }
+
     #[test_case("exif.heic", Image(Heic))]
     #[test_case("exif.jpg", Image(Jpeg))]
     #[test_case("fujifilm_x_t1_01.raf.meta", Image(Raf))]
@@ -376,6 +628,7 @@ mod tests {
     #[test_case("mkv_640x360.mkv", Video(Matroska))]
     #[test_case("mka.mka", Video(Matroska))]
     #[test_case("3gp_640x360.3gp", Video(_3gpp))]
+    #[test_case("mdir.m4a", Video(Mp4))]
     fn mime(path: &str, mime: Mime) {
         let data = read_sample(path).unwrap();
         let m: Mime = data.deref().try_into().unwrap();
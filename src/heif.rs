@@ -1,10 +1,13 @@
-use std::io::{Read, Seek};
+use std::cmp::min;
+use std::io::{self, Read, Seek, SeekFrom};
 
 use nom::combinator::fail;
 use nom::{number::complete::be_u32, IResult};
 
 use crate::bbox::find_box;
+use crate::error::{nom_error_to_parsing_error_with_state, ParsingError, ParsingErrorState};
 use crate::exif::Exif;
+use crate::parser::ParsingState;
 use crate::{
     bbox::{BoxHolder, MetaBox, ParseBox},
     exif::check_exif_header,
@@ -98,6 +101,164 @@ pub(crate) fn extract_exif_with_meta<'a>(
     }
 }
 
+/// Extract the XMP packet from the bytes of a HEIF/HEIC file, i.e. the data
+/// of its `mime` item with the "application/rdf+xml" content type.
+///
+/// If the packet is beyond the loaded bytes, a [`ParsingError::ClearAndSkip`]
+/// is returned, and the buffer is expected to start at the packet when
+/// called with the returned [`ParsingState::HeifXmp`].
+pub(crate) fn extract_xmp_data(
+    buf: &[u8],
+    state: Option<ParsingState>,
+) -> Result<Option<&[u8]>, ParsingErrorState> {
+    match state {
+        Some(ParsingState::HeifXmp { xmp_size }) => {
+            let (_, data) = nom::bytes::streaming::take(xmp_size)(buf)
+                .map_err(|e| nom_error_to_parsing_error_with_state(e, state.clone()))?;
+            Ok(Some(data))
+        }
+        None => {
+            let (_, meta) =
+                parse_meta_box(buf).map_err(|e| nom_error_to_parsing_error_with_state(e, None))?;
+            let Some(range) = meta.and_then(|meta| meta.xmp_data_offset()) else {
+                return Ok(None);
+            };
+
+            if range.end > buf.len() {
+                let state = Some(ParsingState::HeifXmp {
+                    xmp_size: range.len(),
+                });
+                let clear_and_skip = ParsingError::ClearAndSkip(range.start);
+                Err(ParsingErrorState::new(clear_and_skip, state))
+            } else {
+                Ok(Some(&buf[range])) // Safe-slice
+            }
+        }
+        _ => unreachable!(),
+    }
+}
+
+/// Extract the ICC profile of the primary item from the bytes of a
+/// HEIF/HEIC file, which is stored in a `colr` property.
+pub(crate) fn extract_icc_profile(buf: &[u8]) -> Result<Option<Vec<u8>>, ParsingErrorState> {
+    let (_, meta) =
+        parse_meta_box(buf).map_err(|e| nom_error_to_parsing_error_with_state(e, None))?;
+    Ok(meta.and_then(|meta| meta.primary_icc_profile().map(|x| x.to_vec())))
+}
+
+/// Size of the chunks read when scanning an `mdat` box for an embedded
+/// motion clip.
+const SCAN_CHUNK_SIZE: usize = 64 * 1024;
+
+/// Locates the motion clip (MOV/MP4) embedded in a HEIF/HEIC file, e.g. a
+/// Live Photo or a Motion Photo, and returns the offset from which it can be
+/// parsed as a video.
+///
+/// Two layouts are supported:
+///
+/// - The boxes of the clip (`wide`, `mdat`, `moov`, ...) are appended after
+///   the top level boxes of the image.
+///
+/// - The clip is stored inside an `mdat` box of the image, usually at its
+///   end. The `mdat` body is scanned for it.
+///
+/// In both cases, the clip is expected to start with a `ftyp` or `wide` box,
+/// and lead to a `moov` box.
+pub(crate) fn find_embedded_mov<R: Read + Seek>(
+    reader: &mut R,
+    start: u64,
+) -> io::Result<Option<u64>> {
+    let end = reader.seek(SeekFrom::End(0))?;
+
+    let mut clip_start = None;
+    let mut mdats = Vec::new();
+    let mut pos = start;
+    while let Some((box_type, size, header_size)) = read_box_header(reader, pos, end)? {
+        match &box_type {
+            // The first `ftyp` belongs to the image itself
+            b"ftyp" | b"wide" if pos != start && clip_start.is_none() => clip_start = Some(pos),
+            b"moov" => return Ok(clip_start),
+            b"mdat" => mdats.push((pos + header_size, pos + size)),
+            _ => (),
+        }
+        pos += size;
+    }
+
+    for (body_start, body_end) in mdats {
+        if let Some(pos) = scan_for_mov(reader, body_start, body_end)? {
+            return Ok(Some(pos));
+        }
+    }
+    Ok(None)
+}
+
+fn scan_for_mov<R: Read + Seek>(reader: &mut R, start: u64, end: u64) -> io::Result<Option<u64>> {
+    let mut buf = vec![0; SCAN_CHUNK_SIZE];
+    let mut chunk_start = start;
+    while chunk_start + 8 <= end {
+        let len = min(SCAN_CHUNK_SIZE as u64, end - chunk_start) as usize;
+        reader.seek(SeekFrom::Start(chunk_start))?;
+        reader.read_exact(&mut buf[..len])?;
+
+        for i in 4..=len - 4 {
+            if !matches!(&buf[i..i + 4], b"ftyp" | b"wide") {
+                continue;
+            }
+            let pos = chunk_start + i as u64 - 4;
+            if leads_to_moov(reader, pos, end)? {
+                return Ok(Some(pos));
+            }
+        }
+
+        if chunk_start + len as u64 >= end {
+            break;
+        }
+        // Overlap the chunks, so that box headers across chunks are found
+        chunk_start += len as u64 - 7;
+    }
+    Ok(None)
+}
+
+/// Walks the boxes from `pos` and checks if there is a `moov` box before
+/// `end`.
+fn leads_to_moov<R: Read + Seek>(reader: &mut R, mut pos: u64, end: u64) -> io::Result<bool> {
+    while let Some((box_type, size, _)) = read_box_header(reader, pos, end)? {
+        if &box_type == b"moov" {
+            return Ok(true);
+        }
+        pos += size;
+    }
+    Ok(false)
+}
+
+/// Reads the box header at `pos`, returns the box type, box size and header
+/// size, or `None` if there is no valid box which ends before `end`.
+pub(crate) fn read_box_header<R: Read + Seek>(
+    reader: &mut R,
+    pos: u64,
+    end: u64,
+) -> io::Result<Option<([u8; 4], u64, u64)>> {
+    if pos + 8 > end {
+        return Ok(None);
+    }
+    let mut header = [0; 16];
+    let header_len = min(16, end - pos) as usize;
+    reader.seek(SeekFrom::Start(pos))?;
+    reader.read_exact(&mut header[..header_len])?;
+
+    let box_type: [u8; 4] = header[4..8].try_into().unwrap(); // Safe unwrap
+    let (size, header_size) = match u32::from_be_bytes(header[..4].try_into().unwrap()) {
+        0 => (end - pos, 8),
+        1 if header_len == 16 => (u64::from_be_bytes(header[8..].try_into().unwrap()), 16),
+        1 => return Ok(None),
+        x => (x as u64, 8),
+    };
+    if size < header_size || size > end - pos {
+        return Ok(None);
+    }
+    Ok(Some((box_type, size, header_size)))
+}
+
 #[allow(deprecated)]
 #[cfg(test)]
 mod tests {
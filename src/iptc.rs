@@ -0,0 +1,229 @@
+//! IPTC-IIM metadata, stored in the Photoshop image resources (`8BIM`) of
+//! a JPEG `APP13` segment.
+
+/// Identifier at the start of an `APP13` segment holding Photoshop image
+/// resources.
+pub(crate) const PHOTOSHOP_IDENT: &[u8] = b"Photoshop 3.0\0";
+
+/// Image resource ID of the IPTC-NAA record.
+const IPTC_RESOURCE_ID: u16 = 0x0404;
+
+/// Tag marker which starts every IIM dataset.
+const DATASET_MARKER: u8 = 0x1C;
+
+/// Escape sequence of the `1:90` (Coded Character Set) dataset declaring
+/// UTF-8.
+const UTF8_ESCAPE: &[u8] = b"\x1b%G";
+
+/// IPTC-IIM datasets, parsed by [`MediaParser::parse_iptc`](crate::MediaParser::parse_iptc).
+///
+/// The raw IIM bytes are kept, so that datasets other than the common ones
+/// can be decoded by the caller. Text values are decoded as UTF-8 if the
+/// `1:90` dataset declares it, or if the value is valid UTF-8, otherwise as
+/// Latin-1.
+///
+/// ```rust
+/// use nom_exif::*;
+///
+/// let mut parser = MediaParser::new();
+/// let ms = MediaSource::file_path("./testdata/exif.jpg").unwrap();
+///
+/// // This image has no APP13 segment
+/// assert!(parser.parse_iptc(ms).unwrap().is_none());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Iptc {
+    data: Vec<u8>,
+}
+
+impl Iptc {
+    pub(crate) fn new(data: Vec<u8>) -> Self {
+        Self { data }
+    }
+
+    /// Returns the raw IIM dataset bytes.
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Returns the `2:05` (Object Name) dataset, i.e. the title.
+    pub fn object_name(&self) -> Option<String> {
+        self.get(2, 5).into_iter().next()
+    }
+
+    /// Returns all the `2:25` (Keywords) datasets.
+    pub fn keywords(&self) -> Vec<String> {
+        self.get(2, 25)
+    }
+
+    /// Returns the `2:120` (Caption/Abstract) dataset.
+    pub fn caption(&self) -> Option<String> {
+        self.get(2, 120).into_iter().next()
+    }
+
+    /// Returns the decoded values of all the datasets with the given record
+    /// & dataset numbers, e.g. `get(2, 80)` for By-line.
+    pub fn get(&self, record: u8, dataset: u8) -> Vec<String> {
+        let utf8 = self
+            .datasets()
+            .any(|(r, d, value)| r == 1 && d == 90 && value == UTF8_ESCAPE);
+        self.datasets()
+            .filter(|(r, d, _)| *r == record && *d == dataset)
+            .map(|(_, _, value)| decode_text(value, utf8))
+            .collect()
+    }
+
+    /// Iterates `(record, dataset, value)` of the datasets, stops at the
+    /// first malformed one.
+    fn datasets(&self) -> impl Iterator<Item = DataSet<'_>> {
+        let mut remain = self.data.as_slice();
+        std::iter::from_fn(move || {
+            let (dataset, rem) = parse_dataset(remain)?;
+            remain = rem;
+            Some(dataset)
+        })
+    }
+}
+
+/// `(record, dataset, value)` of an IIM dataset
+type DataSet<'a> = (u8, u8, &'a [u8]);
+
+fn parse_dataset(input: &[u8]) -> Option<(DataSet<'_>, &[u8])> {
+    let [DATASET_MARKER, record, dataset, l1, l2, remain @ ..] = input else {
+        return None;
+    };
+    let len = u16::from_be_bytes([*l1, *l2]);
+
+    // Extended dataset, the lower 15 bits is the size of the length field
+    let (len, remain) = if len & 0x8000 != 0 {
+        let n = (len & 0x7FFF) as usize;
+        if n > 4 {
+            return None;
+        }
+        let (len, remain) = remain.split_at_checked(n)?;
+        let len = len.iter().fold(0_usize, |acc, x| (acc << 8) | *x as usize);
+        (len, remain)
+    } else {
+        (len as usize, remain)
+    };
+
+    let (value, remain) = remain.split_at_checked(len)?;
+    Some(((*record, *dataset, value), remain))
+}
+
+fn decode_text(value: &[u8], utf8: bool) -> String {
+    match std::str::from_utf8(value) {
+        Ok(s) => s.to_owned(),
+        Err(_) if utf8 => String::from_utf8_lossy(value).into_owned(),
+        // Latin-1 maps each byte to the code point of the same value
+        Err(_) => value.iter().map(|x| *x as char).collect(),
+    }
+}
+
+/// Finds the IPTC-NAA resource in the Photoshop image resources of an
+/// `APP13` payload, which starts after [`PHOTOSHOP_IDENT`].
+pub(crate) fn find_iptc_resource(mut input: &[u8]) -> Option<&[u8]> {
+    while let [b'8', b'B', b'I', b'M', i1, i2, remain @ ..] = input {
+        let id = u16::from_be_bytes([*i1, *i2]);
+
+        // Pascal string name, padded to an even size
+        let name_len = *remain.first()? as usize + 1;
+        let remain = remain.get(name_len + name_len % 2..)?;
+
+        let (size, remain) = remain.split_at_checked(4)?;
+        let size = u32::from_be_bytes(size.try_into().unwrap()) as usize;
+        let (data, remain) = remain.split_at_checked(size)?;
+        if id == IPTC_RESOURCE_ID {
+            return Some(data);
+        }
+
+        // Resource data is padded to an even size
+        input = remain.get(size % 2..)?;
+    }
+    None
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    pub(crate) fn make_dataset(record: u8, dataset: u8, value: &[u8]) -> Vec<u8> {
+        let mut buf = vec![DATASET_MARKER, record, dataset];
+        buf.extend_from_slice(&(value.len() as u16).to_be_bytes());
+        buf.extend_from_slice(value);
+        buf
+    }
+
+    /// Builds the payload of a Photoshop `APP13` segment, with a resource
+    /// before the IPTC-NAA one.
+    pub(crate) fn make_app13_payload(iim: &[u8]) -> Vec<u8> {
+        let mut buf = PHOTOSHOP_IDENT.to_vec();
+
+        // 0x0425 (IPTC digest) with a 1-byte name & odd data size
+        buf.extend_from_slice(b"8BIM\x04\x25\x01a");
+        buf.extend_from_slice(&3u32.to_be_bytes());
+        buf.extend_from_slice(b"xyz\0");
+
+        // 0x0404 with an empty name
+        buf.extend_from_slice(b"8BIM\x04\x04\0\0");
+        buf.extend_from_slice(&(iim.len() as u32).to_be_bytes());
+        buf.extend_from_slice(iim);
+        buf
+    }
+
+    #[test]
+    fn iptc_datasets() {
+        let _ = tracing_subscriber::fmt().with_test_writer().try_init();
+
+        let mut iim = make_dataset(1, 90, UTF8_ESCAPE);
+        iim.extend(make_dataset(2, 5, "Café".as_bytes()));
+        iim.extend(make_dataset(2, 25, b"sea"));
+        iim.extend(make_dataset(2, 25, b"sky"));
+        iim.extend(make_dataset(2, 120, b"A caption"));
+
+        let payload = make_app13_payload(&iim);
+        let data = find_iptc_resource(&payload[PHOTOSHOP_IDENT.len()..]).unwrap();
+        assert_eq!(data, iim);
+
+        let iptc = Iptc::new(data.to_vec());
+        assert_eq!(iptc.data(), iim);
+        assert_eq!(iptc.object_name().unwrap(), "Café");
+        assert_eq!(iptc.keywords(), ["sea", "sky"]);
+        assert_eq!(iptc.caption().unwrap(), "A caption");
+        assert!(iptc.get(2, 80).is_empty());
+    }
+
+    #[test]
+    fn iptc_latin1() {
+        let _ = tracing_subscriber::fmt().with_test_writer().try_init();
+
+        let iptc = Iptc::new(make_dataset(2, 5, b"Caf\xe9"));
+        assert_eq!(iptc.object_name().unwrap(), "Café");
+
+        let mut iim = make_dataset(1, 90, UTF8_ESCAPE);
+        iim.extend(make_dataset(2, 5, b"Caf\xe9"));
+        let iptc = Iptc::new(iim);
+        assert_eq!(iptc.object_name().unwrap(), "Caf\u{fffd}");
+    }
+
+    #[test]
+    fn iptc_extended_dataset() {
+        let _ = tracing_subscriber::fmt().with_test_writer().try_init();
+
+        let mut iim = vec![DATASET_MARKER, 2, 120, 0x80, 0x02, 0x00, 0x03];
+        iim.extend_from_slice(b"abc");
+        // Truncated dataset is ignored
+        iim.extend_from_slice(&[DATASET_MARKER, 2, 25, 0x00, 0x10, b'x']);
+
+        let iptc = Iptc::new(iim);
+        assert_eq!(iptc.caption().unwrap(), "abc");
+        assert!(iptc.keywords().is_empty());
+    }
+
+    #[test]
+    fn iptc_resource_not_found() {
+        assert!(find_iptc_resource(b"").is_none());
+        assert!(find_iptc_resource(b"8BIM\x04\x25\0\0\0\0\0\x01x").is_none());
+        assert!(find_iptc_resource(b"8BIM\x04\x04\0\0\0\0\0\x10xx").is_none());
+    }
+}
@@ -0,0 +1,103 @@
+use nom::{bytes::complete, IResult};
+
+use crate::{bbox::travel_while, exif::check_exif_header};
+
+/// The 12-byte JPEG 2000 signature box: `size=12`, `type='jP  '`, and the
+/// fixed payload `<CR><LF><0x87><LF>`.
+const SIGNATURE: &[u8] = b"\0\0\0\x0cjP  \x0d\x0a\x87\x0a";
+
+/// UUID of the box carrying Exif data in a JP2 file, i.e. "JpgTiffExif->JP2".
+const EXIF_UUID: &[u8] = b"JpgTiffExif->JP2";
+
+/// Refer to: [JPEG 2000](https://www.loc.gov/preservation/digital/formats/fdd/fdd000143.shtml)
+pub(crate) fn check_jp2(input: &[u8]) -> crate::Result<()> {
+    let _ = complete::tag(SIGNATURE)(input)?;
+    Ok(())
+}
+
+/// Extract Exif TIFF data from the bytes of a JPEG 2000 file.
+///
+/// JP2 boxes share the same length/type layout as ISOBMFF boxes, the Exif
+/// data is stored in a top level `uuid` box with the [`EXIF_UUID`].
+#[tracing::instrument(skip_all)]
+pub(crate) fn extract_exif_data(input: &[u8]) -> IResult<&[u8], Option<&[u8]>> {
+    let (remain, _) = complete::tag(SIGNATURE)(input)?;
+    let (remain, bbox) = travel_while(remain, |b| {
+        tracing::debug!(box_type = b.box_type(), "Got");
+        !(b.box_type() == "uuid" && b.body_data().starts_with(EXIF_UUID))
+    })?;
+
+    let data = bbox.map(|b| {
+        let data = &b.body_data()[EXIF_UUID.len()..]; // Safe-slice
+
+        // Some writers keep the "Exif\0\0" header of the JPEG APP1 segment
+        if check_exif_header(data).unwrap_or(false) {
+            &data[6..] // Safe-slice
+        } else {
+            data
+        }
+    });
+    Ok((remain, data))
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use crate::{
+        exif::extract_exif_with_mime,
+        file::MimeImage,
+        testkit::{make_box, read_sample},
+    };
+
+    use super::*;
+
+    /// Builds a minimal JP2 file embedding the Exif data of "exif.jpg".
+    pub(crate) fn build_jp2(exif_header: bool) -> (Vec<u8>, Vec<u8>) {
+        let buf = read_sample("exif.jpg").unwrap();
+        let (exif, _) = extract_exif_with_mime(MimeImage::Jpeg, &buf, None).unwrap();
+        let exif = exif.unwrap().to_vec();
+
+        let mut jp2 = SIGNATURE.to_vec();
+        let mut push_box = |box_type: &[u8], body: &[u8]| jp2.extend(make_box(box_type, body));
+        push_box(b"ftyp", b"jp2 \0\0\0\0jp2 ");
+        push_box(b"jp2h", &[0; 22]);
+        push_box(b"uuid", b"0123456789abcdef");
+
+        let mut body = EXIF_UUID.to_vec();
+        if exif_header {
+            body.extend_from_slice(b"Exif\0\0");
+        }
+        body.extend_from_slice(&exif);
+        push_box(b"uuid", &body);
+        push_box(b"jp2c", &[0xff, 0x4f, 0xff, 0x51]);
+
+        (jp2, exif)
+    }
+
+    #[test]
+    fn jp2_exif() {
+        let _ = tracing_subscriber::fmt().with_test_writer().try_init();
+
+        for exif_header in [false, true] {
+            let (jp2, exif) = build_jp2(exif_header);
+            check_jp2(&jp2).unwrap();
+            let (_, data) = extract_exif_data(&jp2).unwrap();
+            assert_eq!(data, Some(&exif[..]));
+        }
+
+        check_jp2(&read_sample("exif.jpg").unwrap()).unwrap_err();
+    }
+
+    #[test]
+    fn jp2_without_exif() {
+        let _ = tracing_subscriber::fmt().with_test_writer().try_init();
+
+        let mut jp2 = SIGNATURE.to_vec();
+        jp2.extend_from_slice(b"\0\0\0\x14ftypjp2 \0\0\0\0jp2 ");
+        let (_, data) = extract_exif_data(&jp2).unwrap();
+        assert_eq!(data, None);
+
+        // Incomplete boxes should ask for more data
+        jp2.extend_from_slice(b"\0\0\0\x20uuid");
+        extract_exif_data(&jp2).unwrap_err();
+    }
+}
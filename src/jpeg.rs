@@ -1,9 +1,17 @@
 use crate::{ExifIter, MediaParser, MediaSource};
-use std::io::{Read, Seek};
+use std::{
+    cmp::min,
+    io::{Read, Seek},
+    ops::Range,
+};
 
-use nom::{bytes::streaming, combinator::fail, number, sequence::tuple, IResult};
+use nom::{
+    bytes::streaming, combinator::fail, number, number::Endianness, sequence::tuple, IResult,
+};
 
-use crate::exif::{check_exif_header, Exif};
+use crate::exif::{check_exif_header, is_tiff_header, Exif};
+use crate::iptc::{find_iptc_resource, PHOTOSHOP_IDENT};
+use crate::slice::SubsliceRange;
 
 /// *Deprecated*: Please use [`MediaParser`] + [`MediaSource`] instead.
 ///
@@ -62,6 +70,214 @@ pub(crate) fn extract_exif_data(input: &[u8]) -> IResult<&[u8], Option<&[u8]>> {
     Ok((remain, data))
 }
 
+/// Identifier at the start of an `APP1` segment holding an XMP packet.
+const XMP_IDENT: &[u8] = b"http://ns.adobe.com/xap/1.0/\0";
+
+/// Extract the XMP packet from the bytes of a JPEG file, i.e. the payload of
+/// the first `APP1` segment which starts with the XMP namespace. Extended XMP
+/// segments are not merged.
+pub(crate) fn extract_xmp_data(input: &[u8]) -> IResult<&[u8], Option<&[u8]>> {
+    let (remain, segment) = travel_until(input, |s| {
+        (s.marker_code == MarkerCode::APP1.code() && s.payload.starts_with(XMP_IDENT))
+            // searching stop at SOS
+            || s.marker_code == MarkerCode::Sos.code()
+    })?;
+
+    if segment.marker_code != MarkerCode::Sos.code() {
+        Ok((remain, Some(&segment.payload[XMP_IDENT.len()..]))) // Safe-slice
+    } else {
+        Ok((remain, None))
+    }
+}
+
+/// Extract the raw IPTC-IIM data from the bytes of a JPEG file, i.e. the
+/// IPTC-NAA resource of the first Photoshop `APP13` segment containing one.
+pub(crate) fn extract_iptc_data(input: &[u8]) -> IResult<&[u8], Option<&[u8]>> {
+    fn iptc_resource<'a>(s: &Segment<'a>) -> Option<&'a [u8]> {
+        s.payload
+            .strip_prefix(PHOTOSHOP_IDENT)
+            .and_then(find_iptc_resource)
+    }
+
+    let (remain, segment) = travel_until(input, |s| {
+        (s.marker_code == MarkerCode::APP13.code() && iptc_resource(s).is_some())
+            // searching stop at SOS
+            || s.marker_code == MarkerCode::Sos.code()
+    })?;
+
+    if segment.marker_code != MarkerCode::Sos.code() {
+        Ok((remain, iptc_resource(&segment)))
+    } else {
+        Ok((remain, None))
+    }
+}
+
+/// Identifier at the start of an `APP2` segment holding an ICC profile
+/// chunk.
+const ICC_IDENT: &[u8] = b"ICC_PROFILE\0";
+
+/// Extract the ICC profile from the bytes of a JPEG file, which may be split
+/// into several `APP2` segments. Each chunk is preceded by its 1-based
+/// sequence number and the total number of chunks.
+///
+/// The chunks are concatenated in the order of their sequence numbers. An
+/// error is returned if the chunks are inconsistent, e.g. a chunk is missing
+/// or duplicated, rather than returning a corrupted profile.
+pub(crate) fn extract_icc_profile(input: &[u8]) -> IResult<&[u8], Result<Option<Vec<u8>>, String>> {
+    let mut chunks = Vec::new();
+    let (remain, _) = travel_until(input, |s| {
+        if s.marker_code == MarkerCode::APP2.code() {
+            if let Some([seq, count, data @ ..]) = s.payload.strip_prefix(ICC_IDENT) {
+                chunks.push((*seq, *count, data));
+            }
+        }
+        // searching stop at SOS
+        s.marker_code == MarkerCode::Sos.code()
+    })?;
+    Ok((remain, merge_icc_chunks(chunks)))
+}
+
+fn merge_icc_chunks(mut chunks: Vec<(u8, u8, &[u8])>) -> Result<Option<Vec<u8>>, String> {
+    let Some(&(_, count, _)) = chunks.first() else {
+        return Ok(None);
+    };
+    if chunks.iter().any(|x| x.1 != count) {
+        return Err("inconsistent ICC profile chunk counts".into());
+    }
+
+    chunks.sort_by_key(|x| x.0);
+    let seqs = chunks.iter().map(|x| x.0);
+    if chunks.len() != count as usize || !seqs.eq(1..=count) {
+        return Err(format!(
+            "ICC profile chunks are missing or duplicated, expect {count} chunks"
+        ));
+    }
+    Ok(Some(
+        chunks.into_iter().flat_map(|x| x.2).copied().collect(),
+    ))
+}
+
+/// Max number of bytes searched by [`search_exif_data`].
+const EXIF_SEARCH_LIMIT: usize = 64 * 1024;
+
+/// Number of bytes which should be loaded before calling
+/// [`search_exif_data`], i.e. the search window plus a max size APP1 segment.
+pub(crate) const EXIF_RECOVERY_SIZE: usize = EXIF_SEARCH_LIMIT + 0xFFFF;
+
+/// Linearly searches the "Exif\0\0" signature followed by a valid TIFF
+/// header in the first bytes of `input`, for recovering Exif data from JPEG
+/// files with damaged segment markers. Returns the range of the TIFF data.
+pub(crate) fn search_exif_data(input: &[u8]) -> Option<Range<usize>> {
+    let window = &input[..min(input.len(), EXIF_SEARCH_LIMIT)]; // Safe-slice
+    let pos = window
+        .windows(14)
+        .position(|x| x.starts_with(b"Exif\0\0") && is_tiff_header(&x[6..]))?;
+
+    let start = pos + 6;
+    // The payload of an APP1 segment is at most 0xFFFF - 2 bytes, including
+    // the "Exif\0\0" ident
+    let end = min(input.len(), start + 0xFFFF - 8);
+    Some(start..end)
+}
+
+/// Parses the MP Index IFD in the APP2 "MPF" segment of a JPEG file
+/// (Multi-Picture Format, used by dual-camera phones, 3D cameras etc.), and
+/// returns the ranges of the images it lists, the first one being the primary
+/// image.
+///
+/// `input` should contain the whole JPEG file, images which are out of the
+/// bounds of `input` are skipped. An empty `Vec` is returned if there is no
+/// MPF segment, or it's invalid.
+///
+/// ```rust
+/// use nom_exif::*;
+///
+/// let buf = std::fs::read("./testdata/exif.jpg").unwrap();
+/// assert!(mpf_images(&buf).is_empty());
+/// ```
+pub fn mpf_images(input: &[u8]) -> Vec<Range<usize>> {
+    let Ok((_, segment)) = travel_until(input, |s| {
+        (s.marker_code == MarkerCode::APP2.code() && s.payload.starts_with(MPF_IDENT))
+            || s.marker_code == MarkerCode::Sos.code()
+    }) else {
+        return Vec::new();
+    };
+    if segment.marker_code == MarkerCode::Sos.code() {
+        return Vec::new();
+    }
+
+    let tiff = &segment.payload[MPF_IDENT.len()..]; // Safe-slice
+    let Some(tiff_start) = input.subslice_in_range(tiff).map(|x| x.start) else {
+        return Vec::new();
+    };
+    let Some(entries) = parse_mp_entries(tiff) else {
+        tracing::warn!("invalid MP Index IFD");
+        return Vec::new();
+    };
+
+    entries
+        .into_iter()
+        .filter_map(|(offset, size)| {
+            // The offset of the primary image is 0, other offsets are
+            // relative to the MP header, i.e. the TIFF header in the segment.
+            let start = if offset == 0 {
+                0
+            } else {
+                tiff_start.checked_add(offset as usize)?
+            };
+            let end = start.checked_add(size as usize)?;
+            (end <= input.len()).then_some(start..end)
+        })
+        .collect()
+}
+
+const MPF_IDENT: &[u8] = b"MPF\0";
+
+/// MP Entry tag of the MP Index IFD.
+const MP_ENTRY_TAG: u16 = 0xB002;
+const MP_ENTRY_SIZE: usize = 16;
+
+/// Returns `(data offset, image size)` of the MP entries in `tiff`.
+fn parse_mp_entries(tiff: &[u8]) -> Option<Vec<(u32, u32)>> {
+    let endian = match tiff.get(..4)? {
+        b"II*\0" => Endianness::Little,
+        b"MM\0*" => Endianness::Big,
+        _ => return None,
+    };
+    let u16_at = |pos| read_u16(tiff, pos, endian);
+    let u32_at = |pos| read_u32(tiff, pos, endian);
+
+    let ifd = u32_at(4)? as usize;
+    let entry_num = u16_at(ifd)? as usize;
+    let entry = (0..entry_num)
+        .map(|i| ifd + 2 + i * 12)
+        .find(|pos| u16_at(*pos) == Some(MP_ENTRY_TAG))?;
+
+    // The format is UNDEFINED, so count is the size in bytes
+    let size = u32_at(entry + 4)? as usize;
+    let offset = u32_at(entry + 8)? as usize;
+    let data = tiff.get(offset..offset.checked_add(size)?)?;
+    let entries = data
+        .chunks_exact(MP_ENTRY_SIZE)
+        .map(|x| {
+            // attributes, image size, data offset, then 2 dependent image
+            // entry numbers
+            Some((read_u32(x, 8, endian)?, read_u32(x, 4, endian)?))
+        })
+        .collect::<Option<Vec<_>>>()?;
+    Some(entries)
+}
+
+fn read_u16(data: &[u8], pos: usize, endian: Endianness) -> Option<u16> {
+    let (_, v) = number::complete::u16::<_, nom::error::Error<_>>(endian)(data.get(pos..)?).ok()?;
+    Some(v)
+}
+
+fn read_u32(data: &[u8], pos: usize, endian: Endianness) -> Option<u32> {
+    let (_, v) = number::complete::u32::<_, nom::error::Error<_>>(endian)(data.get(pos..)?).ok()?;
+    Some(v)
+}
+
 struct Segment<'a> {
     marker_code: u8,
     payload: &'a [u8],
@@ -272,6 +488,12 @@ enum MarkerCode {
     // APP1 marker
     APP1 = 0xE1,
 
+    // APP2 marker
+    APP2 = 0xE2,
+
+    // APP13 marker
+    APP13 = 0xED,
+
     // Start of Scan
     Sos = 0xDA,
 
@@ -392,6 +614,106 @@ mod tests {
         );
     }
 
+    #[test]
+    fn search_exif() {
+        let _ = tracing_subscriber::fmt().with_test_writer().try_init();
+
+        let buf = read_sample("exif.jpg").unwrap();
+        let (_, data) = extract_exif_data(&buf).unwrap();
+        let range = search_exif_data(&buf).unwrap();
+        assert_eq!(range.start, 12);
+        assert!(buf[range].starts_with(data.unwrap()));
+
+        // "Exif\0\0" not followed by a TIFF header
+        let mut buf = b"Exif\0\0Exif\0\0".to_vec();
+        buf.extend_from_slice(b"II*\0\x08\0\0\0");
+        assert_eq!(search_exif_data(&buf), Some(12..20));
+        assert_eq!(search_exif_data(&buf[..18]), None);
+
+        let buf = read_sample("no-exif.jpg").unwrap();
+        assert_eq!(search_exif_data(&buf), None);
+    }
+
+    #[test_case(&[(1, 1, b"abc")], Some(b"abc"))]
+    #[test_case(&[(2, 3, b"cd"), (1, 3, b"ab"), (3, 3, b"e")], Some(b"abcde"))]
+    #[test_case(&[(1, 2, b"ab")], None; "missing")]
+    #[test_case(&[(1, 2, b"ab"), (1, 2, b"ab")], None; "duplicated")]
+    #[test_case(&[(1, 2, b"ab"), (2, 3, b"cd")], None; "inconsistent count")]
+    #[test_case(&[(0, 1, b"ab")], None; "invalid sequence number")]
+    fn merge_icc(chunks: &[(u8, u8, &[u8])], expect: Option<&[u8]>) {
+        let res = merge_icc_chunks(chunks.to_vec());
+        match expect {
+            Some(expect) => assert_eq!(res.unwrap().unwrap(), expect),
+            None => {
+                res.unwrap_err();
+            }
+        }
+        assert_eq!(merge_icc_chunks(Vec::new()), Ok(None));
+    }
+
+    /// Makes a JPEG file with an MPF segment, followed by a secondary image.
+    fn make_mpo(big_endian: bool) -> Vec<u8> {
+        let u32b = |x: u32| {
+            if big_endian {
+                x.to_be_bytes()
+            } else {
+                x.to_le_bytes()
+            }
+        };
+
+        // MP Index IFD: MPFVersion, NumberOfImages, MPEntry, the MP entries
+        // follow the IFD at offset 50
+        let endian = if big_endian {
+            Endianness::Big
+        } else {
+            Endianness::Little
+        };
+        let mut tiff = TiffBuilder::new(endian);
+        tiff.undefined(0, 0xB000, b"0100")
+            .long(0, 0xB001, &[2])
+            .entry(0, 0xB002, 7, 32, TiffValue::Field(50));
+        let tiff = tiff.build();
+        assert_eq!(tiff.len(), 50);
+
+        let secondary = b"\xff\xd8\xff\xda\0\x02\xff\xd9";
+        let mut buf = b"\xff\xd8\xff\xe2".to_vec();
+        let app2_size = 2 + MPF_IDENT.len() + tiff.len() + 2 * MP_ENTRY_SIZE;
+        buf.extend_from_slice(&(app2_size as u16).to_be_bytes());
+        buf.extend_from_slice(MPF_IDENT);
+        let tiff_start = buf.len();
+        buf.extend_from_slice(&tiff);
+        let primary_size = app2_size as u32 + 4 + 6;
+        for (size, offset) in [
+            (primary_size, 0),
+            (secondary.len() as u32, primary_size - tiff_start as u32),
+        ] {
+            buf.extend_from_slice(&u32b(0));
+            buf.extend_from_slice(&u32b(size));
+            buf.extend_from_slice(&u32b(offset));
+            buf.extend_from_slice(&[0; 4]);
+        }
+        buf.extend_from_slice(b"\xff\xda\0\x02\xff\xd9");
+        assert_eq!(buf.len(), primary_size as usize);
+        buf.extend_from_slice(secondary);
+        buf
+    }
+
+    #[test_case(false)]
+    #[test_case(true)]
+    fn mpf(big_endian: bool) {
+        let _ = tracing_subscriber::fmt().with_test_writer().try_init();
+
+        let buf = make_mpo(big_endian);
+        let images = mpf_images(&buf);
+        assert_eq!(images, [0..98, 98..106]);
+        assert!(buf[images[1].clone()].starts_with(&[0xff, 0xd8]));
+
+        // The secondary image is truncated
+        assert_eq!(mpf_images(&buf[..100]), vec![0..98]);
+        assert!(mpf_images(&buf[..60]).is_empty());
+        assert!(mpf_images(&read_sample("no-exif.jpg").unwrap()).is_empty());
+    }
+
     #[allow(deprecated)]
     #[test]
     fn broken_jpg() {
@@ -7,7 +7,14 @@
 //!   - *.heic, *.heif, etc.
 //!   - *.jpg, *.jpeg
 //!   - *.tiff, *.tif
+//!   - *.nef (Nikon RAW)
+//!   - *.arw (Sony RAW)
 //!   - *.RAF (Fujifilm RAW)
+//!   - *.jp2, *.jpf (JPEG 2000)
+//!   - *.png
+//!   - *.webp
+//!   - *.cr3
+//!   - *.avif
 //! - Video/Audio
 //!   - ISO base media file format (ISOBMFF): *.mp4, *.mov, *.3gp, etc.
 //!   - Matroska based file format: *.webm, *.mkv, *.mka, etc.
@@ -140,6 +147,9 @@
 //!     // `MediaSource` can also be created from a `TcpStream`:
 //!     // let ms = MediaSource::tcp_stream(stream)?;
 //!
+//!     // From in-memory data, which is always seekable:
+//!     // let ms = MediaSource::cursor(bytes)?;
+//!
 //!     // Or from any `Read + Seek`:
 //!     // let ms = MediaSource::seekable(stream)?;
 //!     
@@ -169,8 +179,9 @@
 //!
 //! ## GPS Info
 //!
-//! `ExifIter` provides a convenience method for parsing gps information. (`Exif` &
-//! `TrackInfo` also provide a `get_gps_info` method).
+//! `ExifIter` provides a convenience method for parsing gps information. (`Exif`
+//! provides an `Exif::gps` method, and `TrackInfo` provides a `get_gps_info`
+//! method).
 //!     
 //! ```rust
 //! use nom_exif::*;
@@ -211,6 +222,8 @@
 //! ImageWidth                      => 720
 //! ImageHeight                     => 1280
 //! GpsIso6709                      => +27.1281+100.2508+000.000/
+//! Timescale                       => 600
+//! PreferredRate                   => 1
 //! ```
 //!
 //! ### Json Dump
@@ -226,7 +239,9 @@
 //!   "GpsIso6709": "+27.1281+100.2508+000.000/",
 //!   "CreateDate": "2024-02-02T08:09:57+00:00",
 //!   "Model": "iPhone X",
-//!   "DurationMs": "500"
+//!   "DurationMs": "500",
+//!   "Timescale": "600",
+//!   "PreferredRate": "1"
 //! }
 //! ```
 //!
@@ -248,6 +263,8 @@
 //! ImageWidth                      => 1920
 //! ImageHeight                     => 1440
 //! GpsIso6709                      => +22.5797+113.9380+028.396/
+//! Timescale                       => 600
+//! PreferredRate                   => 1
 //!
 //! File: "testdata/compatible-brands-fail.heic"
 //! ------------------------------------------------
@@ -304,15 +321,26 @@
 //! ...
 //! ```
 
-pub use parser::{MediaParser, MediaSource};
-pub use video::{TrackInfo, TrackInfoTag};
+pub use explain::ParseExplanation;
+pub use file::MediaType;
+pub use iptc::Iptc;
+pub use parser::{MediaMetadata, MediaParser, MediaSource};
+pub use parser_pool::{MediaParserPool, PooledMediaParser};
+pub use video::{DateSource, SingleTrack, TrackInfo, TrackInfoTag};
 
 #[cfg(feature = "async")]
 pub use parser_async::{AsyncMediaParser, AsyncMediaSource};
 
-pub use exif::{Exif, ExifIter, ExifTag, GPSInfo, LatLng, ParsedExifEntry};
+pub use exif::{
+    is_exif_header, is_tiff_header, AppleMakerNote, CameraSettings, CanonMakerNote,
+    DuplicateStrategy, Exif, ExifIter, ExifTag, FileSource, GPSInfo, IfdKind, LatLng,
+    LensResolverFn, MakerNoteEntries, ParsedExifEntry, SceneType, SensingMethod, TagDiff,
+};
 pub use values::{EntryValue, IRational, URational};
 
+#[cfg(feature = "json_dump")]
+pub use exif::ExifSnapshot;
+
 #[allow(deprecated)]
 pub use exif::parse_exif;
 #[cfg(feature = "async")]
@@ -321,6 +349,7 @@ pub use exif::parse_exif_async;
 
 #[allow(deprecated)]
 pub use heif::parse_heif_exif;
+pub use jpeg::mpf_images;
 #[allow(deprecated)]
 pub use jpeg::parse_jpeg_exif;
 
@@ -336,24 +365,31 @@ pub use mov::{parse_metadata, parse_mov_metadata};
 
 mod bbox;
 mod buffer;
+mod cr3;
 mod ebml;
 mod error;
 mod exif;
+mod explain;
 mod file;
 mod heif;
+mod iptc;
+mod jp2;
 mod jpeg;
 mod loader;
 mod mov;
 mod parser;
 #[cfg(feature = "async")]
 mod parser_async;
+mod parser_pool;
 mod partial_vec;
+mod png;
 mod raf;
 mod skip;
 mod slice;
 mod utils;
 mod values;
 mod video;
+mod webp;
 
 #[cfg(test)]
 mod testkit;
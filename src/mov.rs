@@ -5,18 +5,24 @@ use std::{
 };
 
 use chrono::DateTime;
-use nom::{bytes::streaming, IResult};
+use nom::{
+    bytes::streaming,
+    number::complete::{be_u16, be_u32},
+    sequence::tuple,
+    IResult,
+};
 
 #[allow(deprecated)]
 use crate::{
     bbox::{
-        find_box, parse_video_tkhd_in_moov, travel_header, IlstBox, KeysBox, MvhdBox, ParseBox,
+        find_box, find_video_track, parse_video_tkhd_in_moov, travel_header, travel_while,
+        BoxHolder, IlstBox, KeysBox, MvhdBox, ParseBox, TkhdBox,
     },
     error::ParsingError,
     loader::{BufLoader, Load},
     partial_vec::PartialVec,
     skip::Seekable,
-    video::TrackInfoTag,
+    video::{DateSource, SingleTrack, TrackInfo, TrackInfoTag},
     EntryValue, FileFormat,
 };
 
@@ -88,6 +94,9 @@ pub fn parse_metadata<R: Read + Seek>(reader: R) -> crate::Result<Vec<(String, E
 
     let map: BTreeMap<TrackInfoTag, EntryValue> = map_qt_tag_to_video_tag(entries.clone());
     let mut extras = parse_mvhd_tkhd(&moov_body);
+    // Not supported by this deprecated API
+    extras.remove(&TrackInfoTag::Timescale);
+    extras.remove(&TrackInfoTag::PreferredRate);
 
     const CREATIONDATE_KEY: &str = "com.apple.quicktime.creationdate";
     if map.contains_key(&TrackInfoTag::CreateDate) {
@@ -128,9 +137,7 @@ pub fn parse_metadata<R: Read + Seek>(reader: R) -> crate::Result<Vec<(String, E
 }
 
 #[tracing::instrument(skip_all)]
-pub(crate) fn parse_qt(
-    moov_body: &[u8],
-) -> Result<BTreeMap<TrackInfoTag, EntryValue>, ParsingError> {
+pub(crate) fn parse_qt(moov_body: &[u8]) -> Result<TrackInfo, ParsingError> {
     let (_, entries) = match parse_moov_body(moov_body) {
         Ok((remain, Some(entries))) => (remain, entries),
         Ok((remain, None)) => (remain, Vec::new()),
@@ -144,15 +151,26 @@ pub(crate) fn parse_qt(
     if entries.contains_key(&TrackInfoTag::CreateDate) {
         entries.remove(&TrackInfoTag::CreateDate);
     }
+    let mvhd_date = extras.contains_key(&TrackInfoTag::CreateDate);
     entries.extend(extras);
+    entries.extend(parse_video_sample_entry(moov_body));
+    if let Some(n) = parse_frame_count(moov_body) {
+        entries.insert(TrackInfoTag::FrameCount, n.into());
+    }
 
-    Ok(entries)
+    for (k, v) in map_qt_tag_to_video_tag(parse_udta_tags(moov_body)) {
+        entries.entry(k).or_insert(v);
+    }
+    // After merging `udta`, which may hold the only date (`©day`)
+    let date_source = create_date_source(&entries, mvhd_date);
+
+    let mut info: TrackInfo = entries.into();
+    info.create_date_source = date_source;
+    Ok(info)
 }
 
 #[tracing::instrument(skip_all)]
-pub(crate) fn parse_mp4(
-    moov_body: &[u8],
-) -> Result<BTreeMap<TrackInfoTag, EntryValue>, ParsingError> {
+pub(crate) fn parse_mp4(moov_body: &[u8]) -> Result<TrackInfo, ParsingError> {
     let (_, entries) = match parse_moov_body(moov_body) {
         Ok((remain, Some(entries))) => (remain, entries),
         Ok((remain, None)) => (remain, Vec::new()),
@@ -163,7 +181,18 @@ pub(crate) fn parse_mp4(
 
     let mut entries: BTreeMap<TrackInfoTag, EntryValue> = map_qt_tag_to_video_tag(entries);
     let extras = parse_mvhd_tkhd(moov_body);
+    let mvhd_date = extras.contains_key(&TrackInfoTag::CreateDate);
     entries.extend(extras);
+    entries.extend(parse_video_sample_entry(moov_body));
+    if let Some(n) = parse_frame_count(moov_body) {
+        entries.insert(TrackInfoTag::FrameCount, n.into());
+    }
+
+    for (k, v) in map_qt_tag_to_video_tag(parse_udta_tags(moov_body)) {
+        entries.entry(k).or_insert(v);
+    }
+    // After merging `udta`, which may hold the only date (`©day`)
+    let date_source = create_date_source(&entries, mvhd_date);
 
     // If the GPSInfo doesn't exist, then try to find GPS info from box
     // `moov/udta/©xyz`. For mp4 files, Android phones store GPS info in that
@@ -174,14 +203,38 @@ pub(crate) fn parse_mp4(
         }
     }
 
-    Ok(entries)
+    let mut info: TrackInfo = entries.into();
+    info.create_date_source = date_source;
+    Ok(info)
+}
+
+/// The date of `mvhd` takes precedence over the ones of QuickTime keys and
+/// `udta` items, which are merged into `entries`.
+fn create_date_source(
+    entries: &BTreeMap<TrackInfoTag, EntryValue>,
+    mvhd_date: bool,
+) -> Option<DateSource> {
+    if mvhd_date {
+        Some(DateSource::Mvhd)
+    } else if entries.contains_key(&TrackInfoTag::CreateDate) {
+        Some(DateSource::QuickTimeKey)
+    } else {
+        None
+    }
 }
 
 fn parse_mvhd_tkhd(moov_body: &[u8]) -> BTreeMap<TrackInfoTag, EntryValue> {
     let mut entries = BTreeMap::new();
     if let Ok((_, Some(bbox))) = find_box(moov_body, "mvhd") {
         if let Ok((_, mvhd)) = MvhdBox::parse_box(bbox.data) {
-            entries.insert(TrackInfoTag::DurationMs, mvhd.duration_ms().into());
+            if let Some(duration) = mvhd.duration_ms() {
+                entries.insert(TrackInfoTag::DurationMs, duration.into());
+            }
+            entries.insert(TrackInfoTag::Timescale, mvhd.time_scale().into());
+            entries.insert(
+                TrackInfoTag::PreferredRate,
+                EntryValue::F64(mvhd.preferred_rate()),
+            );
 
             entries.insert(
                 TrackInfoTag::CreateDate,
@@ -216,6 +269,12 @@ fn map_qt_tag_to_video_tag(
                 Some((TrackInfoTag::Software, v))
             } else if k == "com.apple.quicktime.location.ISO6709" {
                 Some((TrackInfoTag::GpsIso6709, v))
+            } else if k == "com.apple.quicktime.title" {
+                Some((TrackInfoTag::Title, v))
+            } else if k == "com.apple.quicktime.artist" {
+                Some((TrackInfoTag::Artist, v))
+            } else if k == "com.apple.quicktime.album" {
+                Some((TrackInfoTag::Album, v))
             } else {
                 None
             }
@@ -244,6 +303,305 @@ fn parse_mp4_gps(moov_body: &[u8]) -> Option<String> {
     None
 }
 
+/// Computes the frame count of the video track from its sample table, i.e.
+/// the sum of the sample counts in `stts`, or the sample count in `stsz`.
+fn parse_frame_count(moov_body: &[u8]) -> Option<u64> {
+    let trak = find_video_track(moov_body).ok()??;
+    let (_, stbl) = find_box(trak.body_data(), "mdia/minf/stbl").ok()?;
+    let stbl = stbl?;
+
+    if let Ok((_, Some(stts))) = find_box(stbl.body_data(), "stts") {
+        if let Some(n) = stts_sample_count(stts.body_data()) {
+            return Some(n);
+        }
+    }
+
+    // version & flags, sample_size, then sample_count
+    let (_, stsz) = find_box(stbl.body_data(), "stsz").ok()?;
+    let count = stsz?.body_data().get(8..12)?;
+    Some(u32::from_be_bytes(count.try_into().ok()?) as u64)
+}
+
+fn stts_sample_count(stts_body: &[u8]) -> Option<u64> {
+    // version & flags, entry_count, then (sample_count, sample_delta) pairs
+    let count = stts_body.get(4..8)?;
+    let entry_count = u32::from_be_bytes(count.try_into().ok()?) as usize;
+    let table = stts_body.get(8..8 + entry_count.checked_mul(8)?)?;
+    Some(
+        table
+            .chunks_exact(8)
+            .map(|x| u32::from_be_bytes([x[0], x[1], x[2], x[3]]) as u64)
+            .sum(),
+    )
+}
+
+/// Size of the fields of a visual sample entry, before its child boxes.
+const VISUAL_SAMPLE_ENTRY_SIZE: usize = 78;
+
+/// Parses the metadata in the video sample entry of the video track, i.e.
+/// the spherical video boxes (`st3d` and `sv3d`) defined by the Google Spatial
+/// Media spec, and the `colr` box.
+fn parse_video_sample_entry(moov_body: &[u8]) -> BTreeMap<TrackInfoTag, EntryValue> {
+    let mut entries = BTreeMap::new();
+    let Some(entry) = find_video_track(moov_body)
+        .ok()
+        .flatten()
+        .and_then(|trak| find_box(trak.body_data(), "mdia/minf/stbl/stsd").ok())
+        .and_then(|(_, stsd)| stsd)
+        // version & flags, entry count, then the first entry
+        .and_then(|stsd| stsd.body_data().get(8..))
+        .and_then(|data| BoxHolder::parse(data).ok())
+        .map(|(_, entry)| entry)
+    else {
+        return entries;
+    };
+    let Some(children) = entry.body_data().get(VISUAL_SAMPLE_ENTRY_SIZE..) else {
+        return entries;
+    };
+
+    // Some writers append a 4-byte terminator after the child boxes, so the
+    // parsing error is ignored here.
+    let _ = travel_while(children, |b| {
+        match b.box_type() {
+            // version & flags, then stereo_mode
+            "st3d" => {
+                if let Some(mode) = b.body_data().get(4).and_then(|x| st3d_stereo_mode(*x)) {
+                    entries.insert(TrackInfoTag::StereoMode, mode.into());
+                }
+            }
+            "sv3d" => {
+                if let Some(projection) = parse_sv3d_projection(b.body_data()) {
+                    entries.insert(TrackInfoTag::Projection, projection.into());
+                }
+            }
+            "colr" => {
+                if let Some((primaries, transfer)) = parse_colr(b.body_data()) {
+                    entries.insert(TrackInfoTag::ColorPrimaries, primaries.into());
+                    entries.insert(TrackInfoTag::TransferCharacteristics, transfer.into());
+                }
+            }
+            _ => (),
+        }
+        true
+    });
+    entries
+}
+
+fn st3d_stereo_mode(mode: u8) -> Option<&'static str> {
+    let mode = match mode {
+        0 => "mono",
+        1 => "top-bottom",
+        2 => "left-right",
+        3 => "stereo-custom",
+        4 => "right-left",
+        _ => return None,
+    };
+    Some(mode)
+}
+
+/// Parses `(colour_primaries, transfer_characteristics)` from a `colr` box
+/// of type `nclx` (ISOBMFF) or `nclc` (QuickTime). Other types carry an ICC
+/// profile, `None` is returned for them.
+fn parse_colr(colr_body: &[u8]) -> Option<(u16, u16)> {
+    // colour_type, then primaries, transfer characteristics & matrix
+    // coefficients
+    let (colour_type, data) = colr_body.split_at_checked(4)?;
+    if colour_type != b"nclx" && colour_type != b"nclc" {
+        return None;
+    }
+    let primaries = u16::from_be_bytes(data.get(0..2)?.try_into().ok()?);
+    let transfer = u16::from_be_bytes(data.get(2..4)?.try_into().ok()?);
+    Some((primaries, transfer))
+}
+
+/// Finds the projection type in `sv3d/proj`, which is one of `equi`, `cbmp`
+/// and `mshp`.
+fn parse_sv3d_projection(sv3d_body: &[u8]) -> Option<&'static str> {
+    let (_, proj) = find_box(sv3d_body, "proj").ok()?;
+    let mut projection = None;
+    let _ = travel_while(proj?.body_data(), |b| {
+        projection = match b.box_type() {
+            "equi" => Some("equirectangular"),
+            "cbmp" => Some("cubemap"),
+            "mshp" => Some("mesh"),
+            _ => None,
+        };
+        projection.is_none()
+    });
+    projection
+}
+
+/// Lists all tracks in `moov_body`, in the order of their `trak` boxes.
+/// Tracks without a valid `tkhd` box are skipped.
+pub(crate) fn parse_tracks(moov_body: &[u8]) -> Vec<SingleTrack> {
+    let mut tracks = Vec::new();
+    let _ = travel_while(moov_body, |b| {
+        if b.box_type() == "trak" {
+            if let Some(track) = parse_single_track(b.body_data()) {
+                tracks.push(track);
+            }
+        }
+        true
+    });
+    tracks
+}
+
+fn parse_single_track(trak_body: &[u8]) -> Option<SingleTrack> {
+    let (_, tkhd) = find_box(trak_body, "tkhd").ok()?;
+    let (_, tkhd) = TkhdBox::parse_box(tkhd?.data).ok()?;
+    let handler_type = find_box(trak_body, "mdia/hdlr")
+        .ok()
+        .and_then(|(_, b)| b)
+        // version & flags, component type, then component subtype
+        .and_then(|b| b.body_data().get(8..12).map(|x| x.to_vec()))
+        .map(|x| x.iter().map(|b| *b as char).collect())
+        .unwrap_or_default();
+    Some(SingleTrack {
+        track_id: tkhd.track_id,
+        alternate_group: tkhd.alt_group,
+        handler_type,
+    })
+}
+
+/// Handler types of timed metadata tracks, e.g. `meta` is used by Apple
+/// (`mebx`) & GoPro (`gpmd`) metadata tracks.
+const TIMED_METADATA_HANDLERS: [&[u8]; 3] = [b"meta", b"tmcd", b"camm"];
+
+/// Lists the subtypes of timed metadata tracks in `moov_body`.
+///
+/// For tracks with a generic `meta` handler, the format of the first sample
+/// description (e.g. `mebx`, `gpmd`) is returned instead, since it's more
+/// specific.
+pub(crate) fn parse_timed_metadata_tracks(moov_body: &[u8]) -> Vec<String> {
+    let mut tracks = Vec::new();
+    let _ = travel_while(moov_body, |b| {
+        if b.box_type() == "trak" {
+            if let Some(subtype) = parse_timed_metadata_subtype(b.body_data()) {
+                tracks.push(subtype);
+            }
+        }
+        true
+    });
+    tracks
+}
+
+fn parse_timed_metadata_subtype(trak_body: &[u8]) -> Option<String> {
+    let (_, hdlr) = find_box(trak_body, "mdia/hdlr").ok()?;
+    // version & flags, component type, then component subtype
+    let handler = hdlr?.body_data().get(8..12)?;
+    if !TIMED_METADATA_HANDLERS.contains(&handler) {
+        return None;
+    }
+
+    let format = if handler == b"meta" {
+        find_box(trak_body, "mdia/minf/stbl/stsd")
+            .ok()
+            .and_then(|(_, b)| b)
+            // version & flags, entry count, then the first entry's size
+            .and_then(|b| b.body_data().get(12..16))
+    } else {
+        None
+    };
+
+    let subtype = format.unwrap_or(handler);
+    Some(subtype.iter().map(|b| *b as char).collect())
+}
+
+/// Try to find iTunes-style items (see [`ILST_KEYS`]) from box
+/// `moov/udta/meta/ilst`, which is where iTunes & ffmpeg put them, or from
+/// QuickTime user data text atoms `moov/udta/©nam`, etc. The items are keyed
+/// the same as the `mdta` form.
+fn parse_udta_tags(moov_body: &[u8]) -> Vec<(String, EntryValue)> {
+    let Ok((_, Some(udta))) = find_box(moov_body, "udta") else {
+        return Vec::new();
+    };
+
+    let mut entries = find_udta_ilst(udta.body_data())
+        .map(parse_mdir_ilst)
+        .unwrap_or_default();
+    for (box_type, key) in ILST_KEYS {
+        if entries.iter().any(|(k, _)| k == key) {
+            continue;
+        }
+        let text = find_box(udta.body_data(), box_type)
+            .ok()
+            .and_then(|x| x.1)
+            .and_then(|b| parse_udta_text(b.body_data()));
+        if let Some(text) = text {
+            entries.push((key.to_string(), text.into()));
+        }
+    }
+    entries
+}
+
+/// Returns the body data of box `udta/meta/ilst`.
+fn find_udta_ilst(udta_body: &[u8]) -> Option<&[u8]> {
+    let (_, meta) = find_box(udta_body, "meta").ok()?;
+    let body = meta_children(meta?.body_data())?;
+    let (_, ilst) = find_box(body, "ilst").ok()?;
+    ilst.map(|x| x.body_data())
+}
+
+/// Returns the child boxes of a `meta` box body.
+fn meta_children(meta_body: &[u8]) -> Option<&[u8]> {
+    // In MP4 files, `meta` is a full box, while in QuickTime files it may be
+    // a normal box, which starts with a `hdlr` box directly.
+    if meta_body.get(4..8) == Some(b"hdlr") {
+        Some(meta_body)
+    } else {
+        meta_body.get(4..)
+    }
+}
+
+/// iTunes-style items of a `mdir` handler `ilst`, which are keyed by fourcc
+/// directly, mapped to the keys of the `mdta` form.
+const ILST_KEYS: &[(&str, &str)] = &[
+    ("©nam", "com.apple.quicktime.title"),
+    ("©ART", "com.apple.quicktime.artist"),
+    ("©alb", "com.apple.quicktime.album"),
+    ("©day", "com.apple.quicktime.creationdate"),
+    ("©too", "com.apple.quicktime.software"),
+    ("©mak", "com.apple.quicktime.make"),
+    ("©mod", "com.apple.quicktime.model"),
+    ("©xyz", "com.apple.quicktime.location.ISO6709"),
+];
+
+/// Parses the body of a `mdir` handler `ilst`, whose items are keyed by
+/// fourcc rather than by the indexes of a `keys` box.
+fn parse_mdir_ilst(ilst_body: &[u8]) -> Vec<(String, EntryValue)> {
+    let mut entries = Vec::new();
+    let _ = travel_while(ilst_body, |b| {
+        let key = ILST_KEYS.iter().find(|(k, _)| *k == b.box_type());
+        if let Some(((_, key), text)) = key.zip(parse_udta_text(b.body_data())) {
+            entries.push((key.to_string(), text.into()));
+        }
+        true
+    });
+    entries
+}
+
+/// Parse text from the body of an iTunes-style metadata item, or a QuickTime
+/// user data text atom.
+fn parse_udta_text(body: &[u8]) -> Option<String> {
+    if body.get(4..8) == Some(b"data") {
+        // iTunes-style: a `data` box with type indicator & locale
+        let (_, data) = find_box(body, "data").ok()?;
+        let data = data?.body_data();
+        let (value, (type_code, _)) =
+            tuple((be_u32::<_, nom::error::Error<_>>, be_u32))(data).ok()?;
+        // UTF-8 text
+        if type_code & 0xFF_FFFF != 1 {
+            return None;
+        }
+        String::from_utf8(value.to_vec()).ok()
+    } else {
+        // QuickTime user data text: text size (u16), language code (u16), text
+        let (remain, (size, _)) = tuple((be_u16::<_, nom::error::Error<_>>, be_u16))(body).ok()?;
+        let text = remain.get(..size as usize)?;
+        String::from_utf8(text.to_vec()).ok()
+    }
+}
+
 /// *Deprecated*: Please use [`crate::MediaParser`] instead.
 ///
 /// Analyze the byte stream in the `reader` as a MOV file, attempting to extract
@@ -351,12 +709,21 @@ fn parse_moov_body(input: &[u8]) -> EntriesResult {
     let (remain, Some(meta)) = find_box(input, "meta")? else {
         return Ok((input, None));
     };
+    let Some(meta_body) = meta_children(meta.body_data()) else {
+        return Ok((remain, None));
+    };
 
-    let (_, Some(keys)) = find_box(meta.body_data(), "keys")? else {
+    let (_, Some(ilst)) = find_box(meta_body, "ilst")? else {
         return Ok((remain, None));
     };
 
-    let (_, Some(ilst)) = find_box(meta.body_data(), "ilst")? else {
+    // iTunes-style metadata, there is no `keys` box
+    let (_, hdlr) = find_box(meta_body, "hdlr")?;
+    if hdlr.and_then(|x| x.body_data().get(8..12)) == Some(b"mdir") {
+        return Ok((remain, Some(parse_mdir_ilst(ilst.body_data()))));
+    }
+
+    let (_, Some(keys)) = find_box(meta_body, "keys")? else {
         return Ok((remain, None));
     };
 
@@ -501,6 +868,247 @@ mod tests {
         );
     }
 
+    #[test_case("meta.mov", &["mebx", "mebx"])]
+    #[test_case("embedded-in-heic.mov", &["mebx", "mebx", "mebx"])]
+    #[test_case("meta.mp4", &[])]
+    fn timed_metadata_tracks(path: &str, expect: &[&str]) {
+        let _ = tracing_subscriber::fmt().with_test_writer().try_init();
+
+        let buf = read_sample(path).unwrap();
+        let range = extract_moov_body_from_buf(&buf).unwrap();
+        assert_eq!(parse_timed_metadata_tracks(&buf[range]), expect);
+    }
+
+    fn itunes_item(box_type: &[u8], text: &str) -> Vec<u8> {
+        let mut data = vec![0, 0, 0, 1, 0, 0, 0, 0];
+        data.extend_from_slice(text.as_bytes());
+        make_box(box_type, &make_box(b"data", &data))
+    }
+
+    fn qt_text(box_type: &[u8], text: &str) -> Vec<u8> {
+        let mut data = (text.len() as u16).to_be_bytes().to_vec();
+        data.extend_from_slice(&[0x15, 0xc7]);
+        data.extend_from_slice(text.as_bytes());
+        make_box(box_type, &data)
+    }
+
+    #[test]
+    fn udta_tags() {
+        let _ = tracing_subscriber::fmt().with_test_writer().try_init();
+
+        // iTunes-style tags in `udta/meta/ilst`
+        let mut ilst = itunes_item(b"\xa9nam", "Title");
+        ilst.extend(itunes_item(b"\xa9ART", "Artist"));
+        let mut meta = vec![0, 0, 0, 0];
+        meta.extend(make_box(b"hdlr", &[0; 25]));
+        meta.extend(make_box(b"ilst", &ilst));
+        let mut udta = make_box(b"meta", &meta);
+        // QuickTime user data text atom
+        udta.extend(qt_text(b"\xa9alb", "Album"));
+        udta.extend(qt_text(b"\xa9nam", "Ignored"));
+        let moov_body = make_box(b"udta", &udta);
+
+        let entries = map_qt_tag_to_video_tag(parse_udta_tags(&moov_body));
+        assert_eq!(
+            entries.into_iter().collect::<Vec<_>>(),
+            [
+                (TrackInfoTag::Title, "Title".into()),
+                (TrackInfoTag::Artist, "Artist".into()),
+                (TrackInfoTag::Album, "Album".into()),
+            ]
+        );
+
+        let moov_body = make_box(b"udta", &qt_text(b"\xa9xyz", "+27.2939+112.6932/"));
+        assert_eq!(
+            parse_udta_tags(&moov_body),
+            [(
+                "com.apple.quicktime.location.ISO6709".to_string(),
+                "+27.2939+112.6932/".into()
+            )]
+        );
+
+        let moov_body = make_box(b"udta", &qt_text(b"\xa9gen", "Genre"));
+        assert!(parse_udta_tags(&moov_body).is_empty());
+    }
+
+    #[test]
+    fn udta_only_create_date() {
+        let _ = tracing_subscriber::fmt().with_test_writer().try_init();
+
+        let moov_body = make_box(b"udta", &qt_text(b"\xa9day", "2024-02-03T07:05:38Z"));
+        for info in [super::parse_qt(&moov_body), super::parse_mp4(&moov_body)] {
+            let info = info.unwrap();
+            assert!(info.get(TrackInfoTag::CreateDate).is_some());
+            assert_eq!(info.create_date_source(), Some(DateSource::QuickTimeKey));
+        }
+    }
+
+    #[test]
+    fn mdir_meta() {
+        let _ = tracing_subscriber::fmt().with_test_writer().try_init();
+
+        let mut hdlr = vec![0; 8];
+        hdlr.extend(b"mdirappl");
+        hdlr.extend([0; 9]);
+        let mut ilst = itunes_item(b"\xa9nam", "Title");
+        ilst.extend(itunes_item(b"\xa9day", "2024-02-03T07:05:38Z"));
+        ilst.extend(itunes_item(b"\xa9gen", "Ignored"));
+
+        // `meta` may be either a normal box or a full box
+        let mut meta = make_box(b"hdlr", &hdlr);
+        meta.extend(make_box(b"ilst", &ilst));
+        let mut full_meta = vec![0; 4];
+        full_meta.extend(&meta);
+
+        // without `mvhd`, the date comes from the `©day` item
+        let info = super::parse_mp4(&make_box(b"meta", &meta)).unwrap();
+        assert!(info.get(TrackInfoTag::CreateDate).is_some());
+        assert_eq!(info.create_date_source(), Some(DateSource::QuickTimeKey));
+
+        for meta in [meta, full_meta] {
+            let (_, entries) = parse_moov_body(&make_box(b"meta", &meta)).unwrap();
+            assert_eq!(
+                entries.unwrap(),
+                [
+                    ("com.apple.quicktime.title".to_string(), "Title".into()),
+                    (
+                        "com.apple.quicktime.creationdate".to_string(),
+                        "2024-02-03T07:05:38Z".into()
+                    ),
+                ]
+            );
+        }
+    }
+
+    #[test_case("meta.mov", &[(1, 0, "vide"), (2, 0, "soun"), (3, 0, "meta"), (4, 0, "meta")])]
+    #[test_case("meta.mp4", &[(1, 0, "vide"), (2, 0, "soun")])]
+    #[test_case("3gp_640x360.3gp", &[(1, 0, "vide")])]
+    fn tracks(path: &str, expect: &[(u32, u16, &str)]) {
+        let _ = tracing_subscriber::fmt().with_test_writer().try_init();
+
+        let buf = read_sample(path).unwrap();
+        let range = extract_moov_body_from_buf(&buf).unwrap();
+        let tracks = parse_tracks(&buf[range])
+            .into_iter()
+            .map(|x| (x.track_id, x.alternate_group, x.handler_type))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            tracks,
+            expect
+                .iter()
+                .map(|x| (x.0, x.1, x.2.to_string()))
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test_case("meta.mov", Some(30))]
+    #[test_case("meta.mp4", Some(32))]
+    #[test_case("3gp_640x360.3gp", Some(400))]
+    fn frame_count(path: &str, expect: Option<u64>) {
+        let _ = tracing_subscriber::fmt().with_test_writer().try_init();
+
+        let buf = read_sample(path).unwrap();
+        let range = extract_moov_body_from_buf(&buf).unwrap();
+        assert_eq!(parse_frame_count(&buf[range]), expect);
+    }
+
+    #[test]
+    fn stts_sample_count_bounds() {
+        let _ = tracing_subscriber::fmt().with_test_writer().try_init();
+
+        let mut stts = vec![0, 0, 0, 0, 0, 0, 0, 2];
+        stts.extend([0, 0, 0, 10, 0, 0, 2, 0, 0, 0, 0, 5, 0, 0, 2, 0]);
+        assert_eq!(stts_sample_count(&stts), Some(15));
+
+        // entry_count exceeds the box size
+        stts[7] = 3;
+        assert_eq!(stts_sample_count(&stts), None);
+        stts[4] = 0xff;
+        assert_eq!(stts_sample_count(&stts), None);
+        assert_eq!(stts_sample_count(&stts[..6]), None);
+    }
+
+    fn video_trak(sample_entry_children: &[u8]) -> Vec<u8> {
+        let mut hdlr = vec![0; 8];
+        hdlr.extend_from_slice(b"vide");
+        hdlr.extend_from_slice(&[0; 13]);
+
+        let mut avc1 = vec![0; VISUAL_SAMPLE_ENTRY_SIZE];
+        avc1.extend_from_slice(sample_entry_children);
+        let mut stsd = vec![0, 0, 0, 0, 0, 0, 0, 1];
+        stsd.extend(make_box(b"avc1", &avc1));
+
+        let stbl = make_box(b"stbl", &make_box(b"stsd", &stsd));
+        let mut mdia = make_box(b"hdlr", &hdlr);
+        mdia.extend(make_box(b"minf", &stbl));
+        make_box(b"trak", &make_box(b"mdia", &mdia))
+    }
+
+    #[test]
+    fn spherical_video() {
+        let _ = tracing_subscriber::fmt().with_test_writer().try_init();
+
+        let mut proj = make_box(b"prhd", &[0; 16]);
+        proj.extend(make_box(b"equi", &[0; 20]));
+        let mut sv3d = make_box(b"svhd", &[0, 0, 0, 0, 0]);
+        sv3d.extend(make_box(b"proj", &proj));
+        let mut children = make_box(b"avcC", &[1, 2, 3]);
+        children.extend(make_box(b"st3d", &[0, 0, 0, 0, 1]));
+        children.extend(make_box(b"sv3d", &sv3d));
+        // terminator
+        children.extend([0; 4]);
+
+        let moov_body = video_trak(&children);
+        let entries = parse_video_sample_entry(&moov_body);
+        assert_eq!(
+            entries.into_iter().collect::<Vec<_>>(),
+            [
+                (TrackInfoTag::Projection, "equirectangular".into()),
+                (TrackInfoTag::StereoMode, "top-bottom".into()),
+            ]
+        );
+
+        let moov_body = video_trak(&make_box(b"avcC", &[1, 2, 3]));
+        assert!(parse_video_sample_entry(&moov_body).is_empty());
+
+        let buf = read_sample("meta.mp4").unwrap();
+        let range = extract_moov_body_from_buf(&buf).unwrap();
+        // BT.709
+        assert_eq!(
+            parse_video_sample_entry(&buf[range])
+                .into_iter()
+                .collect::<Vec<_>>(),
+            [
+                (TrackInfoTag::ColorPrimaries, 1_u16.into()),
+                (TrackInfoTag::TransferCharacteristics, 1_u16.into()),
+            ]
+        );
+    }
+
+    #[test_case(b"nclx", &[0, 9, 0, 16, 0, 9, 0x80], Some((9, 16)); "nclx pq")]
+    #[test_case(b"nclc", &[0, 1, 0, 1, 0, 1], Some((1, 1)); "nclc bt709")]
+    #[test_case(b"nclx", &[0, 9, 0], None; "truncated")]
+    #[test_case(b"prof", &[0; 16], None; "icc profile")]
+    fn colr(colour_type: &[u8], data: &[u8], expect: Option<(u16, u16)>) {
+        let _ = tracing_subscriber::fmt().with_test_writer().try_init();
+
+        let mut body = colour_type.to_vec();
+        body.extend_from_slice(data);
+        assert_eq!(parse_colr(&body), expect);
+
+        let mut children = make_box(b"avcC", &[1, 2, 3]);
+        children.extend(make_box(b"colr", &body));
+        let entries = parse_video_sample_entry(&video_trak(&children));
+        assert_eq!(
+            entries.get(&TrackInfoTag::ColorPrimaries),
+            expect.map(|x| x.0.into()).as_ref()
+        );
+        assert_eq!(
+            entries.get(&TrackInfoTag::TransferCharacteristics),
+            expect.map(|x| x.1.into()).as_ref()
+        );
+    }
+
     #[test]
     fn test_iso_8601_tz_to_rfc3339() {
         let _ = tracing_subscriber::fmt().with_test_writer().try_init();
@@ -2,7 +2,7 @@ use std::{
     cmp::{max, min},
     fmt::{Debug, Display},
     fs::File,
-    io::{self, Read, Seek},
+    io::{self, Cursor, Read, Seek},
     marker::PhantomData,
     net::TcpStream,
     ops::Range,
@@ -11,13 +11,20 @@ use std::{
 
 use crate::{
     buffer::Buffers,
-    error::{ParsedError, ParsingError, ParsingErrorState},
-    exif::{parse_exif_iter, TiffHeader},
-    file::Mime,
+    cr3::Cr3Boxes,
+    ebml::webm::EbmlFileInfo,
+    error::{nom_error_to_parsing_error_with_state, ParsedError, ParsingError, ParsingErrorState},
+    exif::{
+        is_exif_not_found, parse_exif_iter, parse_exif_iter_from_jpeg_segment, LensResolver,
+        TiffHeader,
+    },
+    file::{parse_compatible_brands, MediaType, Mime},
+    heif::{self, find_embedded_mov},
+    jpeg,
     partial_vec::PartialVec,
     skip::Skip,
     video::parse_track_info,
-    ExifIter, Seekable, TrackInfo, Unseekable,
+    ExifIter, Iptc, LensResolverFn, Seekable, TrackInfo, Unseekable,
 };
 
 /// `MediaSource` represents a media data source that can be parsed by
@@ -26,6 +33,16 @@ use crate::{
 /// - Use `MediaSource::file_path(path)` or `MediaSource::file(file)` to create
 ///   a MediaSource from a file
 ///
+/// - Use `MediaSource::mmap(path)` to create a MediaSource from a memory-mapped
+///   file, which requires the `mmap` feature flag
+///
+/// - Use `MediaSource::gzip(reader)` or `MediaSource::deflate(reader)` to
+///   create a MediaSource from a compressed stream, which requires the
+///   `flate2` feature flag
+///
+/// - Use `MediaSource::cursor(data)` to create a MediaSource from in-memory
+///   data
+///
 /// - Use `MediaSource::tcp_stream(stream)` to create a MediaSource from a `TcpStream`
 /// - In other cases:
 ///
@@ -33,6 +50,9 @@ use crate::{
 ///   
 ///   - Use `MediaSource::unseekable(reader)` to create a MediaSource from a
 ///     reader that only impl `Read`
+///
+///   - Use `MediaSource::at_offset(reader, offset)` to create a MediaSource
+///     for the media embedded at `offset` of a `Read + Seek`
 ///   
 /// `seekable` is preferred to `unseekable`, since the former is more efficient
 /// when the parser needs to skip a large number of bytes.
@@ -44,6 +64,9 @@ pub struct MediaSource<R, S = Seekable> {
     pub(crate) reader: R,
     pub(crate) buf: Vec<u8>,
     pub(crate) mime: Mime,
+    /// Position of the media in the reader, only tracked for `Seekable`
+    /// sources, see [`MediaParser::parse_ref`].
+    start: u64,
     phantom: PhantomData<S>,
 }
 
@@ -61,18 +84,23 @@ impl<R, S: Skip<R>> Debug for MediaSource<R, S> {
 const HEADER_PARSE_BUF_SIZE: usize = 128;
 
 impl<R: Read, S: Skip<R>> MediaSource<R, S> {
-    fn build(mut reader: R) -> crate::Result<Self> {
+    fn build(reader: R) -> crate::Result<Self> {
+        Self::build_with_capacity(reader, HEADER_PARSE_BUF_SIZE)
+    }
+
+    /// Pre-reads `cap` bytes (at least enough for parsing header), the bytes
+    /// read will be handed over to the parser's buffer.
+    fn build_with_capacity(mut reader: R, cap: usize) -> crate::Result<Self> {
         // TODO: reuse MediaParser to parse header
-        let mut buf = Vec::with_capacity(HEADER_PARSE_BUF_SIZE);
-        reader
-            .by_ref()
-            .take(HEADER_PARSE_BUF_SIZE as u64)
-            .read_to_end(&mut buf)?;
-        let mime: Mime = buf.as_slice().try_into()?;
+        let cap = max(cap, HEADER_PARSE_BUF_SIZE);
+        let buf = read_up_to(&mut reader, cap)?;
+        let header = &buf[..min(buf.len(), HEADER_PARSE_BUF_SIZE)]; // Safe-slice
+        let mime: Mime = header.try_into()?;
         Ok(Self {
             reader,
             buf,
             mime,
+            start: 0,
             phantom: PhantomData,
         })
     }
@@ -90,15 +118,157 @@ impl<R: Read, S: Skip<R>> MediaSource<R, S> {
             Mime::Video(_) => false,
         }
     }
+
+    /// Returns the media format detected when this source was built.
+    pub fn media_type(&self) -> MediaType {
+        self.mime.into()
+    }
+
+    /// Returns the compatible brands declared in the `ftyp` box, e.g. `["isom",
+    /// "mp41"]`, so that the caller can make format-compatibility decisions
+    /// without reparsing the file. Returns an empty `Vec` for non-ISOBMFF
+    /// media.
+    ///
+    /// Only the probe read when building the source (128 bytes, or the `cap`
+    /// of [`MediaSource::buffered`]) is examined, brands beyond it are
+    /// omitted.
+    ///
+    /// ```rust
+    /// use nom_exif::*;
+    ///
+    /// let ms = MediaSource::file_path("./testdata/meta.mp4").unwrap();
+    /// assert!(ms.compatible_brands().iter().any(|x| x == "mp41"));
+    ///
+    /// let ms = MediaSource::file_path("./testdata/exif.jpg").unwrap();
+    /// assert!(ms.compatible_brands().is_empty());
+    /// ```
+    pub fn compatible_brands(&self) -> Vec<String> {
+        parse_compatible_brands(&self.buf)
+    }
+
+    /// Consumes the source and returns `(reader, probe, media_type)`, so that
+    /// the caller can take over the I/O after sniffing the media type, e.g.
+    /// hand the file over to another tool.
+    ///
+    /// Building a `MediaSource` only reads a small probe (128 bytes, or the
+    /// `cap` of [`MediaSource::buffered`]) from the reader, no parser buffer
+    /// is allocated. The reader is positioned right after the probe bytes,
+    /// which are returned as `probe`.
+    ///
+    /// ```rust
+    /// use nom_exif::*;
+    /// use std::io::Read;
+    ///
+    /// let ms = MediaSource::file_path("./testdata/exif.jpg").unwrap();
+    /// let (mut file, probe, media_type) = ms.into_parts();
+    /// assert_eq!(media_type, MediaType::Jpeg);
+    ///
+    /// let mut data = probe;
+    /// file.read_to_end(&mut data).unwrap();
+    /// assert_eq!(data, std::fs::read("./testdata/exif.jpg").unwrap());
+    /// ```
+    pub fn into_parts(self) -> (R, Vec<u8>, MediaType) {
+        (self.reader, self.buf, self.mime.into())
+    }
+}
+
+/// Reads at most `size` bytes with as few read calls as possible, unlike
+/// `read_to_end`, which starts with small reads and grows them gradually.
+fn read_up_to<R: Read>(reader: &mut R, size: usize) -> io::Result<Vec<u8>> {
+    let mut buf = vec![0; size];
+    let mut n = 0;
+    while n < size {
+        match reader.read(&mut buf[n..]) {
+            Ok(0) => break,
+            Ok(x) => n += x,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        }
+    }
+    buf.truncate(n);
+    Ok(buf)
 }
 
 impl<R: Read + Seek> MediaSource<R, Seekable> {
     pub fn seekable(reader: R) -> crate::Result<Self> {
-        Self::build(reader)
+        Self::build_seekable(reader, HEADER_PARSE_BUF_SIZE)
+    }
+
+    /// Creates a `MediaSource` which pre-reads `cap` bytes from `reader` in
+    /// one go, rather than the small chunk needed for detecting the media
+    /// type.
+    ///
+    /// The pre-read bytes are handed over to the parser's buffer, so nothing
+    /// is read twice. This reduces the number of read calls (i.e. syscalls
+    /// for a raw `File`) when the metadata is located near the beginning,
+    /// e.g. the Exif data of a JPEG image. 64 KiB covers the Exif data of
+    /// most JPEG images.
+    ///
+    /// ```rust
+    /// use nom_exif::*;
+    /// use std::fs::File;
+    ///
+    /// let mut parser = MediaParser::new();
+    /// let f = File::open("./testdata/exif.jpg").unwrap();
+    /// let ms = MediaSource::buffered(f, 64 * 1024).unwrap();
+    /// let exif: Exif = parser.parse::<_, _, ExifIter>(ms).unwrap().into();
+    /// assert_eq!(exif.get(ExifTag::Make).unwrap().as_str().unwrap(), "vivo");
+    /// ```
+    pub fn buffered(reader: R, cap: usize) -> crate::Result<Self> {
+        Self::build_seekable(reader, cap)
+    }
+
+    /// Creates a `MediaSource` for the media which starts at `offset` of
+    /// `reader`, e.g. a JPEG image embedded in a PDF file.
+    ///
+    /// The reader is seeked to `offset` before detecting the media type, and
+    /// `offset` is treated as the origin of the media during parsing, so
+    /// there is no need to copy the sub-range out.
+    ///
+    /// ```rust
+    /// use nom_exif::*;
+    /// use std::io::Cursor;
+    ///
+    /// let mut data = b"some leading bytes".to_vec();
+    /// let offset = data.len() as u64;
+    /// data.extend(std::fs::read("./testdata/exif.jpg").unwrap());
+    ///
+    /// let mut parser = MediaParser::new();
+    /// let ms = MediaSource::at_offset(Cursor::new(data), offset).unwrap();
+    /// let exif: Exif = parser.parse::<_, _, ExifIter>(ms).unwrap().into();
+    /// assert_eq!(exif.get(ExifTag::Make).unwrap().as_str().unwrap(), "vivo");
+    /// ```
+    pub fn at_offset(mut reader: R, offset: u64) -> crate::Result<Self> {
+        reader.seek(io::SeekFrom::Start(offset))?;
+        Self::build_seekable(reader, HEADER_PARSE_BUF_SIZE)
+    }
+
+    fn build_seekable(mut reader: R, cap: usize) -> crate::Result<Self> {
+        let start = reader.stream_position()?;
+        let mut ms = Self::build_with_capacity(reader, cap)?;
+        ms.start = start;
+        Ok(ms)
     }
 }
 
 impl<R: Read> MediaSource<R, Unseekable> {
+    /// Creates a `MediaSource` from a reader which only impl `Read`, skipping
+    /// data is done by reading (and discarding) it.
+    ///
+    /// Type-erased readers, e.g. `&mut dyn Read` or `Box<dyn Read>`, are
+    /// supported as well:
+    ///
+    /// ```rust
+    /// use nom_exif::*;
+    /// use std::io::Read;
+    ///
+    /// let mut file = std::fs::File::open("./testdata/meta.mov").unwrap();
+    /// let reader: &mut dyn Read = &mut file;
+    ///
+    /// let mut parser = MediaParser::new();
+    /// let info: TrackInfo = parser.parse(MediaSource::unseekable(reader).unwrap()).unwrap();
+    /// assert_eq!(info.get(TrackInfoTag::Make), Some(&"Apple".into()));
+    /// ```
     pub fn unseekable(reader: R) -> crate::Result<Self> {
         Self::build(reader)
     }
@@ -112,6 +282,125 @@ impl MediaSource<File, Seekable> {
     pub fn file(file: File) -> crate::Result<Self> {
         Self::seekable(file)
     }
+
+    /// Opens the file at `path` and returns the source together with its
+    /// detected [`MediaType`].
+    ///
+    /// ```rust
+    /// use nom_exif::*;
+    ///
+    /// let (ms, media_type) = MediaSource::open("./testdata/exif.heic").unwrap();
+    /// assert_eq!(media_type, MediaType::Heic);
+    /// assert!(ms.has_exif());
+    /// ```
+    pub fn open<P: AsRef<Path>>(path: P) -> crate::Result<(Self, MediaType)> {
+        let ms = Self::file_path(path)?;
+        let media_type = ms.media_type();
+        Ok((ms, media_type))
+    }
+}
+
+#[cfg(feature = "mmap")]
+impl MediaSource<io::Cursor<memmap2::Mmap>, Seekable> {
+    /// Memory-maps the file at `path`, and creates a seekable `MediaSource`
+    /// from the mapped bytes. This feature requires the `mmap` feature flag.
+    ///
+    /// This is more efficient for large files, e.g. videos whose `moov` box
+    /// is at the end of the file, since skipping the `mdat` box is just a
+    /// seek and no data needs to be read.
+    ///
+    /// **Note**: The behavior is undefined if the file is modified or
+    /// truncated (by this or another process) while it's mapped, e.g. on
+    /// Unix the process may receive a `SIGBUS`. Please make sure the file
+    /// won't be changed until the `MediaSource` has been parsed.
+    ///
+    /// ```rust
+    /// use nom_exif::*;
+    ///
+    /// let mut parser = MediaParser::new();
+    /// let ms = MediaSource::mmap("./testdata/meta.mov").unwrap();
+    /// let info: TrackInfo = parser.parse(ms).unwrap();
+    /// assert_eq!(info.get(TrackInfoTag::Make), Some(&"Apple".into()));
+    /// ```
+    pub fn mmap<P: AsRef<Path>>(path: P) -> crate::Result<Self> {
+        let file = File::open(path)?;
+        // Safety: See the note above, the caller is responsible for keeping
+        // the file unchanged.
+        let mmap = unsafe { memmap2::Mmap::map(&file)? };
+        Self::from_mmap(mmap)
+    }
+
+    /// Creates a seekable `MediaSource` from an already mapped file. This
+    /// feature requires the `mmap` feature flag.
+    ///
+    /// See [`MediaSource::mmap`] for the caveats.
+    pub fn from_mmap(mmap: memmap2::Mmap) -> crate::Result<Self> {
+        Self::seekable(io::Cursor::new(mmap))
+    }
+}
+
+#[cfg(feature = "flate2")]
+impl<R: Read> MediaSource<flate2::read::GzDecoder<R>, Unseekable> {
+    /// Creates an unseekable `MediaSource` which transparently decompresses
+    /// the gzip-compressed `reader`. This feature requires the `flate2`
+    /// feature flag.
+    ///
+    /// Since a compressed stream can't be seeked, skipping data means
+    /// decompressing it, e.g. a trailing `moov` box of a MOV file can only be
+    /// reached by streaming through the whole `mdat` box.
+    ///
+    /// ```rust
+    /// use nom_exif::*;
+    /// use std::io::Write;
+    ///
+    /// let mut encoder = flate2::write::GzEncoder::new(Vec::new(), Default::default());
+    /// encoder.write_all(&std::fs::read("./testdata/exif.jpg").unwrap()).unwrap();
+    /// let compressed = encoder.finish().unwrap();
+    ///
+    /// let mut parser = MediaParser::new();
+    /// let ms = MediaSource::gzip(compressed.as_slice()).unwrap();
+    /// let exif: Exif = parser.parse::<_, _, ExifIter>(ms).unwrap().into();
+    /// assert_eq!(exif.get(ExifTag::Make).and_then(|x| x.as_str()), Some("vivo"));
+    /// ```
+    pub fn gzip(reader: R) -> crate::Result<Self> {
+        Self::unseekable(flate2::read::GzDecoder::new(reader))
+    }
+}
+
+#[cfg(feature = "flate2")]
+impl<R: Read> MediaSource<flate2::read::DeflateDecoder<R>, Unseekable> {
+    /// Creates an unseekable `MediaSource` which transparently decompresses
+    /// the raw deflate `reader`. This feature requires the `flate2` feature
+    /// flag.
+    ///
+    /// See [`MediaSource::gzip`] for the caveats.
+    pub fn deflate(reader: R) -> crate::Result<Self> {
+        Self::unseekable(flate2::read::DeflateDecoder::new(reader))
+    }
+}
+
+impl MediaSource<io::Cursor<Vec<u8>>, Seekable> {
+    /// Creates a seekable `MediaSource` from in-memory data, e.g. an upload
+    /// or a fuzzing input.
+    ///
+    /// This is equivalent to `MediaSource::seekable(Cursor::new(data))`.
+    /// In-memory data is always seekable, skipping data (e.g. the `mdat` box
+    /// of a video) is just a change of the position. Please don't use
+    /// [`MediaSource::unseekable`] for a `Cursor`, which skips data by
+    /// reading and copying it.
+    ///
+    /// ```rust
+    /// use nom_exif::*;
+    ///
+    /// let data = std::fs::read("./testdata/meta.mov").unwrap();
+    ///
+    /// let mut parser = MediaParser::new();
+    /// let info: TrackInfo = parser.parse(MediaSource::cursor(data).unwrap()).unwrap();
+    /// assert_eq!(info.get(TrackInfoTag::Make), Some(&"Apple".into()));
+    /// ```
+    pub fn cursor(data: Vec<u8>) -> crate::Result<Self> {
+        Self::seekable(io::Cursor::new(data))
+    }
 }
 
 impl MediaSource<TcpStream, Unseekable> {
@@ -140,14 +429,43 @@ pub(crate) trait Buf {
 #[derive(Debug, Clone)]
 pub(crate) enum ParsingState {
     TiffHeader(TiffHeader),
-    HeifExifSize(usize),
+    HeifExif {
+        exif_size: usize,
+        /// Exif `Orientation` equivalent to the primary item's `irot`/`imir`
+        orientation: Option<u16>,
+    },
+    /// The buffer starts at the XMP packet of a HEIF/HEIC file.
+    HeifXmp {
+        xmp_size: usize,
+    },
+    WebmFileInfo(Box<EbmlFileInfo>),
+    /// The buffer starts at a PNG chunk, rather than the PNG signature.
+    PngChunk,
+    /// The buffer starts at a WebP chunk, rather than the RIFF header.
+    WebpChunk {
+        /// Canvas size from the `VP8X` chunk
+        canvas_size: (u32, u32),
+    },
+    /// The TIFF blocks in the Canon metadata box of a CR3 file
+    Cr3Boxes(Cr3Boxes),
 }
 
 impl Display for ParsingState {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             ParsingState::TiffHeader(h) => Display::fmt(&format!("ParsingState: {h:?})"), f),
-            ParsingState::HeifExifSize(n) => Display::fmt(&format!("ParsingState: {n}"), f),
+            ParsingState::HeifExif { exif_size, .. } => {
+                Display::fmt(&format!("ParsingState: {exif_size}"), f)
+            }
+            ParsingState::HeifXmp { xmp_size } => {
+                Display::fmt(&format!("ParsingState: HeifXmp {xmp_size}"), f)
+            }
+            ParsingState::WebmFileInfo(info) => Display::fmt(&format!("ParsingState: {info:?}"), f),
+            ParsingState::PngChunk => Display::fmt("ParsingState: PngChunk", f),
+            ParsingState::Cr3Boxes(boxes) => Display::fmt(&format!("ParsingState: {boxes:?}"), f),
+            ParsingState::WebpChunk { canvas_size } => {
+                Display::fmt(&format!("ParsingState: WebpChunk {canvas_size:?}"), f)
+            }
         }
     }
 }
@@ -313,14 +631,25 @@ impl<R: Read, S: Skip<R>> ParseOutput<R, S> for TrackInfo {
         if !ms.has_track() {
             return Err(crate::Error::ParseFailed("no track info here".into()));
         }
-        let out = parser.load_and_parse::<R, S, _, _>(ms.reader.by_ref(), |data, _| {
-            parse_track_info(data, ms.mime.unwrap_video())
-                .map_err(|e| ParsingErrorState::new(e, None))
+        let out = parser.load_and_parse::<R, S, _, _>(ms.reader.by_ref(), |data, state| {
+            parse_track_info(data, ms.mime.unwrap_video(), state)
         })?;
         Ok(out)
     }
 }
 
+/// Exif data and track info parsed from a single source, see
+/// [`MediaParser::parse_all`].
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct MediaMetadata {
+    /// Exif data of an image
+    pub exif: Option<ExifIter>,
+    /// Track info of a video/audio file, or of the motion clip embedded in a
+    /// HEIF/HEIC image
+    pub track: Option<TrackInfo>,
+}
+
 /// A `MediaParser`/`AsyncMediaParser` can parse media info from a
 /// [`MediaSource`].
 ///
@@ -375,6 +704,10 @@ pub struct MediaParser {
     bb: Buffers,
     buf: Option<Vec<u8>>,
     position: usize,
+    pub(crate) swap_endian_on_mismatch: bool,
+    pub(crate) search_exif_on_corrupt_jpeg: bool,
+    pub(crate) lens_resolver: Option<LensResolver>,
+    pub(crate) max_entry_components: Option<u32>,
 }
 
 impl Debug for MediaParser {
@@ -383,6 +716,11 @@ impl Debug for MediaParser {
             .field("buffers", &self.bb)
             .field("buf len", &self.buf.as_ref().map(|x| x.len()))
             .field("position", &self.position)
+            .field("swap_endian_on_mismatch", &self.swap_endian_on_mismatch)
+            .field(
+                "search_exif_on_corrupt_jpeg",
+                &self.search_exif_on_corrupt_jpeg,
+            )
             .finish_non_exhaustive()
     }
 }
@@ -393,6 +731,10 @@ impl Default for MediaParser {
             bb: Buffers::new(),
             buf: None,
             position: 0,
+            swap_endian_on_mismatch: false,
+            search_exif_on_corrupt_jpeg: false,
+            lens_resolver: None,
+            max_entry_components: None,
         }
     }
 }
@@ -411,11 +753,85 @@ impl ShareBuf for MediaParser {
     }
 }
 
+/// Returns true if a motion clip may be embedded in images of `media_type`,
+/// which can be located by [`find_embedded_mov`].
+fn has_embedded_track(media_type: MediaType) -> bool {
+    matches!(
+        media_type,
+        MediaType::Heic | MediaType::Heif | MediaType::Avif
+    )
+}
+
 impl MediaParser {
     pub fn new() -> Self {
         Self::default()
     }
 
+    /// Retry with the opposite byte order when the TIFF header's declared
+    /// endianness looks wrong, i.e. the IFD0 entry num is absurd under the
+    /// declared byte order but sane under the opposite one. Disabled by
+    /// default.
+    ///
+    /// A warning is logged whenever such a mismatch is detected, whether this
+    /// option is enabled or not.
+    pub fn swap_endian_on_mismatch(mut self, enable: bool) -> Self {
+        self.swap_endian_on_mismatch = enable;
+        self
+    }
+
+    /// Search the "Exif\0\0" signature linearly in the first 64 KiB of a
+    /// JPEG file when its segments can't be walked, e.g. the segment length
+    /// bytes are corrupt. Disabled by default.
+    ///
+    /// This recovers Exif data from mildly damaged files, at the risk of
+    /// picking up an Exif block which isn't the primary one.
+    pub fn search_exif_on_corrupt_jpeg(mut self, enable: bool) -> Self {
+        self.search_exif_on_corrupt_jpeg = enable;
+        self
+    }
+
+    /// Reject Exif entries with more than `max` components, which are then
+    /// reported as errors. Unlimited by default.
+    ///
+    /// An entry can never claim more components than its data holds, since
+    /// the data is sliced from the loaded Exif buffer. This limit further
+    /// bounds the memory allocated for a single array value (e.g. a
+    /// `U32Array` takes 4 bytes per component) when parsing untrusted input.
+    ///
+    /// Note that the components of `Undefined` entries (e.g. `MakerNote`)
+    /// are bytes, so a small limit will reject them too.
+    pub fn max_entry_components(mut self, max: u32) -> Self {
+        self.max_entry_components = Some(max);
+        self
+    }
+
+    /// Set a resolver which turns `(Make, lens type)` into a lens name, e.g.
+    /// by looking up a lens database, which isn't shipped with this crate.
+    ///
+    /// The numeric lens type ([`ExifTag::LensType`](crate::ExifTag::LensType))
+    /// is read from the maker note, see [`ExifIter::lens_type`], and the
+    /// resolved name is returned by [`ExifIter::lens_name`]. The lens type is
+    /// vendor specific, so `make` is passed as well.
+    ///
+    /// ```rust
+    /// use nom_exif::*;
+    ///
+    /// let mut parser = MediaParser::new();
+    /// parser.set_lens_resolver(Box::new(|make, lens_type| {
+    ///     match (make, lens_type) {
+    ///         ("Canon", 254) => Some("Canon EF 100mm f/2.8L Macro IS USM".to_owned()),
+    ///         _ => None,
+    ///     }
+    /// }));
+    ///
+    /// let ms = MediaSource::file_path("./testdata/exif.jpg").unwrap();
+    /// let iter: ExifIter = parser.parse(ms).unwrap();
+    /// assert!(iter.lens_name().is_none());
+    /// ```
+    pub fn set_lens_resolver(&mut self, resolver: LensResolverFn) {
+        self.lens_resolver = Some(resolver.into());
+    }
+
     /// `MediaParser`/`AsyncMediaParser` comes with its own buffer management,
     /// so that buffers can be reused during multiple parsing processes to
     /// avoid frequent memory allocations. Therefore, try to reuse a
@@ -449,6 +865,347 @@ impl MediaParser {
         res
     }
 
+    /// Like [`Self::parse`], but borrows the `MediaSource` instead of
+    /// consuming it, so that the same source can be parsed multiple times,
+    /// e.g. parse an [`ExifIter`] first, and then parse it again for a
+    /// [`TrackInfo`], without reopening the file.
+    ///
+    /// The reader is rewound after each parsing, so this method requires a
+    /// [`Seekable`] source. The source is left as it was before the call, so
+    /// it can still be consumed by [`Self::parse`] afterwards.
+    ///
+    /// ```rust
+    /// use nom_exif::*;
+    ///
+    /// let mut parser = MediaParser::new();
+    /// let mut ms = MediaSource::file_path("./testdata/exif.jpg").unwrap();
+    ///
+    /// let iter: ExifIter = parser.parse_ref(&mut ms).unwrap();
+    /// let exif: Exif = iter.into();
+    /// assert_eq!(exif.get(ExifTag::Make).unwrap().as_str().unwrap(), "vivo");
+    ///
+    /// // Parse it again, e.g. for the GPS info only
+    /// let iter: ExifIter = parser.parse_ref(&mut ms).unwrap();
+    /// assert!(iter.parse_gps_info().unwrap().is_some());
+    /// ```
+    pub fn parse_ref<R: Read + Seek, O>(
+        &mut self,
+        ms: &mut MediaSource<R, Seekable>,
+    ) -> crate::Result<O>
+    where
+        O: for<'a> ParseOutput<&'a mut R, Seekable>,
+    {
+        // The reader is always kept right after the pre-read bytes, so the
+        // source stays intact for the next parsing.
+        let header_end = ms.start + ms.buf.len() as u64;
+        ms.reader.seek(io::SeekFrom::Start(header_end))?;
+        let borrowed = MediaSource {
+            reader: &mut ms.reader,
+            buf: ms.buf.clone(),
+            mime: ms.mime,
+            start: ms.start,
+            phantom: PhantomData,
+        };
+        let res = self.parse(borrowed);
+        ms.reader.seek(io::SeekFrom::Start(header_end))?;
+        res
+    }
+
+    /// Parses the [`TrackInfo`] of the motion clip (MOV/MP4) embedded in a
+    /// HEIF/HEIC image, e.g. a Live Photo or a Motion Photo.
+    ///
+    /// The clip is located either after the top level boxes of the image, or
+    /// inside its `mdat` box. Returns `Ok(None)` if the image has no embedded
+    /// clip, or an error if `ms` is not a HEIF/HEIC/AVIF image.
+    ///
+    /// **Note**: Motion Photos stored as JPEG files, i.e. with the clip
+    /// appended after the JPEG data, are not supported yet.
+    ///
+    /// The still's Exif can be parsed from the same source beforehand with
+    /// [`Self::parse_ref`]:
+    ///
+    /// ```rust
+    /// use nom_exif::*;
+    ///
+    /// let mut parser = MediaParser::new();
+    /// let mut ms = MediaSource::file_path("./testdata/exif.heic").unwrap();
+    ///
+    /// let iter: ExifIter = parser.parse_ref(&mut ms).unwrap();
+    /// let exif: Exif = iter.into();
+    /// assert_eq!(exif.get(ExifTag::Make).unwrap().as_str().unwrap(), "Apple");
+    ///
+    /// // This image has no embedded clip
+    /// assert!(parser.parse_embedded_track(ms).unwrap().is_none());
+    /// ```
+    pub fn parse_embedded_track<R: Read + Seek>(
+        &mut self,
+        mut ms: MediaSource<R, Seekable>,
+    ) -> crate::Result<Option<TrackInfo>> {
+        if !has_embedded_track(ms.media_type()) {
+            return Err(crate::Error::ParseFailed(
+                "not a HEIF/HEIC/AVIF image".into(),
+            ));
+        }
+
+        let Some(offset) = find_embedded_mov(&mut ms.reader, ms.start)? else {
+            return Ok(None);
+        };
+        tracing::debug!(offset, "embedded clip found");
+
+        let clip = MediaSource::at_offset(ms.reader, offset)?;
+        if !clip.has_track() {
+            return Ok(None);
+        }
+        self.parse(clip).map(Some)
+    }
+
+    /// Parses everything `ms` contains in one call: the Exif data of an image,
+    /// the track info of a video/audio file, or both for a HEIF/HEIC/AVIF
+    /// image with an embedded motion clip (e.g. a Live Photo or a Motion
+    /// Photo), see [`Self::parse_embedded_track`] for the supported layouts.
+    ///
+    /// Errors of parsing the Exif data or the track info are returned, while
+    /// a missing Exif data or motion clip just leaves [`MediaMetadata::exif`]
+    /// or [`MediaMetadata::track`] as `None`.
+    ///
+    /// ```rust
+    /// use nom_exif::*;
+    ///
+    /// let mut parser = MediaParser::new();
+    ///
+    /// let ms = MediaSource::file_path("./testdata/exif.heic").unwrap();
+    /// let meta = parser.parse_all(ms).unwrap();
+    /// let exif: Exif = meta.exif.unwrap().into();
+    /// assert_eq!(exif.get(ExifTag::Make).unwrap().as_str().unwrap(), "Apple");
+    /// assert!(meta.track.is_none());
+    ///
+    /// let ms = MediaSource::file_path("./testdata/meta.mov").unwrap();
+    /// let meta = parser.parse_all(ms).unwrap();
+    /// assert!(meta.exif.is_none());
+    /// assert!(meta.track.is_some());
+    /// ```
+    pub fn parse_all<R: Read + Seek>(
+        &mut self,
+        mut ms: MediaSource<R, Seekable>,
+    ) -> crate::Result<MediaMetadata> {
+        if ms.has_track() {
+            let track = self.parse(ms)?;
+            return Ok(MediaMetadata {
+                exif: None,
+                track: Some(track),
+            });
+        }
+
+        let exif = match self.parse_ref(&mut ms) {
+            Ok(iter) => Some(iter),
+            Err(e) if is_exif_not_found(&e) => None,
+            Err(e) => return Err(e),
+        };
+        let track = if has_embedded_track(ms.media_type()) {
+            self.parse_embedded_track(ms)?
+        } else {
+            None
+        };
+        Ok(MediaMetadata { exif, track })
+    }
+
+    /// Parses Exif data from the payload of a JPEG `APP1` segment, which has
+    /// already been extracted by the caller, e.g. received from a network
+    /// protocol.
+    ///
+    /// The `payload` may or may not start with the "Exif\0\0" header, both
+    /// cases are supported.
+    ///
+    /// ```rust
+    /// use nom_exif::*;
+    ///
+    /// let mut payload = b"Exif\0\0".to_vec();
+    /// payload.extend_from_slice(b"II*\0\x08\0\0\0\0\0\0\0\0\0");
+    ///
+    /// let mut parser = MediaParser::new();
+    /// let iter = parser.parse_exif_from_jpeg_segment(&payload).unwrap();
+    /// assert_eq!(iter.count(), 0);
+    /// ```
+    pub fn parse_exif_from_jpeg_segment(&mut self, payload: &[u8]) -> crate::Result<ExifIter> {
+        self.reset();
+        self.acquire_buf();
+
+        self.buf_mut().extend_from_slice(payload);
+        let res = parse_exif_iter_from_jpeg_segment(self);
+
+        self.reset();
+        res
+    }
+
+    /// Extracts the raw XMP packet (XML bytes) from a JPEG or HEIF/HEIC image,
+    /// so that it can be fed to an XML parser.
+    ///
+    /// For JPEG, the `APP1` segment starting with the XMP namespace is used,
+    /// extended XMP segments are not merged. For HEIF/HEIC, the `mime` item
+    /// with the "application/rdf+xml" content type is used.
+    ///
+    /// Returns `Ok(None)` if the image has no XMP packet, or an error if the
+    /// format of `ms` is not supported.
+    ///
+    /// ```rust
+    /// use nom_exif::*;
+    ///
+    /// let mut parser = MediaParser::new();
+    ///
+    /// let ms = MediaSource::file_path("./testdata/exif.heic").unwrap();
+    /// let xmp = parser.parse_xmp(ms).unwrap().unwrap();
+    /// assert!(xmp.starts_with(b"<?xpacket begin"));
+    ///
+    /// let ms = MediaSource::file_path("./testdata/exif.jpg").unwrap();
+    /// assert!(parser.parse_xmp(ms).unwrap().is_none());
+    /// ```
+    pub fn parse_xmp<R: Read, S: Skip<R>>(
+        &mut self,
+        ms: MediaSource<R, S>,
+    ) -> crate::Result<Option<Vec<u8>>> {
+        let media_type = ms.media_type();
+        let extract = match media_type {
+            MediaType::Jpeg => |buf: &[u8], _| {
+                jpeg::extract_xmp_data(buf)
+                    .map(|res| res.1.map(|x| x.to_vec()))
+                    .map_err(|e| nom_error_to_parsing_error_with_state(e, None))
+            },
+            MediaType::Heic | MediaType::Heif | MediaType::Avif => |buf: &[u8], state| {
+                heif::extract_xmp_data(buf, state).map(|x| x.map(|x| x.to_vec()))
+            },
+            _ => {
+                return Err(crate::Error::ParseFailed(
+                    format!("XMP extraction is not supported for {media_type}").into(),
+                ))
+            }
+        };
+        self.parse_with(ms, extract)
+    }
+
+    /// Extracts the IPTC-IIM datasets (keywords, caption, etc.) stored in the
+    /// Photoshop `APP13` segment of a JPEG image, see [`Iptc`].
+    ///
+    /// Returns `Ok(None)` if the image has no IPTC-NAA resource, or an error
+    /// if `ms` is not a JPEG image.
+    ///
+    /// ```rust
+    /// use nom_exif::*;
+    ///
+    /// let mut parser = MediaParser::new();
+    /// let ms = MediaSource::file_path("./testdata/no-exif.jpg").unwrap();
+    /// assert!(parser.parse_iptc(ms).unwrap().is_none());
+    ///
+    /// let ms = MediaSource::file_path("./testdata/exif.heic").unwrap();
+    /// assert!(parser.parse_iptc(ms).is_err());
+    /// ```
+    pub fn parse_iptc<R: Read, S: Skip<R>>(
+        &mut self,
+        ms: MediaSource<R, S>,
+    ) -> crate::Result<Option<Iptc>> {
+        let media_type = ms.media_type();
+        if media_type != MediaType::Jpeg {
+            return Err(crate::Error::ParseFailed(
+                format!("IPTC extraction is not supported for {media_type}").into(),
+            ));
+        }
+
+        self.parse_with(ms, |buf, _| {
+            jpeg::extract_iptc_data(buf)
+                .map(|res| res.1.map(|x| Iptc::new(x.to_vec())))
+                .map_err(|e| nom_error_to_parsing_error_with_state(e, None))
+        })
+    }
+
+    /// Extracts the embedded ICC color profile from a JPEG or HEIF/HEIC image.
+    ///
+    /// For JPEG, the profile may be split into several `APP2` segments, which
+    /// are reassembled in order. An error is returned if the chunks are
+    /// inconsistent, e.g. a chunk is missing or duplicated. For HEIF/HEIC,
+    /// the profile is read from the `colr` property of the primary item.
+    ///
+    /// Returns `Ok(None)` if the image has no ICC profile, or an error if the
+    /// format of `ms` is not supported.
+    ///
+    /// ```rust
+    /// use nom_exif::*;
+    ///
+    /// let mut parser = MediaParser::new();
+    ///
+    /// let ms = MediaSource::file_path("./testdata/exif.heic").unwrap();
+    /// let icc = parser.parse_icc_profile(ms).unwrap().unwrap();
+    /// assert_eq!(&icc[36..40], b"acsp");
+    ///
+    /// let ms = MediaSource::file_path("./testdata/exif-one-entry.heic").unwrap();
+    /// assert!(parser.parse_icc_profile(ms).unwrap().is_none());
+    /// ```
+    pub fn parse_icc_profile<R: Read, S: Skip<R>>(
+        &mut self,
+        ms: MediaSource<R, S>,
+    ) -> crate::Result<Option<Vec<u8>>> {
+        let media_type = ms.media_type();
+        let extract = match media_type {
+            MediaType::Jpeg => |buf: &[u8], _| {
+                let (_, res) = jpeg::extract_icc_profile(buf)
+                    .map_err(|e| nom_error_to_parsing_error_with_state(e, None))?;
+                res.map_err(|e| ParsingErrorState::new(ParsingError::Failed(e), None))
+            },
+            MediaType::Heic | MediaType::Heif | MediaType::Avif => {
+                |buf: &[u8], _| heif::extract_icc_profile(buf)
+            }
+            _ => {
+                return Err(crate::Error::ParseFailed(
+                    format!("ICC profile extraction is not supported for {media_type}").into(),
+                ))
+            }
+        };
+        self.parse_with(ms, extract)
+    }
+
+    /// Parses track info from an in-memory ISOBMFF (*.mov, *.mp4, etc.) or
+    /// Matroska (*.webm, *.mkv, etc.) file, e.g. a downloaded file, without
+    /// wrapping it in a [`MediaSource`]. The file format is detected
+    /// automatically.
+    ///
+    /// ```rust
+    /// use nom_exif::*;
+    ///
+    /// let data = std::fs::read("./testdata/meta.mov").unwrap();
+    ///
+    /// let mut parser = MediaParser::new();
+    /// let info = parser.parse_track_bytes(&data).unwrap();
+    /// assert_eq!(info.get(TrackInfoTag::Make), Some(&"Apple".into()));
+    ///
+    /// // Images don't contain track info
+    /// let data = std::fs::read("./testdata/exif.jpg").unwrap();
+    /// assert!(parser.parse_track_bytes(&data).is_err());
+    /// ```
+    pub fn parse_track_bytes(&mut self, data: &[u8]) -> crate::Result<TrackInfo> {
+        self.parse(MediaSource::seekable(Cursor::new(data))?)
+    }
+
+    /// Loads data from `ms` and parses it with `parse`, for the outputs which
+    /// don't implement [`ParseOutput`].
+    fn parse_with<R: Read, S: Skip<R>, O>(
+        &mut self,
+        mut ms: MediaSource<R, S>,
+        parse: impl FnMut(&[u8], Option<ParsingState>) -> Result<O, ParsingErrorState>,
+    ) -> crate::Result<O> {
+        self.reset();
+        self.acquire_buf();
+
+        self.buf_mut().append(&mut ms.buf);
+        let res = self
+            .fill_buf(&mut ms.reader, INIT_BUF_SIZE)
+            .map_err(crate::Error::from)
+            .and_then(|_| {
+                self.load_and_parse::<R, S, _, _>(&mut ms.reader, parse)
+                    .map_err(crate::Error::from)
+            });
+
+        self.reset();
+        res
+    }
+
     fn do_parse<R: Read, S, O: ParseOutput<R, S>>(
         &mut self,
         mut ms: MediaSource<R, S>,
@@ -571,9 +1328,28 @@ mod tests {
         }
     }
 
-    use crate::testkit::open_sample;
+    #[test_case("exif.jpg", MediaType::Jpeg)]
+    #[test_case("exif.heic", MediaType::Heic)]
+    #[test_case("tif.tif", MediaType::Tiff)]
+    #[test_case("fujifilm_x_t1_01.raf.meta", MediaType::Raf)]
+    #[test_case("meta.mov", MediaType::QuickTime)]
+    #[test_case("meta.mp4", MediaType::Mp4)]
+    #[test_case("webm_480.webm", MediaType::Webm)]
+    #[test_case("mkv_640x360.mkv", MediaType::Matroska)]
+    fn open_media_type(path: &str, expect: MediaType) {
+        let (ms, media_type) = MediaSource::open(Path::new("testdata").join(path)).unwrap();
+        assert_eq!(media_type, expect);
+        assert_eq!(ms.has_exif(), media_type.is_image());
+        assert_eq!(ms.has_track(), media_type.is_video());
+    }
+
+    use crate::testkit::{
+        assert_exif_snapshot, assert_track_snapshot, open_sample, read_sample, TiffBuilder,
+        TiffValue,
+    };
     use crate::{EntryValue, ExifTag, TrackInfoTag};
     use chrono::DateTime;
+    use nom::number::Endianness;
     use test_case::test_case;
 
     use crate::video::TrackInfoTag::*;
@@ -585,11 +1361,23 @@ mod tests {
     #[test_case("meta.mov", Make, "Apple".into())]
     #[test_case("meta.mov", Model, "iPhone X".into())]
     #[test_case("meta.mov", GpsIso6709, "+27.1281+100.2508+000.000/".into())]
+    #[test_case("meta.mov", Timescale, 600_u32.into())]
+    #[test_case("meta.mov", PreferredRate, EntryValue::F64(1.0))]
+    #[test_case("meta.mov", ColorPrimaries, 12_u16.into())]
+    #[test_case("meta.mov", TransferCharacteristics, 1_u16.into())]
     #[test_case("meta.mp4", ImageWidth, 1920_u32.into())]
+    #[test_case("meta.mp4", ColorPrimaries, 1_u16.into())]
+    #[test_case("meta.mp4", TransferCharacteristics, 1_u16.into())]
     #[test_case("meta.mp4", ImageHeight, 1080_u32.into())]
     #[test_case("meta.mp4", DurationMs, 1063_u64.into())]
     #[test_case("meta.mp4", GpsIso6709, "+27.2939+112.6932/".into())]
+    #[test_case("meta.mp4", Timescale, 10000_u32.into())]
     #[test_case("meta.mp4", CreateDate, DateTime::parse_from_str("2024-02-03T07:05:38Z", "%+").unwrap().into())]
+    #[test_case("mdir.m4a", Title, "Title".into())]
+    #[test_case("mdir.m4a", Artist, "Artist".into())]
+    #[test_case("mdir.m4a", Album, "Album".into())]
+    #[test_case("mdir.m4a", Software, "Lavf60.16.100".into())]
+    #[test_case("mdir.m4a", DurationMs, 3000_u64.into())]
     fn parse_track_info(path: &str, tag: TrackInfoTag, v: EntryValue) {
         let mut parser = parser();
 
@@ -601,4 +1389,1017 @@ mod tests {
         let info: TrackInfo = parser.parse(mf).unwrap();
         assert_eq!(info.get(tag).unwrap(), &v);
     }
+
+    #[test_case("mkv_640x360.mkv", &[("COMPATIBLE_BRANDS", "mp42mp41isomavc1"), ("ENCODER", "Lavf57.83.100"), ("MAJOR_BRAND", "mp42"), ("MINOR_VERSION", "0")])]
+    #[test_case("webm_480.webm", &[("ENCODER", "Lavf57.83.100")])]
+    #[test_case("meta.mp4", &[])]
+    fn parse_matroska_tags(path: &str, tags: &[(&str, &str)]) {
+        let mut parser = parser();
+
+        let ms = MediaSource::file(open_sample(path).unwrap()).unwrap();
+        let info: TrackInfo = parser.parse(ms).unwrap();
+        let tags: std::collections::BTreeMap<String, String> = tags
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        assert_eq!(info.tags(), &tags);
+    }
+
+    #[test_case("exif.jpg")]
+    #[test_case("exif.heic")]
+    #[test_case("meta.mov")]
+    #[test_case("webm_480.webm")]
+    fn parse_cursor(path: &str) {
+        let mut parser = parser();
+
+        let ms: MediaSource<Cursor<Vec<u8>>, Seekable> =
+            MediaSource::cursor(read_sample(path).unwrap()).unwrap();
+        let expect = MediaSource::file(open_sample(path).unwrap()).unwrap();
+        assert_eq!(ms.media_type(), expect.media_type());
+
+        if ms.has_exif() {
+            let exif: crate::Exif = parser.parse::<_, _, ExifIter>(ms).unwrap().into();
+            let expect: crate::Exif = parser.parse::<_, _, ExifIter>(expect).unwrap().into();
+            assert_eq!(exif, expect);
+        } else {
+            let info: TrackInfo = parser.parse(ms).unwrap();
+            let expect: TrackInfo = parser.parse(expect).unwrap();
+            assert_eq!(format!("{info:?}"), format!("{expect:?}"));
+        }
+    }
+
+    // Estimated by the last CuePoint, the Cues of mka.mka is empty
+    #[test_case("mkv_640x360.mkv", 8342)]
+    #[test_case("webm_480.webm", 29870)]
+    #[test_case("mka.mka", 0)]
+    fn parse_webm_without_duration(path: &str, expect: u64) {
+        let mut parser = parser();
+
+        // Hide the Duration element by changing its ID to an unknown one
+        let mut data = read_sample(path).unwrap();
+        let pos = data.windows(2).position(|x| x == [0x44, 0x89]).unwrap();
+        data[pos + 1] = 0x88;
+
+        let info: TrackInfo = parser
+            .parse(MediaSource::seekable(std::io::Cursor::new(&data)).unwrap())
+            .unwrap();
+        assert_eq!(info.get(DurationMs), Some(&expect.into()));
+
+        let info: TrackInfo = parser
+            .parse(MediaSource::unseekable(std::io::Cursor::new(&data)).unwrap())
+            .unwrap();
+        assert_eq!(info.get(DurationMs), Some(&expect.into()));
+    }
+
+    #[test_case("meta.mov", Some(crate::DateSource::Mvhd))]
+    #[test_case("meta.mp4", Some(crate::DateSource::Mvhd))]
+    #[test_case("mkv_640x360.mkv", Some(crate::DateSource::EbmlDate))]
+    #[test_case("webm_480.webm", Some(crate::DateSource::EbmlDate))]
+    fn create_date_source(path: &str, expect: Option<crate::DateSource>) {
+        let mut parser = parser();
+
+        let info: TrackInfo = parser
+            .parse(MediaSource::file_path(Path::new("testdata").join(path)).unwrap())
+            .unwrap();
+        assert_eq!(info.create_date_source(), expect);
+        assert_eq!(
+            info.get(TrackInfoTag::CreateDate).is_some(),
+            expect.is_some()
+        );
+    }
+
+    #[test_case("exif.jpg")]
+    #[test_case("exif.heic")]
+    #[test_case("meta.mov")]
+    #[test_case("webm_480.webm")]
+    fn parse_dyn_read(path: &str) {
+        let mut parser = parser();
+
+        let expect = MediaSource::file_path(Path::new("testdata").join(path)).unwrap();
+        let mut file = open_sample(path).unwrap();
+        let reader: &mut dyn Read = &mut file;
+        let ms = MediaSource::unseekable(reader).unwrap();
+        assert_eq!(ms.media_type(), expect.media_type());
+        let boxed: Box<dyn Read> = Box::new(open_sample(path).unwrap());
+        let boxed = MediaSource::unseekable(boxed).unwrap();
+
+        if ms.has_exif() {
+            let expect: crate::Exif = parser.parse::<_, _, ExifIter>(expect).unwrap().into();
+            let exif: crate::Exif = parser.parse::<_, _, ExifIter>(ms).unwrap().into();
+            assert_eq!(exif, expect);
+            let exif: crate::Exif = parser.parse::<_, _, ExifIter>(boxed).unwrap().into();
+            assert_eq!(exif, expect);
+        } else {
+            let expect: TrackInfo = parser.parse(expect).unwrap();
+            let info: TrackInfo = parser.parse(ms).unwrap();
+            assert_eq!(format!("{info:?}"), format!("{expect:?}"));
+            let info: TrackInfo = parser.parse(boxed).unwrap();
+            assert_eq!(format!("{info:?}"), format!("{expect:?}"));
+        }
+    }
+
+    #[cfg(feature = "flate2")]
+    #[test_case("exif.heic")]
+    #[test_case("exif.jpg")]
+    #[test_case("meta.mov")]
+    fn parse_compressed(path: &str) {
+        use std::io::Write;
+
+        use flate2::{
+            write::{DeflateEncoder, GzEncoder},
+            Compression,
+        };
+
+        let mut parser = parser();
+
+        let data = read_sample(path).unwrap();
+        let mut gz = GzEncoder::new(Vec::new(), Compression::default());
+        gz.write_all(&data).unwrap();
+        let gz = gz.finish().unwrap();
+        let mut deflate = DeflateEncoder::new(Vec::new(), Compression::default());
+        deflate.write_all(&data).unwrap();
+        let deflate = deflate.finish().unwrap();
+
+        let expect = MediaSource::file_path(Path::new("testdata").join(path)).unwrap();
+        let ms = MediaSource::gzip(gz.as_slice()).unwrap();
+        assert_eq!(ms.media_type(), expect.media_type());
+        if ms.has_exif() {
+            let expect: crate::Exif = parser.parse::<_, _, ExifIter>(expect).unwrap().into();
+            let exif: crate::Exif = parser.parse::<_, _, ExifIter>(ms).unwrap().into();
+            assert_eq!(exif, expect);
+            let ms = MediaSource::deflate(deflate.as_slice()).unwrap();
+            let exif: crate::Exif = parser.parse::<_, _, ExifIter>(ms).unwrap().into();
+            assert_eq!(exif, expect);
+        } else {
+            let expect: TrackInfo = parser.parse(expect).unwrap();
+            let info: TrackInfo = parser.parse(ms).unwrap();
+            assert_eq!(format!("{info:?}"), format!("{expect:?}"));
+            let ms = MediaSource::deflate(deflate.as_slice()).unwrap();
+            let info: TrackInfo = parser.parse(ms).unwrap();
+            assert_eq!(format!("{info:?}"), format!("{expect:?}"));
+        }
+    }
+
+    #[cfg(feature = "mmap")]
+    #[test_case("exif.heic")]
+    #[test_case("meta.mov")]
+    #[test_case("webm_480.webm")]
+    fn parse_mmap(path: &str) {
+        let mut parser = parser();
+
+        let path = Path::new("testdata").join(path);
+        let ms = MediaSource::mmap(&path).unwrap();
+        let expect = MediaSource::file_path(&path).unwrap();
+        assert_eq!(ms.media_type(), expect.media_type());
+        if ms.has_exif() {
+            let exif: crate::Exif = parser.parse::<_, _, ExifIter>(ms).unwrap().into();
+            let expect: crate::Exif = parser.parse::<_, _, ExifIter>(expect).unwrap().into();
+            assert_eq!(exif, expect);
+        } else {
+            let info: TrackInfo = parser.parse(ms).unwrap();
+            let expect: TrackInfo = parser.parse(expect).unwrap();
+            assert_eq!(format!("{info:?}"), format!("{expect:?}"));
+        }
+    }
+
+    /// A `File` wrapper which counts the read calls.
+    struct CountingReader {
+        file: File,
+        reads: usize,
+    }
+
+    impl Read for CountingReader {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            self.reads += 1;
+            self.file.read(buf)
+        }
+    }
+
+    impl Seek for CountingReader {
+        fn seek(&mut self, pos: io::SeekFrom) -> io::Result<u64> {
+            self.file.seek(pos)
+        }
+    }
+
+    /// A reader which counts the bytes read.
+    struct ByteCountingReader<R> {
+        inner: R,
+        bytes: usize,
+    }
+
+    impl<R: Read> Read for ByteCountingReader<R> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let n = self.inner.read(buf)?;
+            self.bytes += n;
+            Ok(n)
+        }
+    }
+
+    #[test_case("meta.mp4")]
+    #[test_case("meta.mov")]
+    #[test_case("3gp_640x360.3gp")]
+    fn parse_unseekable_front_moov(path: &str) {
+        let mut parser = parser();
+
+        // Move `moov` in front of `mdat`, like a streaming-optimized file
+        let buf = read_sample(path).unwrap();
+        let mut boxes = Vec::new();
+        let mut remain = &buf[..];
+        while !remain.is_empty() {
+            let (rest, b) = crate::bbox::BoxHolder::parse(remain).unwrap();
+            boxes.push(b);
+            remain = rest;
+        }
+        let mut data = Vec::new();
+        for box_type in ["ftyp", "wide", "free", "moov"] {
+            if let Some(b) = boxes.iter().find(|b| b.box_type() == box_type) {
+                data.extend_from_slice(b.data);
+            }
+        }
+        let moov_end = data.len();
+        let mdat = boxes.iter().find(|b| b.box_type() == "mdat").unwrap();
+        data.extend_from_slice(mdat.data);
+        assert!(data.len() > moov_end + MAX_GROW_SIZE);
+
+        let mut reader = ByteCountingReader {
+            inner: Cursor::new(&data),
+            bytes: 0,
+        };
+        let info: TrackInfo = parser
+            .parse(MediaSource::unseekable(&mut reader).unwrap())
+            .unwrap();
+        let expect: TrackInfo = parser
+            .parse(MediaSource::file_path(Path::new("testdata").join(path)).unwrap())
+            .unwrap();
+        assert_eq!(format!("{info:?}"), format!("{expect:?}"));
+
+        // Stop reading once `moov` has been captured
+        assert!(
+            reader.bytes <= moov_end + MIN_GROW_SIZE,
+            "{} bytes read, moov ends at {moov_end}",
+            reader.bytes
+        );
+    }
+
+    #[test_case("exif.jpg")]
+    #[test_case("exif.heic")]
+    #[test_case("meta.mov")]
+    fn parse_buffered(path: &str) {
+        let mut parser = parser();
+
+        let mut parse = |ms: MediaSource<&mut CountingReader>| {
+            if ms.has_exif() {
+                let iter: ExifIter = parser.parse(ms).unwrap();
+                (Some(crate::Exif::from(iter)), None)
+            } else {
+                let info: TrackInfo = parser.parse(ms).unwrap();
+                (None, Some(format!("{info:?}")))
+            }
+        };
+
+        let mut reader = CountingReader {
+            file: open_sample(path).unwrap(),
+            reads: 0,
+        };
+        let expect = parse(MediaSource::seekable(&mut reader).unwrap());
+        let unbuffered_reads = reader.reads;
+
+        let mut reader = CountingReader {
+            file: open_sample(path).unwrap(),
+            reads: 0,
+        };
+        let res = parse(MediaSource::buffered(&mut reader, 64 * 1024).unwrap());
+        let buffered_reads = reader.reads;
+
+        tracing::info!(path, unbuffered_reads, buffered_reads);
+        assert_eq!(res, expect);
+        assert!(buffered_reads <= unbuffered_reads);
+        if path.ends_with(".jpg") {
+            assert!(buffered_reads < unbuffered_reads);
+        }
+    }
+
+    #[test]
+    fn parse_snapshot() {
+        let mut parser = parser();
+
+        let ms = MediaSource::file(open_sample("exif-one-entry.heic").unwrap()).unwrap();
+        let iter: ExifIter = parser.parse(ms).unwrap();
+        assert_exif_snapshot(&iter.into(), &[("0.Orientation", "1")]);
+
+        let ms = MediaSource::file(open_sample("mkv_640x360.mkv").unwrap()).unwrap();
+        let info: TrackInfo = parser.parse(ms).unwrap();
+        assert_track_snapshot(
+            &info,
+            &[
+                ("CreateDate", "2008-08-08T08:08:08+00:00"),
+                ("DurationMs", "13346"),
+                ("ImageHeight", "360"),
+                ("ImageWidth", "640"),
+            ],
+        );
+    }
+
+    #[test]
+    fn parse_jp2() {
+        let mut parser = parser();
+
+        let (jp2, _) = crate::jp2::tests::build_jp2(false);
+        let ms = MediaSource::seekable(std::io::Cursor::new(jp2)).unwrap();
+        assert_eq!(ms.media_type(), MediaType::Jp2);
+        let iter: ExifIter = parser.parse(ms).unwrap();
+        let exif = crate::Exif::from(iter);
+
+        let ms = MediaSource::seekable(open_sample("exif.jpg").unwrap()).unwrap();
+        let iter: ExifIter = parser.parse(ms).unwrap();
+        assert_eq!(exif, crate::Exif::from(iter));
+    }
+
+    #[test_case(false, 100)]
+    #[test_case(true, 100)]
+    #[test_case(true, 1024 * 1024)]
+    fn parse_png(after_idat: bool, idat_size: usize) {
+        let mut parser = parser();
+
+        let ms = MediaSource::seekable(open_sample("exif.jpg").unwrap()).unwrap();
+        let iter: ExifIter = parser.parse(ms).unwrap();
+        let expect = crate::Exif::from(iter);
+
+        let (png, _) = crate::png::tests::build_png(after_idat, idat_size);
+        let ms = MediaSource::seekable(std::io::Cursor::new(&png)).unwrap();
+        assert_eq!(ms.media_type(), MediaType::Png);
+        assert!(ms.has_exif());
+        let iter: ExifIter = parser.parse(ms).unwrap();
+        assert_eq!(crate::Exif::from(iter), expect);
+
+        let ms = MediaSource::unseekable(png.as_slice()).unwrap();
+        let iter: ExifIter = parser.parse(ms).unwrap();
+        assert_eq!(crate::Exif::from(iter), expect);
+
+        // Without the eXIf chunk
+        let mut png = png;
+        let pos = png.windows(4).position(|x| x == b"eXIf").unwrap();
+        png[pos..pos + 4].copy_from_slice(b"tEXt");
+        let ms = MediaSource::seekable(std::io::Cursor::new(&png)).unwrap();
+        let err = parser.parse::<_, _, ExifIter>(ms).unwrap_err();
+        assert_eq!(err.to_string(), "parse failed: Exif not found");
+    }
+
+    // The Exif data of exif.avif is copied from exif.jpg
+    #[test_case(b"avif"; "major brand")]
+    #[test_case(b"mif1"; "compatible brand")]
+    fn parse_avif(major_brand: &[u8]) {
+        let mut parser = parser();
+
+        let ms = MediaSource::seekable(open_sample("exif.jpg").unwrap()).unwrap();
+        let expect: crate::Exif = parser.parse::<_, _, ExifIter>(ms).unwrap().into();
+
+        let mut buf = read_sample("exif.avif").unwrap();
+        assert_eq!(&buf[8..12], b"avif");
+        buf[8..12].copy_from_slice(major_brand);
+
+        let ms = MediaSource::seekable(std::io::Cursor::new(&buf)).unwrap();
+        assert_eq!(ms.media_type(), MediaType::Avif);
+        assert!(ms.has_exif());
+        let exif: crate::Exif = parser.parse::<_, _, ExifIter>(ms).unwrap().into();
+        assert_eq!(
+            exif.get(ExifTag::Make).and_then(|x| x.as_str()),
+            Some("vivo")
+        );
+        assert_eq!(exif, expect);
+
+        let ms = MediaSource::unseekable(buf.as_slice()).unwrap();
+        let exif: crate::Exif = parser.parse::<_, _, ExifIter>(ms).unwrap().into();
+        assert_eq!(exif, expect);
+    }
+
+    #[test_case(100)]
+    #[test_case(1024 * 1024)]
+    fn parse_webp(vp8_size: usize) {
+        let mut parser = parser();
+
+        let ms = MediaSource::seekable(open_sample("exif.jpg").unwrap()).unwrap();
+        let iter: ExifIter = parser.parse(ms).unwrap();
+        let mut expect = crate::Exif::from(iter);
+        expect.container_dimensions = Some((640, 480));
+
+        let (webp, _) = crate::webp::tests::build_webp(vp8_size);
+        let ms = MediaSource::seekable(std::io::Cursor::new(&webp)).unwrap();
+        assert_eq!(ms.media_type(), MediaType::WebP);
+        assert!(ms.has_exif());
+        let iter: ExifIter = parser.parse(ms).unwrap();
+        assert_eq!(iter.container_dimensions(), Some((640, 480)));
+        assert_eq!(crate::Exif::from(iter), expect);
+
+        let ms = MediaSource::unseekable(webp.as_slice()).unwrap();
+        let iter: ExifIter = parser.parse(ms).unwrap();
+        assert_eq!(crate::Exif::from(iter), expect);
+
+        // Without the Exif flag
+        let mut webp = webp;
+        webp[20] = 0;
+        let ms = MediaSource::seekable(std::io::Cursor::new(&webp)).unwrap();
+        let err = parser.parse::<_, _, ExifIter>(ms).unwrap_err();
+        assert_eq!(err.to_string(), "parse failed: Exif not found");
+    }
+
+    #[test]
+    fn parse_cr3() {
+        let mut parser = parser();
+
+        let cr3 = crate::cr3::tests::build_cr3(1024 * 1024);
+        let ms = MediaSource::seekable(std::io::Cursor::new(&cr3)).unwrap();
+        assert_eq!(ms.media_type(), MediaType::Cr3);
+        assert!(ms.has_exif());
+        let iter: ExifIter = parser.parse(ms).unwrap();
+        assert_eq!(
+            iter.duplicate_strategy(),
+            crate::DuplicateStrategy::FirstWins
+        );
+        let gps = iter.parse_gps_info().unwrap().unwrap();
+        assert_eq!(gps.format_iso6709(), "+22.53113+114.02148/");
+        let exif = crate::Exif::from(iter);
+        assert_eq!(
+            exif.get(ExifTag::Make).and_then(|x| x.as_str()),
+            Some("Canon")
+        );
+        assert_eq!(
+            exif.get(ExifTag::Model).and_then(|x| x.as_str()),
+            Some("Canon EOS R5")
+        );
+        assert_eq!(
+            exif.get(ExifTag::ExposureTime)
+                .and_then(|x| x.as_urational()),
+            Some((1, 200).into())
+        );
+        assert_eq!(
+            exif.get(ExifTag::ISOSpeedRatings).and_then(|x| x.as_u16()),
+            Some(400)
+        );
+        assert_eq!(exif.gps(), Some(&gps));
+
+        let ms = MediaSource::unseekable(cr3.as_slice()).unwrap();
+        let iter: ExifIter = parser.parse(ms).unwrap();
+        assert_eq!(crate::Exif::from(iter), exif);
+    }
+
+    #[test_case("exif.heic")]
+    #[test_case("tif.tif")]
+    #[test_case("meta.mov")]
+    #[test_case("meta.mp4")]
+    fn parse_at_offset(path: &str) {
+        let mut parser = parser();
+
+        let sample = read_sample(path).unwrap();
+        let mut data = vec![0xff; 1000];
+        data.extend_from_slice(&sample);
+        data.extend_from_slice(&[0; 1000]);
+
+        let ms = MediaSource::at_offset(std::io::Cursor::new(data), 1000).unwrap();
+        let expect = MediaSource::seekable(std::io::Cursor::new(sample)).unwrap();
+        assert_eq!(ms.media_type(), expect.media_type());
+        if ms.has_exif() {
+            let exif: crate::Exif = parser.parse::<_, _, ExifIter>(ms).unwrap().into();
+            let expect: crate::Exif = parser.parse::<_, _, ExifIter>(expect).unwrap().into();
+            assert_eq!(exif, expect);
+        } else {
+            let info: TrackInfo = parser.parse(ms).unwrap();
+            let expect: TrackInfo = parser.parse(expect).unwrap();
+            assert_eq!(format!("{info:?}"), format!("{expect:?}"));
+        }
+    }
+
+    /// Builds a HEIC image ("exif.heic") with the clip of
+    /// "embedded-in-heic.mov" appended, which is located inside the `mdat`
+    /// box of the image if `in_mdat` is true. Returns the image and the clip.
+    fn build_live_photo(in_mdat: bool) -> (Vec<u8>, Vec<u8>) {
+        // Keep only the `wide` box, an empty `mdat` and the `moov` box of the
+        // clip, the media data isn't needed for parsing the track info.
+        let mov = read_sample("embedded-in-heic.mov").unwrap();
+        let mut pos = 0;
+        let mut clip = Vec::new();
+        while pos < mov.len() {
+            let size = u32::from_be_bytes(mov[pos..pos + 4].try_into().unwrap()) as usize;
+            match &mov[pos + 4..pos + 8] {
+                b"mdat" => clip.extend_from_slice(b"\0\0\0\x08mdat"),
+                _ => clip.extend_from_slice(&mov[pos..pos + size]),
+            }
+            pos += size;
+        }
+
+        let mut heic = read_sample("exif.heic").unwrap();
+        if in_mdat {
+            // Grow the (last) `mdat` box of the image to contain the clip,
+            // like a Motion Photo does. It has a 64-bit largesize.
+            let size_range = 3622..3630;
+            let size = u64::from_be_bytes(heic[size_range.clone()].try_into().unwrap());
+            heic[size_range].copy_from_slice(&(size + clip.len() as u64).to_be_bytes());
+        }
+        heic.extend_from_slice(&clip);
+        (heic, clip)
+    }
+
+    #[test_case(false)]
+    #[test_case(true)]
+    fn parse_embedded_track(in_mdat: bool) {
+        let mut parser = parser();
+
+        let (heic, clip) = build_live_photo(in_mdat);
+        let ms = MediaSource::seekable(std::io::Cursor::new(heic)).unwrap();
+        assert_eq!(ms.media_type(), MediaType::Heic);
+        let info = parser.parse_embedded_track(ms).unwrap().unwrap();
+
+        let expect: TrackInfo = parser
+            .parse(MediaSource::seekable(std::io::Cursor::new(clip)).unwrap())
+            .unwrap();
+        assert_eq!(format!("{info:?}"), format!("{expect:?}"));
+        assert_eq!(info.get(TrackInfoTag::Model), Some(&"iPhone 15 Pro".into()));
+
+        // No embedded clip
+        let ms = MediaSource::file(open_sample("exif.heic").unwrap()).unwrap();
+        assert!(parser.parse_embedded_track(ms).unwrap().is_none());
+
+        // Not a HEIF/HEIC image
+        let ms = MediaSource::file(open_sample("meta.mov").unwrap()).unwrap();
+        parser.parse_embedded_track(ms).unwrap_err();
+    }
+
+    /// Builds a TIFF in the layout of a Sony ARW 2.3 file, since there is no
+    /// real ARW in testdata: IFD0 holds Make, Model, a preview JPEG of
+    /// `preview_size` bytes & the `SubIFDs` link to the raw image IFD, IFD1
+    /// holds the thumbnail, and the capture settings & the maker note are in
+    /// the Exif sub-IFD after the preview.
+    fn build_arw(preview_size: u32) -> Vec<u8> {
+        let mut preview = vec![0x55; preview_size as usize];
+        preview[..2].copy_from_slice(b"\xff\xd8");
+        let thumbnail = b"\xff\xd8\x55\x55\xff\xd9";
+
+        // Sony maker note: a header followed by an IFD, LensType is inline
+        let mut maker_note = b"SONY DSC \0\0\0".to_vec();
+        maker_note.extend_from_slice(&1u16.to_le_bytes());
+        maker_note.extend_from_slice(&0xb027u16.to_le_bytes());
+        maker_note.extend_from_slice(&4u16.to_le_bytes());
+        maker_note.extend_from_slice(&1u32.to_le_bytes());
+        maker_note.extend_from_slice(&32850u32.to_le_bytes());
+        maker_note.extend_from_slice(&0u32.to_le_bytes());
+
+        let mut tiff = TiffBuilder::new(Endianness::Little);
+        let ifd1 = tiff.ifd();
+        let raw = tiff.ifd();
+        let preview = tiff.blob(&preview);
+        let exif = tiff.ifd();
+        let thumbnail_data = tiff.blob(thumbnail);
+        let raw_data = tiff.blob(&[0; 64]);
+        tiff.long(0, 0x00fe, &[1])
+            .entry(0, 0x014a, 4, 1, TiffValue::Ifds(vec![raw]))
+            .short(0, 0x0103, &[6])
+            .text(0, 0x010f, "SONY")
+            .text(0, 0x0110, "ILCE-7M3")
+            .short(0, 0x0112, &[1])
+            .entry(0, 0x0201, 4, 1, TiffValue::Blob(preview))
+            .long(0, 0x0202, &[preview_size])
+            .sub_ifd(0, 0x8769, exif)
+            .link(0, ifd1)
+            .long(ifd1, 0x00fe, &[1])
+            .short(ifd1, 0x0103, &[6])
+            .entry(ifd1, 0x0201, 4, 1, TiffValue::Blob(thumbnail_data))
+            .long(ifd1, 0x0202, &[thumbnail.len() as u32])
+            .long(raw, 0x00fe, &[0])
+            .short(raw, 0x0100, &[6048])
+            .short(raw, 0x0101, &[4024])
+            .short(raw, 0x0102, &[14])
+            .short(raw, 0x0103, &[32767])
+            .entry(raw, 0x0111, 4, 1, TiffValue::Blob(raw_data))
+            .long(raw, 0x0117, &[64])
+            .rational(exif, 0x829a, &[(1, 250)])
+            .rational(exif, 0x829d, &[(28, 10)])
+            .short(exif, 0x8827, &[100])
+            .text(exif, 0x9003, "2024:05:01 10:20:30")
+            .text(exif, 0x9011, "+09:00")
+            .undefined(exif, 0x927c, &maker_note)
+            .text(exif, 0xa434, "FE 24-70mm F2.8 GM");
+        tiff.build()
+    }
+
+    #[test_case(1000)]
+    #[test_case(1024 * 1024)]
+    fn parse_arw(preview_size: u32) {
+        let mut parser = parser();
+
+        let arw = build_arw(preview_size);
+        assert!(TiffHeader::parse(&arw).is_ok());
+        let ms = MediaSource::seekable(std::io::Cursor::new(&arw)).unwrap();
+        assert_eq!(ms.media_type(), MediaType::Tiff);
+
+        let iter: ExifIter = parser.parse(ms).unwrap();
+        assert_eq!(iter.lens_type(), Some(32850));
+        assert_eq!(
+            iter.extract_thumbnail().as_deref(),
+            Some(&b"\xff\xd8\x55\x55\xff\xd9"[..])
+        );
+        let raw: Vec<_> = iter
+            .clone_and_rewind()
+            .filter(|x| x.ifd_kind() == crate::IfdKind::SubIfd)
+            .filter_map(|x| Some((x.tag()?, x.get_value()?.as_u16()?)))
+            .collect();
+        assert_eq!(
+            raw,
+            [
+                (ExifTag::ImageWidth, 6048),
+                (ExifTag::ImageHeight, 4024),
+                (ExifTag::BitsPerSample, 14),
+                (ExifTag::Compression, 32767),
+            ]
+        );
+
+        let exif: crate::Exif = iter.into();
+        assert_eq!(
+            exif.get(ExifTag::Make).and_then(|x| x.as_str()),
+            Some("SONY")
+        );
+        assert_eq!(
+            exif.get(ExifTag::Model).and_then(|x| x.as_str()),
+            Some("ILCE-7M3")
+        );
+        // Values of the preview in IFD0 aren't overridden by the raw image
+        assert_eq!(
+            exif.get(ExifTag::ThumbnailLength).and_then(|x| x.as_u32()),
+            Some(preview_size)
+        );
+        assert_eq!(
+            exif.get(ExifTag::Compression).and_then(|x| x.as_u16()),
+            Some(6)
+        );
+        assert_eq!(
+            exif.get(ExifTag::FNumber).and_then(|x| x.as_urational()),
+            Some((28, 10).into())
+        );
+        assert_eq!(
+            exif.get(ExifTag::ISOSpeedRatings).and_then(|x| x.as_u16()),
+            Some(100)
+        );
+        assert_eq!(
+            exif.get(ExifTag::LensModel).and_then(|x| x.as_str()),
+            Some("FE 24-70mm F2.8 GM")
+        );
+        assert_eq!(
+            exif.best_datetime().unwrap().to_rfc3339(),
+            "2024-05-01T10:20:30+09:00"
+        );
+
+        let ms = MediaSource::unseekable(arw.as_slice()).unwrap();
+        let iter: ExifIter = parser.parse(ms).unwrap();
+        assert_eq!(crate::Exif::from(iter), exif);
+    }
+
+    #[test_case(false)]
+    #[test_case(true)]
+    fn parse_all(in_mdat: bool) {
+        let mut parser = parser();
+
+        let (heic, clip) = build_live_photo(in_mdat);
+        let ms = MediaSource::seekable(std::io::Cursor::new(heic)).unwrap();
+        let meta = parser.parse_all(ms).unwrap();
+
+        let exif: crate::Exif = meta.exif.unwrap().into();
+        let expect: crate::Exif = parser
+            .parse::<_, _, ExifIter>(MediaSource::file(open_sample("exif.heic").unwrap()).unwrap())
+            .unwrap()
+            .into();
+        assert_eq!(exif, expect);
+
+        let expect: TrackInfo = parser
+            .parse(MediaSource::seekable(std::io::Cursor::new(clip)).unwrap())
+            .unwrap();
+        assert_eq!(format!("{:?}", meta.track.unwrap()), format!("{expect:?}"));
+
+        // Images without an embedded clip
+        for path in ["exif.heic", "exif.jpg"] {
+            let ms = MediaSource::file(open_sample(path).unwrap()).unwrap();
+            let meta = parser.parse_all(ms).unwrap();
+            assert!(meta.exif.is_some());
+            assert!(meta.track.is_none());
+        }
+
+        // Image without Exif
+        let ms = MediaSource::file(open_sample("no-exif.jpg").unwrap()).unwrap();
+        let meta = parser.parse_all(ms).unwrap();
+        assert!(meta.exif.is_none());
+        assert!(meta.track.is_none());
+
+        let ms = MediaSource::file(open_sample("meta.mp4").unwrap()).unwrap();
+        let meta = parser.parse_all(ms).unwrap();
+        assert!(meta.exif.is_none());
+        assert!(meta.track.is_some());
+    }
+
+    #[test_case("exif.jpg")]
+    #[test_case("exif.heic")]
+    #[test_case("tif.tif")]
+    #[test_case("meta.mov")]
+    #[test_case("meta.mp4")]
+    fn parse_ref(path: &str) {
+        let mut parser = parser();
+
+        let sample = read_sample(path).unwrap();
+        let mut data = vec![0xff; 1000];
+        data.extend_from_slice(&sample);
+
+        let expect = MediaSource::seekable(std::io::Cursor::new(sample)).unwrap();
+        let mut ms = MediaSource::at_offset(std::io::Cursor::new(data), 1000).unwrap();
+        if ms.has_exif() {
+            let expect: crate::Exif = parser.parse::<_, _, ExifIter>(expect).unwrap().into();
+            for _ in 0..3 {
+                let exif: crate::Exif = parser.parse_ref::<_, ExifIter>(&mut ms).unwrap().into();
+                assert_eq!(exif, expect);
+            }
+        } else {
+            let expect: TrackInfo = parser.parse(expect).unwrap();
+            for _ in 0..3 {
+                let info: TrackInfo = parser.parse_ref(&mut ms).unwrap();
+                assert_eq!(format!("{info:?}"), format!("{expect:?}"));
+            }
+        }
+
+        // The source can still be consumed after being borrowed
+        if ms.has_exif() {
+            parser.parse::<_, _, ExifIter>(ms).unwrap();
+        } else {
+            parser.parse::<_, _, TrackInfo>(ms).unwrap();
+        }
+    }
+
+    #[test_case("meta.mov", &["mebx", "mebx"])]
+    #[test_case("meta.mp4", &[])]
+    #[test_case("mkv_640x360.mkv", &[])]
+    fn parse_timed_metadata_tracks(path: &str, expect: &[&str]) {
+        let mut parser = parser();
+
+        let mf = MediaSource::file(open_sample(path).unwrap()).unwrap();
+        let info: TrackInfo = parser.parse(mf).unwrap();
+        assert_eq!(info.timed_metadata_tracks(), expect);
+        assert!(!info.gps_track_present());
+    }
+
+    #[test_case("meta.mov")]
+    #[test_case("meta.mp4")]
+    #[test_case("3gp_640x360.3gp")]
+    #[test_case("mkv_640x360.mkv")]
+    #[test_case("webm_480.webm")]
+    fn parse_track_bytes(path: &str) {
+        let mut parser = parser();
+
+        let data = read_sample(path).unwrap();
+        let info = parser.parse_track_bytes(&data).unwrap();
+        let ms = MediaSource::file(open_sample(path).unwrap()).unwrap();
+        let expect: TrackInfo = parser.parse(ms).unwrap();
+        assert_eq!(format!("{info:?}"), format!("{expect:?}"));
+
+        parser
+            .parse_track_bytes(&read_sample("exif.jpg").unwrap())
+            .unwrap_err();
+        parser.parse_track_bytes(&[]).unwrap_err();
+    }
+
+    #[test_case([0x00, 0x02])]
+    #[test_case([0x00, 0x10])]
+    fn search_exif_on_corrupt_jpeg(size: [u8; 2]) {
+        let _ = tracing_subscriber::fmt().with_test_writer().try_init();
+
+        let sample = read_sample("exif.jpg").unwrap();
+        // Corrupt the size of the APP1 segment
+        let mut buf = sample.clone();
+        buf[4..6].copy_from_slice(&size);
+
+        let mut parser = parser();
+        let ms = MediaSource::seekable(std::io::Cursor::new(buf.clone())).unwrap();
+        parser.parse::<_, _, ExifIter>(ms).unwrap_err();
+
+        let mut recovering = MediaParser::new().search_exif_on_corrupt_jpeg(true);
+        let ms = MediaSource::seekable(std::io::Cursor::new(buf)).unwrap();
+        let exif: crate::Exif = recovering.parse::<_, _, ExifIter>(ms).unwrap().into();
+
+        let ms = MediaSource::seekable(std::io::Cursor::new(sample)).unwrap();
+        let expect: crate::Exif = parser.parse::<_, _, ExifIter>(ms).unwrap().into();
+        assert_eq!(exif, expect);
+
+        // Not found at all
+        let ms = MediaSource::file(open_sample("no-exif.jpg").unwrap()).unwrap();
+        recovering.parse::<_, _, ExifIter>(ms).unwrap_err();
+    }
+
+    #[test_case(true)]
+    #[test_case(false)]
+    fn parse_exif_from_jpeg_segment(with_header: bool) {
+        let _ = tracing_subscriber::fmt().with_test_writer().try_init();
+
+        let buf = read_sample("exif.jpg").unwrap();
+        let (_, data) = crate::jpeg::extract_exif_data(&buf).unwrap();
+        let data = data.unwrap();
+
+        let mut payload = Vec::new();
+        if with_header {
+            payload.extend_from_slice(b"Exif\0\0");
+        }
+        payload.extend_from_slice(data);
+
+        let mut parser = parser();
+        let iter = parser.parse_exif_from_jpeg_segment(&payload).unwrap();
+        let exif: crate::Exif = iter.into();
+        assert_eq!(exif.get(ExifTag::Make).unwrap().as_str().unwrap(), "vivo");
+
+        let expect: crate::Exif = parser
+            .parse::<_, _, ExifIter>(MediaSource::file(open_sample("exif.jpg").unwrap()).unwrap())
+            .unwrap()
+            .into();
+        assert_eq!(exif, expect);
+
+        parser.parse_exif_from_jpeg_segment(&[]).unwrap_err();
+        parser
+            .parse_exif_from_jpeg_segment(b"Exif\0\0")
+            .unwrap_err();
+    }
+
+    #[test_case(true)]
+    #[test_case(false)]
+    fn parse_heic_xmp(seekable: bool) {
+        let _ = tracing_subscriber::fmt().with_test_writer().try_init();
+
+        let buf = read_sample("exif.heic").unwrap();
+        let f = open_sample("exif.heic").unwrap();
+        let ms = if seekable {
+            parser().parse_xmp(MediaSource::seekable(f).unwrap())
+        } else {
+            parser().parse_xmp(MediaSource::unseekable(f).unwrap())
+        };
+        let xmp = ms.unwrap().unwrap();
+        assert_eq!(xmp.len(), 2541);
+        assert!(xmp.starts_with(b"<?xpacket begin"));
+        assert!(buf.windows(xmp.len()).any(|x| x == xmp));
+    }
+
+    #[test]
+    fn parse_jpeg_xmp() {
+        let _ = tracing_subscriber::fmt().with_test_writer().try_init();
+
+        let packet = b"<x:xmpmeta xmlns:x=\"adobe:ns:meta/\"></x:xmpmeta>";
+        let mut payload = b"http://ns.adobe.com/xap/1.0/\0".to_vec();
+        payload.extend_from_slice(packet);
+
+        // Insert an APP1 XMP segment right after SOI
+        let buf = read_sample("exif.jpg").unwrap();
+        let mut data = buf[..2].to_vec();
+        data.extend_from_slice(&[0xFF, 0xE1]);
+        data.extend_from_slice(&(payload.len() as u16 + 2).to_be_bytes());
+        data.extend_from_slice(&payload);
+        data.extend_from_slice(&buf[2..]);
+
+        let mut parser = parser();
+        let xmp = parser.parse_xmp(MediaSource::cursor(data).unwrap());
+        assert_eq!(xmp.unwrap().unwrap(), packet);
+
+        // The original file has no XMP
+        let ms = MediaSource::cursor(buf).unwrap();
+        assert!(parser.parse_xmp(ms).unwrap().is_none());
+    }
+
+    #[test_case("no-exif.jpg")]
+    #[test_case("exif-one-entry.heic")]
+    fn parse_xmp_none(path: &str) {
+        let _ = tracing_subscriber::fmt().with_test_writer().try_init();
+
+        let ms = MediaSource::file(open_sample(path).unwrap()).unwrap();
+        assert!(parser().parse_xmp(ms).unwrap().is_none());
+    }
+
+    #[test_case("tif.tif")]
+    #[test_case("meta.mov")]
+    fn parse_xmp_unsupported(path: &str) {
+        let _ = tracing_subscriber::fmt().with_test_writer().try_init();
+
+        let ms = MediaSource::file(open_sample(path).unwrap()).unwrap();
+        parser().parse_xmp(ms).unwrap_err();
+    }
+
+    #[test]
+    fn parse_jpeg_iptc() {
+        let _ = tracing_subscriber::fmt().with_test_writer().try_init();
+
+        let mut iim = crate::iptc::tests::make_dataset(2, 25, b"sea");
+        iim.extend(crate::iptc::tests::make_dataset(2, 120, b"A caption"));
+        let payload = crate::iptc::tests::make_app13_payload(&iim);
+
+        // Insert an APP13 segment right after SOI
+        let buf = read_sample("exif.jpg").unwrap();
+        let mut data = buf[..2].to_vec();
+        data.extend_from_slice(&[0xFF, 0xED]);
+        data.extend_from_slice(&(payload.len() as u16 + 2).to_be_bytes());
+        data.extend_from_slice(&payload);
+        data.extend_from_slice(&buf[2..]);
+
+        let mut parser = parser();
+        let iptc = parser
+            .parse_iptc(MediaSource::cursor(data.clone()).unwrap())
+            .unwrap()
+            .unwrap();
+        assert_eq!(iptc.data(), iim);
+        assert_eq!(iptc.keywords(), ["sea"]);
+        assert_eq!(iptc.caption().unwrap(), "A caption");
+        assert!(iptc.object_name().is_none());
+
+        // Exif is not affected
+        let iter: ExifIter = parser.parse(MediaSource::cursor(data).unwrap()).unwrap();
+        let exif: crate::Exif = iter.into();
+        assert_eq!(exif.get(ExifTag::Make).unwrap().as_str().unwrap(), "vivo");
+
+        let ms = MediaSource::cursor(buf).unwrap();
+        assert!(parser.parse_iptc(ms).unwrap().is_none());
+    }
+
+    #[test]
+    fn lens_resolver() {
+        let _ = tracing_subscriber::fmt().with_test_writer().try_init();
+
+        let maker_note = crate::exif::makernote::tests::make_canon_maker_note(254);
+        let tiff = crate::exif::makernote::tests::make_tiff("Canon", &maker_note);
+
+        let mut parser = MediaParser::new();
+        parser.set_lens_resolver(Box::new(|make, lens_type| {
+            (make == "Canon" && lens_type == 254).then(|| "EF 100mm".to_owned())
+        }));
+        let iter: ExifIter = parser
+            .parse(MediaSource::cursor(tiff.clone()).unwrap())
+            .unwrap();
+        assert_eq!(iter.lens_type(), Some(254));
+        assert_eq!(iter.lens_name().unwrap(), "EF 100mm");
+        // Resolver is kept by clones
+        assert_eq!(iter.clone().lens_name().unwrap(), "EF 100mm");
+
+        let iter: ExifIter = MediaParser::new()
+            .parse(MediaSource::cursor(tiff).unwrap())
+            .unwrap();
+        assert_eq!(iter.lens_type(), Some(254));
+        assert!(iter.lens_name().is_none());
+    }
+
+    #[test_case("exif.jpg", Some(556))]
+    #[test_case("no-exif.jpg", Some(3144))]
+    #[test_case("exif.heic", Some(536))]
+    #[test_case("exif-one-entry.heic", None)]
+    fn parse_icc_profile(path: &str, size: Option<usize>) {
+        let _ = tracing_subscriber::fmt().with_test_writer().try_init();
+
+        let ms = MediaSource::file(open_sample(path).unwrap()).unwrap();
+        let icc = parser().parse_icc_profile(ms).unwrap();
+        assert_eq!(icc.as_ref().map(|x| x.len()), size);
+        if let Some(icc) = icc {
+            assert_eq!(&icc[36..40], b"acsp");
+        }
+    }
+
+    /// Builds a JPEG file with the ICC profile chunks `(seq, count, data)`.
+    fn make_jpeg_with_icc(chunks: &[(u8, u8, &[u8])]) -> Vec<u8> {
+        let mut buf = vec![0xFF, 0xD8];
+        for (seq, count, data) in chunks {
+            let mut payload = b"ICC_PROFILE\0".to_vec();
+            payload.extend_from_slice(&[*seq, *count]);
+            payload.extend_from_slice(data);
+            buf.extend_from_slice(&[0xFF, 0xE2]);
+            buf.extend_from_slice(&(payload.len() as u16 + 2).to_be_bytes());
+            buf.extend_from_slice(&payload);
+        }
+        // SOS, followed by some fake image data & EOI
+        buf.extend_from_slice(&[0xFF, 0xDA, 0x00, 0x02]);
+        buf.extend_from_slice(&[0; 256]);
+        buf.extend_from_slice(&[0xFF, 0xD9]);
+        buf
+    }
+
+    #[test]
+    fn parse_multi_chunk_icc_profile() {
+        let _ = tracing_subscriber::fmt().with_test_writer().try_init();
+
+        let (a, b, c) = ([1; 100], [2; 100], [3; 50]);
+        let mut parser = parser();
+
+        // Chunks are reassembled by their sequence numbers
+        let jpeg = make_jpeg_with_icc(&[(2, 3, &b), (1, 3, &a), (3, 3, &c)]);
+        let icc = parser.parse_icc_profile(MediaSource::cursor(jpeg).unwrap());
+        assert_eq!(icc.unwrap().unwrap(), [&a[..], &b, &c].concat());
+
+        let jpeg = make_jpeg_with_icc(&[(1, 3, &a), (3, 3, &c)]);
+        let ms = MediaSource::cursor(jpeg).unwrap();
+        parser.parse_icc_profile(ms).unwrap_err();
+
+        let jpeg = make_jpeg_with_icc(&[(1, 2, &a), (2, 2, &b), (2, 2, &b)]);
+        let ms = MediaSource::cursor(jpeg).unwrap();
+        parser.parse_icc_profile(ms).unwrap_err();
+
+        let jpeg = make_jpeg_with_icc(&[]);
+        let ms = MediaSource::cursor(jpeg).unwrap();
+        assert!(parser.parse_icc_profile(ms).unwrap().is_none());
+
+        let ms = MediaSource::file(open_sample("meta.mov").unwrap()).unwrap();
+        parser.parse_icc_profile(ms).unwrap_err();
+    }
 }
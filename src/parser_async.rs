@@ -15,15 +15,15 @@ use tokio::{
 use crate::{
     buffer::Buffers,
     error::{ParsedError, ParsingError, ParsingErrorState},
-    exif::parse_exif_iter_async,
-    file::Mime,
+    exif::{parse_exif_iter_async, LensResolver},
+    file::{parse_compatible_brands, MediaType, Mime},
     parser::{
         Buf, ParsingState, ShareBuf, INIT_BUF_SIZE, MAX_ALLOC_SIZE, MAX_GROW_SIZE, MIN_GROW_SIZE,
     },
     partial_vec::PartialVec,
     skip::AsyncSkip,
     video::parse_track_info,
-    ExifIter, Seekable, TrackInfo, Unseekable,
+    ExifIter, LensResolverFn, Seekable, TrackInfo, Unseekable,
 };
 
 // Should be enough for parsing header
@@ -66,6 +66,23 @@ impl<R: AsyncRead + Unpin, S: AsyncSkip<R>> AsyncMediaSource<R, S> {
             Mime::Video(_) => false,
         }
     }
+
+    /// Returns the media format detected when this source was built.
+    pub fn media_type(&self) -> MediaType {
+        self.mime.into()
+    }
+
+    /// Returns the compatible brands declared in the `ftyp` box, see
+    /// [`MediaSource::compatible_brands`](crate::MediaSource::compatible_brands).
+    pub fn compatible_brands(&self) -> Vec<String> {
+        parse_compatible_brands(&self.buf)
+    }
+
+    /// Consumes the source and returns `(reader, probe, media_type)`, see
+    /// [`MediaSource::into_parts`](crate::MediaSource::into_parts).
+    pub fn into_parts(self) -> (R, Vec<u8>, MediaType) {
+        (self.reader, self.buf, self.mime.into())
+    }
 }
 
 impl<R: AsyncRead + AsyncSeek + Unpin + Send> AsyncMediaSource<R, Seekable> {
@@ -88,6 +105,25 @@ impl AsyncMediaSource<File, Seekable> {
     pub async fn file_path<P: AsRef<Path>>(path: P) -> crate::Result<Self> {
         Self::build(File::open(path).await?).await
     }
+
+    /// Opens the file at `path` and returns the source together with its
+    /// detected [`MediaType`], see [`MediaSource::open`](crate::MediaSource::open).
+    ///
+    /// ```rust
+    /// use nom_exif::*;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let (ms, media_type) = AsyncMediaSource::open("./testdata/meta.mov").await.unwrap();
+    /// assert_eq!(media_type, MediaType::QuickTime);
+    /// assert!(ms.has_track());
+    /// # }
+    /// ```
+    pub async fn open<P: AsRef<Path>>(path: P) -> crate::Result<(Self, MediaType)> {
+        let ms = Self::file_path(path).await?;
+        let media_type = ms.media_type();
+        Ok((ms, media_type))
+    }
 }
 
 pub(crate) trait AsyncBufParser: Buf + Debug {
@@ -232,8 +268,8 @@ impl<R: AsyncRead + Unpin + Send, S: AsyncSkip<R> + Send> AsyncParseOutput<R, S>
             Mime::Image(_) => return Err("not a track".into()),
             Mime::Video(v) => {
                 parser
-                    .load_and_parse::<R, S, _, _>(&mut ms.reader, |data, _| {
-                        parse_track_info(data, v).map_err(|e| ParsingErrorState::new(e, None))
+                    .load_and_parse::<R, S, _, _>(&mut ms.reader, |data, state| {
+                        parse_track_info(data, v, state)
                     })
                     .await?
             }
@@ -294,6 +330,10 @@ pub struct AsyncMediaParser {
     bb: Buffers,
     buf: Option<Vec<u8>>,
     position: usize,
+    pub(crate) swap_endian_on_mismatch: bool,
+    pub(crate) search_exif_on_corrupt_jpeg: bool,
+    pub(crate) lens_resolver: Option<LensResolver>,
+    pub(crate) max_entry_components: Option<u32>,
 }
 
 impl Debug for AsyncMediaParser {
@@ -302,6 +342,11 @@ impl Debug for AsyncMediaParser {
             .field("buffers", &self.bb)
             .field("buf len", &self.buf.as_ref().map(|x| x.len()))
             .field("position", &self.position)
+            .field("swap_endian_on_mismatch", &self.swap_endian_on_mismatch)
+            .field(
+                "search_exif_on_corrupt_jpeg",
+                &self.search_exif_on_corrupt_jpeg,
+            )
             .finish_non_exhaustive()
     }
 }
@@ -322,6 +367,10 @@ impl Default for AsyncMediaParser {
             bb: Buffers::new(),
             buf: None,
             position: 0,
+            swap_endian_on_mismatch: false,
+            search_exif_on_corrupt_jpeg: false,
+            lens_resolver: None,
+            max_entry_components: None,
         }
     }
 }
@@ -341,6 +390,42 @@ impl AsyncMediaParser {
         Self::default()
     }
 
+    /// Retry with the opposite byte order when the TIFF header's declared
+    /// endianness looks wrong, i.e. the IFD0 entry num is absurd under the
+    /// declared byte order but sane under the opposite one. Disabled by
+    /// default.
+    ///
+    /// A warning is logged whenever such a mismatch is detected, whether this
+    /// option is enabled or not.
+    pub fn swap_endian_on_mismatch(mut self, enable: bool) -> Self {
+        self.swap_endian_on_mismatch = enable;
+        self
+    }
+
+    /// Search the "Exif\0\0" signature linearly in the first 64 KiB of a
+    /// JPEG file when its segments can't be walked, e.g. the segment length
+    /// bytes are corrupt. Disabled by default.
+    ///
+    /// This recovers Exif data from mildly damaged files, at the risk of
+    /// picking up an Exif block which isn't the primary one.
+    pub fn search_exif_on_corrupt_jpeg(mut self, enable: bool) -> Self {
+        self.search_exif_on_corrupt_jpeg = enable;
+        self
+    }
+
+    /// Reject Exif entries with more than `max` components, see
+    /// [`MediaParser::max_entry_components`](crate::MediaParser::max_entry_components).
+    pub fn max_entry_components(mut self, max: u32) -> Self {
+        self.max_entry_components = Some(max);
+        self
+    }
+
+    /// Set a resolver which turns `(Make, lens type)` into a lens name, see
+    /// [`MediaParser::set_lens_resolver`](crate::MediaParser::set_lens_resolver).
+    pub fn set_lens_resolver(&mut self, resolver: LensResolverFn) {
+        self.lens_resolver = Some(resolver.into());
+    }
+
     /// `MediaParser`/`AsyncMediaParser` comes with its own buffer management,
     /// so that buffers can be reused during multiple parsing processes to
     /// avoid frequent memory allocations. Therefore, try to reuse a
@@ -531,6 +616,23 @@ mod tests {
         }
     }
 
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+    async fn lens_resolver() {
+        let maker_note = crate::exif::makernote::tests::make_canon_maker_note(254);
+        let tiff = crate::exif::makernote::tests::make_tiff("Canon", &maker_note);
+
+        let mut parser = AsyncMediaParser::new();
+        parser.set_lens_resolver(Box::new(|make, lens_type| {
+            (make == "Canon" && lens_type == 254).then(|| "EF 100mm".to_owned())
+        }));
+        let ms = AsyncMediaSource::seekable(std::io::Cursor::new(tiff))
+            .await
+            .unwrap();
+        let iter: ExifIter = parser.parse(ms).await.unwrap();
+        assert_eq!(iter.lens_type(), Some(254));
+        assert_eq!(iter.lens_name().unwrap(), "EF 100mm");
+    }
+
     use crate::{EntryValue, ExifTag, TrackInfoTag};
     use chrono::DateTime;
     use test_case::test_case;
@@ -545,10 +647,13 @@ mod tests {
     #[test_case("meta.mov", Make, "Apple".into())]
     #[test_case("meta.mov", Model, "iPhone X".into())]
     #[test_case("meta.mov", GpsIso6709, "+27.1281+100.2508+000.000/".into())]
+    #[test_case("meta.mov", Timescale, 600_u32.into())]
+    #[test_case("meta.mov", PreferredRate, EntryValue::F64(1.0))]
     #[test_case("meta.mp4", ImageWidth, 1920_u32.into())]
     #[test_case("meta.mp4", ImageHeight, 1080_u32.into())]
     #[test_case("meta.mp4", DurationMs, 1063_u64.into())]
     #[test_case("meta.mp4", GpsIso6709, "+27.2939+112.6932/".into())]
+    #[test_case("meta.mp4", Timescale, 10000_u32.into())]
     #[test_case("meta.mp4", CreateDate, DateTime::parse_from_str("2024-02-03T07:05:38Z", "%+").unwrap().into())]
     async fn parse_track_info(path: &str, tag: TrackInfoTag, v: EntryValue) {
         let mut parser = AsyncMediaParser::new();
@@ -563,4 +668,22 @@ mod tests {
         let info: TrackInfo = parser.parse(ms).await.unwrap();
         assert_eq!(info.get(tag).unwrap(), &v);
     }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+    #[case("exif.jpg", MediaType::Jpeg)]
+    #[case("exif.heic", MediaType::Heic)]
+    #[case("tif.tif", MediaType::Tiff)]
+    #[case("meta.mov", MediaType::QuickTime)]
+    #[case("meta.mp4", MediaType::Mp4)]
+    #[case("webm_480.webm", MediaType::Webm)]
+    #[case("mkv_640x360.mkv", MediaType::Matroska)]
+    async fn open_media_type(path: &str, expect: MediaType) {
+        let (ms, media_type) = AsyncMediaSource::open(Path::new("testdata").join(path))
+            .await
+            .unwrap();
+        assert_eq!(media_type, expect);
+        assert_eq!(ms.media_type(), expect);
+        assert_eq!(ms.has_exif(), media_type.is_image());
+        assert_eq!(ms.has_track(), media_type.is_video());
+    }
 }
@@ -0,0 +1,198 @@
+use std::{
+    fmt::Debug,
+    ops::{Deref, DerefMut},
+    sync::{Mutex, MutexGuard},
+};
+
+use crate::MediaParser;
+
+type Factory = Box<dyn Fn() -> MediaParser + Send + Sync>;
+
+/// A pool of [`MediaParser`]s which can be shared between threads.
+///
+/// A `MediaParser` manages mutable parse buffers, so it can't be shared
+/// between threads directly. Instead of creating a parser per task, or
+/// locking a single one, a `MediaParserPool` hands out a parser to each
+/// worker via [`Self::get`], and takes it back for reuse when the returned
+/// guard is dropped. Thus the parse buffers are reused across the threads.
+///
+/// ## Example
+///
+/// ```rust
+/// use nom_exif::*;
+/// use std::thread;
+///
+/// let pool = MediaParserPool::new();
+///
+/// thread::scope(|s| {
+///     for path in ["./testdata/exif.jpg", "./testdata/exif.heic"] {
+///         let pool = &pool;
+///         s.spawn(move || {
+///             let mut parser = pool.get();
+///             let ms = MediaSource::file_path(path).unwrap();
+///             let iter: ExifIter = parser.parse(ms).unwrap();
+///             let exif: Exif = iter.into();
+///             assert!(exif.get(ExifTag::Make).is_some());
+///         });
+///     }
+/// });
+///
+/// // The parsers have been returned to the pool
+/// assert!(pool.idle_count() >= 1);
+/// ```
+pub struct MediaParserPool {
+    parsers: Mutex<Vec<MediaParser>>,
+    factory: Factory,
+}
+
+impl Debug for MediaParserPool {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MediaParserPool")
+            .field("idle", &self.idle_count())
+            .finish_non_exhaustive()
+    }
+}
+
+impl Default for MediaParserPool {
+    fn default() -> Self {
+        Self::with_factory(MediaParser::new)
+    }
+}
+
+impl MediaParserPool {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a pool which creates new parsers with `factory`, e.g. to
+    /// configure the parsers:
+    ///
+    /// ```rust
+    /// use nom_exif::*;
+    ///
+    /// let pool = MediaParserPool::with_factory(|| {
+    ///     MediaParser::new().swap_endian_on_mismatch(true)
+    /// });
+    /// ```
+    pub fn with_factory<F>(factory: F) -> Self
+    where
+        F: Fn() -> MediaParser + Send + Sync + 'static,
+    {
+        Self {
+            parsers: Mutex::new(Vec::new()),
+            factory: Box::new(factory),
+        }
+    }
+
+    /// Takes an idle parser from the pool, or creates a new one if there is
+    /// none. The parser is returned to the pool when the guard is dropped.
+    pub fn get(&self) -> PooledMediaParser<'_> {
+        let parser = self.lock().pop().unwrap_or_else(|| (self.factory)());
+        PooledMediaParser {
+            pool: self,
+            parser: Some(parser),
+        }
+    }
+
+    /// Returns the number of idle parsers in the pool.
+    pub fn idle_count(&self) -> usize {
+        self.lock().len()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Vec<MediaParser>> {
+        // A panicked worker can't break the Vec, so it's safe to ignore the
+        // poison flag.
+        self.parsers.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// A [`MediaParser`] borrowed from a [`MediaParserPool`], which will be
+/// returned to the pool when dropped.
+#[derive(Debug)]
+pub struct PooledMediaParser<'a> {
+    pool: &'a MediaParserPool,
+    parser: Option<MediaParser>,
+}
+
+impl Deref for PooledMediaParser<'_> {
+    type Target = MediaParser;
+
+    fn deref(&self) -> &Self::Target {
+        // Safe unwrap: it's only taken in `drop`
+        self.parser.as_ref().unwrap()
+    }
+}
+
+impl DerefMut for PooledMediaParser<'_> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        // Safe unwrap: it's only taken in `drop`
+        self.parser.as_mut().unwrap()
+    }
+}
+
+impl Drop for PooledMediaParser<'_> {
+    fn drop(&mut self) {
+        if let Some(parser) = self.parser.take() {
+            self.pool.lock().push(parser);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::thread;
+
+    use crate::{testkit::open_sample, ExifIter, MediaSource, TrackInfo};
+
+    use super::*;
+
+    #[test]
+    fn reuse_parsers() {
+        let _ = tracing_subscriber::fmt().with_test_writer().try_init();
+
+        let pool = MediaParserPool::new();
+        assert_eq!(pool.idle_count(), 0);
+        {
+            let _p1 = pool.get();
+            let _p2 = pool.get();
+            assert_eq!(pool.idle_count(), 0);
+        }
+        assert_eq!(pool.idle_count(), 2);
+
+        let p = pool.get();
+        assert_eq!(pool.idle_count(), 1);
+        drop(p);
+        assert_eq!(pool.idle_count(), 2);
+    }
+
+    #[test]
+    fn parse_in_threads() {
+        let _ = tracing_subscriber::fmt().with_test_writer().try_init();
+
+        let pool =
+            MediaParserPool::with_factory(|| MediaParser::new().swap_endian_on_mismatch(true));
+        let paths = ["exif.jpg", "exif.heic", "meta.mov", "webm_480.webm"];
+
+        thread::scope(|s| {
+            for _ in 0..4 {
+                s.spawn(|| {
+                    for path in paths {
+                        let mut parser = pool.get();
+                        assert!(parser.swap_endian_on_mismatch);
+                        let ms = MediaSource::file(open_sample(path).unwrap()).unwrap();
+                        if ms.has_exif() {
+                            let iter: ExifIter = parser.parse(ms).unwrap();
+                            assert!(iter.count() > 0);
+                        } else {
+                            let info: TrackInfo = parser.parse(ms).unwrap();
+                            assert!(info.iter().count() > 0);
+                        }
+                    }
+                });
+            }
+        });
+
+        let idle = pool.idle_count();
+        assert!((1..=4).contains(&idle), "{idle}");
+    }
+}
@@ -0,0 +1,209 @@
+use nom::bytes::complete;
+
+use crate::{
+    error::{ParsingError, ParsingErrorState},
+    exif::check_exif_header,
+    parser::ParsingState,
+};
+
+/// The 8-byte PNG signature.
+const SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+
+/// Size of the length & type fields of a chunk.
+const CHUNK_HEADER_SIZE: usize = 8;
+
+/// Size of the CRC field of a chunk.
+const CHUNK_CRC_SIZE: usize = 4;
+
+/// Refer to: [PNG](https://www.w3.org/TR/png/#5PNG-file-signature)
+pub(crate) fn check_png(input: &[u8]) -> crate::Result<()> {
+    let _ = complete::tag(SIGNATURE)(input)?;
+    Ok(())
+}
+
+/// Extract Exif TIFF data from the `eXIf` chunk of a PNG file.
+///
+/// The `eXIf` chunk may be located after the (usually large) `IDAT` chunks,
+/// chunks which haven't been fully loaded are skipped with
+/// `ParsingError::ClearAndSkip`. After that, the buffer starts at the next
+/// chunk rather than the signature, which is indicated by
+/// `ParsingState::PngChunk`.
+#[tracing::instrument(skip_all)]
+pub(crate) fn extract_exif_data(
+    input: &[u8],
+    state: Option<ParsingState>,
+) -> Result<(Option<&[u8]>, Option<ParsingState>), ParsingErrorState> {
+    let mut pos = match state {
+        Some(ParsingState::PngChunk) => 0,
+        _ => {
+            if input.len() < SIGNATURE.len() {
+                let need = ParsingError::Need(SIGNATURE.len() - input.len());
+                return Err(ParsingErrorState::new(need, state));
+            }
+            if !input.starts_with(SIGNATURE) {
+                let failed = ParsingError::Failed("invalid PNG signature".into());
+                return Err(ParsingErrorState::new(failed, state));
+            }
+            SIGNATURE.len()
+        }
+    };
+
+    loop {
+        let Some(header) = input.get(pos..pos + CHUNK_HEADER_SIZE) else {
+            let need = ParsingError::Need(pos + CHUNK_HEADER_SIZE - input.len());
+            return Err(ParsingErrorState::new(need, state));
+        };
+        let size = u32::from_be_bytes([header[0], header[1], header[2], header[3]]) as usize;
+        let chunk_type = &header[4..]; // Safe-slice
+        let data_start = pos + CHUNK_HEADER_SIZE;
+        let data_end = data_start.saturating_add(size);
+        tracing::debug!(
+            chunk_type = String::from_utf8_lossy(chunk_type).as_ref(),
+            size,
+            "Got"
+        );
+
+        match chunk_type {
+            b"eXIf" => {
+                let Some(data) = input.get(data_start..data_end) else {
+                    let need = ParsingError::Need(data_end - input.len());
+                    return Err(ParsingErrorState::new(need, state));
+                };
+                // The chunk data is the TIFF data without the "Exif\0\0"
+                // header, but some writers keep the header of the JPEG APP1
+                // segment. Only a present header is stripped.
+                let data = if check_exif_header(data).unwrap_or(false) {
+                    &data[6..] // Safe-slice
+                } else {
+                    data
+                };
+                return Ok((Some(data), state));
+            }
+            b"IEND" => return Ok((None, state)),
+            _ => {
+                let chunk_end = data_end.saturating_add(CHUNK_CRC_SIZE);
+                if chunk_end > input.len() {
+                    let skip = ParsingError::ClearAndSkip(chunk_end);
+                    return Err(ParsingErrorState::new(skip, Some(ParsingState::PngChunk)));
+                }
+                pos = chunk_end;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use crate::{
+        exif::extract_exif_with_mime, file::MimeImage, is_tiff_header, testkit::read_sample, Exif,
+        ExifIter, ExifTag, MediaParser, MediaSource,
+    };
+
+    use super::*;
+    use test_case::test_case;
+
+    /// Builds a minimal PNG file embedding the Exif data of "exif.jpg", the
+    /// `eXIf` chunk is placed after an `IDAT` chunk of `idat_size` bytes if
+    /// `after_idat` is true. Returns the PNG data and the Exif data.
+    pub(crate) fn build_png(after_idat: bool, idat_size: usize) -> (Vec<u8>, Vec<u8>) {
+        let buf = read_sample("exif.jpg").unwrap();
+        let (exif, _) = extract_exif_with_mime(MimeImage::Jpeg, &buf, None).unwrap();
+        let exif = exif.unwrap().to_vec();
+
+        let mut png = SIGNATURE.to_vec();
+        let mut push_chunk = |chunk_type: &[u8], data: &[u8]| {
+            png.extend_from_slice(&(data.len() as u32).to_be_bytes());
+            png.extend_from_slice(chunk_type);
+            png.extend_from_slice(data);
+            // CRC isn't verified
+            png.extend_from_slice(&[0; 4]);
+        };
+        push_chunk(b"IHDR", &[0; 13]);
+        if !after_idat {
+            push_chunk(b"eXIf", &exif);
+        }
+        push_chunk(b"IDAT", &vec![0x55; idat_size]);
+        if after_idat {
+            push_chunk(b"eXIf", &exif);
+        }
+        push_chunk(b"IEND", &[]);
+
+        (png, exif)
+    }
+
+    #[test]
+    fn png_exif() {
+        let _ = tracing_subscriber::fmt().with_test_writer().try_init();
+
+        for after_idat in [false, true] {
+            let (png, exif) = build_png(after_idat, 100);
+            check_png(&png).unwrap();
+            let (data, _) = extract_exif_data(&png, None).unwrap();
+            assert_eq!(data, Some(&exif[..]));
+        }
+
+        check_png(&read_sample("exif.jpg").unwrap()).unwrap_err();
+    }
+
+    #[test]
+    fn png_skip_chunks() {
+        let _ = tracing_subscriber::fmt().with_test_writer().try_init();
+
+        let (png, exif) = build_png(true, 0x10000);
+        // IHDR is loaded, the IDAT is not
+        let idat_pos = SIGNATURE.len() + CHUNK_HEADER_SIZE + 13 + CHUNK_CRC_SIZE;
+        let idat_end = idat_pos + CHUNK_HEADER_SIZE + 0x10000 + CHUNK_CRC_SIZE;
+        let err = extract_exif_data(&png[..idat_pos + 100], None).unwrap_err();
+        assert!(matches!(err.err, ParsingError::ClearAndSkip(n) if n == idat_end));
+        assert!(matches!(err.state, Some(ParsingState::PngChunk)));
+
+        // The buffer starts at the chunk after IDAT
+        let (data, _) = extract_exif_data(&png[idat_end..], err.state).unwrap();
+        assert_eq!(data, Some(&exif[..]));
+
+        let err = extract_exif_data(&png[..idat_pos + 4], None).unwrap_err();
+        assert!(matches!(err.err, ParsingError::Need(4)));
+    }
+
+    #[test_case(false; "raw TIFF")]
+    #[test_case(true; "with Exif header")]
+    fn png_exif_make_model(with_header: bool) {
+        let _ = tracing_subscriber::fmt().with_test_writer().try_init();
+
+        let (mut png, exif) = build_png(true, 100);
+        assert!(is_tiff_header(&exif));
+        if with_header {
+            let pos = png.windows(4).position(|x| x == b"eXIf").unwrap();
+            let size = exif.len() + 6;
+            png[pos - 4..pos].copy_from_slice(&(size as u32).to_be_bytes());
+            png.splice(pos + 4..pos + 4, *b"Exif\0\0");
+        }
+
+        let (data, _) = extract_exif_data(&png, None).unwrap();
+        assert_eq!(data, Some(&exif[..]));
+
+        let mut parser = MediaParser::new();
+        let ms = MediaSource::seekable(std::io::Cursor::new(&png)).unwrap();
+        let exif: Exif = parser.parse::<_, _, ExifIter>(ms).unwrap().into();
+        assert_eq!(
+            exif.get(ExifTag::Make).and_then(|x| x.as_str()),
+            Some("vivo")
+        );
+        assert_eq!(
+            exif.get(ExifTag::Model).and_then(|x| x.as_str()),
+            Some("vivo X90 Pro+")
+        );
+    }
+
+    #[test]
+    fn png_without_exif() {
+        let _ = tracing_subscriber::fmt().with_test_writer().try_init();
+
+        let mut png = SIGNATURE.to_vec();
+        png.extend_from_slice(b"\0\0\0\x0dIHDR");
+        png.extend_from_slice(&[0; 17]);
+        png.extend_from_slice(b"\0\0\0\0IEND\0\0\0\0");
+        let (data, _) = extract_exif_data(&png, None).unwrap();
+        assert_eq!(data, None);
+    }
+}
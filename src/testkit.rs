@@ -1,7 +1,13 @@
-use std::{fs::File, io::Read, path::Path};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs::File,
+    io::Read,
+    path::Path,
+};
 
 use crate::exif::Exif;
-use crate::exif::ExifTag::*;
+use crate::exif::ExifTag::{self, *};
+use crate::TrackInfo;
 
 pub fn read_sample(path: &str) -> Result<Vec<u8>, std::io::Error> {
     let mut f = open_sample(path)?;
@@ -151,3 +157,327 @@ pub fn sorted_exif_entries(exif: &Exif) -> Vec<String> {
 
     entries
 }
+
+/// Asserts that the entries of `exif` equal to `expected`, which is a list of
+/// `("<ifd index>.<tag name>", "<value>")`, e.g. `("0.Make", "Apple")`.
+///
+/// Tags unknown to [`ExifTag`] are named by the hex tag code, e.g.
+/// `"0.0x9999"`. Panics with a diff of the mismatched entries.
+#[track_caller]
+pub fn assert_exif_snapshot(exif: &Exif, expected: &[(&str, &str)]) {
+    let actual = exif
+        .entries()
+        .map(|(ifd, code, v)| {
+            let tag = ExifTag::try_from(code)
+                .map(|t| t.to_string())
+                .unwrap_or_else(|_| format!("0x{code:04x}"));
+            (format!("{ifd}.{tag}"), v.to_string())
+        })
+        .collect();
+    assert_snapshot(actual, expected);
+}
+
+/// Asserts that the entries of `info` equal to `expected`, which is a list of
+/// `("<tag name>", "<value>")`, e.g. `("Width", "640")`. The parsed
+/// `GPSInfo` is not included.
+///
+/// Panics with a diff of the mismatched entries.
+#[track_caller]
+pub fn assert_track_snapshot(info: &TrackInfo, expected: &[(&str, &str)]) {
+    let actual = info
+        .iter()
+        .map(|(tag, v)| (tag.to_string(), v.to_string()))
+        .collect();
+    assert_snapshot(actual, expected);
+}
+
+#[track_caller]
+fn assert_snapshot(actual: BTreeMap<String, String>, expected: &[(&str, &str)]) {
+    let expected = expected
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect::<BTreeMap<_, _>>();
+
+    let keys = expected
+        .keys()
+        .chain(actual.keys())
+        .collect::<BTreeSet<_>>();
+    let mut diff = Vec::new();
+    for key in keys {
+        match (expected.get(key), actual.get(key)) {
+            (Some(e), Some(a)) if e == a => (),
+            (e, a) => {
+                if let Some(e) = e {
+                    diff.push(format!("- {key}: {e}"));
+                }
+                if let Some(a) = a {
+                    diff.push(format!("+ {key}: {a}"));
+                }
+            }
+        }
+    }
+
+    assert!(
+        diff.is_empty(),
+        "snapshot mismatch (- expected, + actual):\n{}",
+        diff.join("\n")
+    );
+}
+
+/// Builds an ISOBMFF box (a.k.a. QuickTime atom) with a 32-bit size, i.e.
+/// `size | box_type | body`.
+pub fn make_box(box_type: &[u8], body: &[u8]) -> Vec<u8> {
+    let mut v = ((body.len() + 8) as u32).to_be_bytes().to_vec();
+    v.extend_from_slice(box_type);
+    v.extend_from_slice(body);
+    v
+}
+
+/// Value of an entry added by [`TiffBuilder::entry`].
+pub enum TiffValue {
+    /// Encoded value data, which is stored inline if it fits in the value
+    /// field, otherwise right after the IFD.
+    Data(Vec<u8>),
+    /// The value field as is, e.g. an invalid offset.
+    Field(u32),
+    /// Offsets of the IFDs, which are stored inline if there is only one.
+    Ifds(Vec<usize>),
+    /// Offset of the blob.
+    Blob(usize),
+}
+
+enum TiffChunk {
+    Ifd {
+        entries: Vec<(u16, u16, u32, TiffValue)>,
+        next: Option<usize>,
+    },
+    Blob(Vec<u8>),
+}
+
+/// Builds TIFF data for tests.
+///
+/// IFDs & blobs are laid out in the order they're added, starting with
+/// IFD0 at offset 8. Each IFD is followed by its values which don't fit in
+/// the value field, in the order of the entries. Offsets referring to IFDs
+/// & blobs are resolved by [`Self::build`].
+///
+/// ```ignore
+/// let mut tiff = TiffBuilder::new(Endianness::Little);
+/// let exif = tiff.ifd();
+/// tiff.sub_ifd(0, 0x8769, exif).text(0, 0x010f, "Canon");
+/// tiff.short(exif, 0x8827, &[100]);
+/// let data = tiff.build();
+/// ```
+pub struct TiffBuilder {
+    endian: nom::number::Endianness,
+    chunks: Vec<TiffChunk>,
+    ifds: Vec<usize>,
+    blobs: Vec<usize>,
+}
+
+impl TiffBuilder {
+    /// Creates a builder with an empty IFD0.
+    pub fn new(endian: nom::number::Endianness) -> Self {
+        let mut builder = Self {
+            endian,
+            chunks: Vec::new(),
+            ifds: Vec::new(),
+            blobs: Vec::new(),
+        };
+        builder.ifd();
+        builder
+    }
+
+    /// Adds an empty IFD, returns its index.
+    pub fn ifd(&mut self) -> usize {
+        self.ifds.push(self.chunks.len());
+        self.chunks.push(TiffChunk::Ifd {
+            entries: Vec::new(),
+            next: None,
+        });
+        self.ifds.len() - 1
+    }
+
+    /// Adds raw data, e.g. a preview image or padding, returns its index.
+    pub fn blob(&mut self, data: &[u8]) -> usize {
+        self.blobs.push(self.chunks.len());
+        self.chunks.push(TiffChunk::Blob(data.to_vec()));
+        self.blobs.len() - 1
+    }
+
+    /// Sets the next IFD offset of the IFD `from` to the IFD `to`.
+    pub fn link(&mut self, from: usize, to: usize) -> &mut Self {
+        match &mut self.chunks[self.ifds[from]] {
+            TiffChunk::Ifd { next, .. } => *next = Some(to),
+            TiffChunk::Blob(_) => unreachable!(),
+        }
+        self
+    }
+
+    pub fn entry(
+        &mut self,
+        ifd: usize,
+        tag: u16,
+        format: u16,
+        count: u32,
+        value: TiffValue,
+    ) -> &mut Self {
+        match &mut self.chunks[self.ifds[ifd]] {
+            TiffChunk::Ifd { entries, .. } => entries.push((tag, format, count, value)),
+            TiffChunk::Blob(_) => unreachable!(),
+        }
+        self
+    }
+
+    pub fn byte(&mut self, ifd: usize, tag: u16, values: &[u8]) -> &mut Self {
+        let data = TiffValue::Data(values.to_vec());
+        self.entry(ifd, tag, 1, values.len() as u32, data)
+    }
+
+    pub fn undefined(&mut self, ifd: usize, tag: u16, values: &[u8]) -> &mut Self {
+        let data = TiffValue::Data(values.to_vec());
+        self.entry(ifd, tag, 7, values.len() as u32, data)
+    }
+
+    /// Adds an ASCII entry, the terminating NUL is appended.
+    pub fn text(&mut self, ifd: usize, tag: u16, text: &str) -> &mut Self {
+        let mut data = text.as_bytes().to_vec();
+        data.push(0);
+        self.entry(ifd, tag, 2, data.len() as u32, TiffValue::Data(data))
+    }
+
+    pub fn short(&mut self, ifd: usize, tag: u16, values: &[u16]) -> &mut Self {
+        let data = values.iter().flat_map(|x| self.u16_bytes(*x)).collect();
+        self.entry(ifd, tag, 3, values.len() as u32, TiffValue::Data(data))
+    }
+
+    pub fn sshort(&mut self, ifd: usize, tag: u16, values: &[i16]) -> &mut Self {
+        let data = values
+            .iter()
+            .flat_map(|x| self.u16_bytes(*x as u16))
+            .collect();
+        self.entry(ifd, tag, 8, values.len() as u32, TiffValue::Data(data))
+    }
+
+    pub fn long(&mut self, ifd: usize, tag: u16, values: &[u32]) -> &mut Self {
+        let data = values.iter().flat_map(|x| self.u32_bytes(*x)).collect();
+        self.entry(ifd, tag, 4, values.len() as u32, TiffValue::Data(data))
+    }
+
+    pub fn rational(&mut self, ifd: usize, tag: u16, values: &[(u32, u32)]) -> &mut Self {
+        let data = values
+            .iter()
+            .flat_map(|(n, d)| self.u32_bytes(*n).into_iter().chain(self.u32_bytes(*d)))
+            .collect();
+        self.entry(ifd, tag, 5, values.len() as u32, TiffValue::Data(data))
+    }
+
+    /// Adds a LONG entry pointing to the IFD `target`, e.g. `ExifOffset`.
+    pub fn sub_ifd(&mut self, ifd: usize, tag: u16, target: usize) -> &mut Self {
+        self.entry(ifd, tag, 4, 1, TiffValue::Ifds(vec![target]))
+    }
+
+    pub fn ifd_offset(&self, ifd: usize) -> u32 {
+        self.chunk_offsets()[self.ifds[ifd]]
+    }
+
+    pub fn blob_offset(&self, blob: usize) -> u32 {
+        self.chunk_offsets()[self.blobs[blob]]
+    }
+
+    pub fn build(&self) -> Vec<u8> {
+        let offsets = self.chunk_offsets();
+        let mut buf = match self.endian {
+            nom::number::Endianness::Big => b"MM\0*".to_vec(),
+            _ => b"II*\0".to_vec(),
+        };
+        buf.extend_from_slice(&self.u32_bytes(offsets[self.ifds[0]]));
+
+        for (chunk, offset) in self.chunks.iter().zip(offsets.iter()) {
+            assert_eq!(buf.len(), *offset as usize);
+            let (entries, next) = match chunk {
+                TiffChunk::Ifd { entries, next } => (entries, next),
+                TiffChunk::Blob(data) => {
+                    buf.extend_from_slice(data);
+                    continue;
+                }
+            };
+
+            let mut data_pos = *offset as usize + Self::ifd_size(entries.len());
+            let mut data = Vec::new();
+            buf.extend_from_slice(&self.u16_bytes(entries.len() as u16));
+            for (tag, format, count, value) in entries {
+                buf.extend_from_slice(&self.u16_bytes(*tag));
+                buf.extend_from_slice(&self.u16_bytes(*format));
+                buf.extend_from_slice(&self.u32_bytes(*count));
+                let value = match value {
+                    TiffValue::Data(x) => x.clone(),
+                    TiffValue::Field(x) => self.u32_bytes(*x).to_vec(),
+                    TiffValue::Ifds(x) => x
+                        .iter()
+                        .flat_map(|i| self.u32_bytes(offsets[self.ifds[*i]]))
+                        .collect(),
+                    TiffValue::Blob(i) => self.u32_bytes(offsets[self.blobs[*i]]).to_vec(),
+                };
+                if value.len() <= 4 {
+                    let mut field = value;
+                    field.resize(4, 0);
+                    buf.extend_from_slice(&field);
+                } else {
+                    buf.extend_from_slice(&self.u32_bytes(data_pos as u32));
+                    data.extend_from_slice(&value);
+                    // Keep the offsets word aligned
+                    data.resize(data.len() + data.len() % 2, 0);
+                    data_pos = *offset as usize + Self::ifd_size(entries.len()) + data.len();
+                }
+            }
+            let next = next.map_or(0, |i| offsets[self.ifds[i]]);
+            buf.extend_from_slice(&self.u32_bytes(next));
+            buf.extend_from_slice(&data);
+        }
+        buf
+    }
+
+    fn ifd_size(entry_num: usize) -> usize {
+        2 + entry_num * 12 + 4
+    }
+
+    fn chunk_offsets(&self) -> Vec<u32> {
+        let mut pos = 8;
+        self.chunks
+            .iter()
+            .map(|chunk| {
+                let offset = pos;
+                pos += match chunk {
+                    TiffChunk::Ifd { entries, .. } => {
+                        let data: usize = entries
+                            .iter()
+                            .map(|(.., value)| match value {
+                                TiffValue::Data(x) if x.len() > 4 => x.len() + x.len() % 2,
+                                TiffValue::Ifds(x) if x.len() > 1 => x.len() * 4,
+                                _ => 0,
+                            })
+                            .sum();
+                        Self::ifd_size(entries.len()) + data
+                    }
+                    TiffChunk::Blob(data) => data.len(),
+                };
+                offset as u32
+            })
+            .collect()
+    }
+
+    fn u16_bytes(&self, x: u16) -> [u8; 2] {
+        match self.endian {
+            nom::number::Endianness::Big => x.to_be_bytes(),
+            _ => x.to_le_bytes(),
+        }
+    }
+
+    fn u32_bytes(&self, x: u32) -> [u8; 4] {
+        match self.endian {
+            nom::number::Endianness::Big => x.to_be_bytes(),
+            _ => x.to_le_bytes(),
+        }
+    }
+}
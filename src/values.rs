@@ -1,4 +1,4 @@
-use std::{fmt::Display, string::FromUtf8Error};
+use std::{fmt::Display, str::Utf8Error, string::FromUtf8Error};
 
 use chrono::{
     offset::LocalResult, DateTime, FixedOffset, Local, NaiveDateTime, Offset, TimeZone as _, Utc,
@@ -12,7 +12,11 @@ use thiserror::Error;
 use crate::ExifTag;
 
 /// Represent a parsed entry value.
+///
+/// More variants may be added as more data formats are supported, so please
+/// keep a wildcard arm when matching on it.
 #[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
 pub enum EntryValue {
     Text(String),
     URational(URational),
@@ -37,8 +41,10 @@ pub enum EntryValue {
     URationalArray(Vec<URational>),
     IRationalArray(Vec<IRational>),
 
+    U8Array(Vec<u8>),
     U16Array(Vec<u16>),
     U32Array(Vec<u32>),
+    I16Array(Vec<i16>),
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -121,49 +127,49 @@ impl EntryValue {
         let data = entry.data;
         let components_num = entry.components_num;
 
-        if data.is_empty() || components_num == 0 {
-            return Ok(EntryValue::variant_default(data_format));
-        }
-
-        let exif_tag: Result<ExifTag, _> = tag.try_into();
-        if let Ok(tag) = exif_tag {
-            if tag == ExifTag::DateTimeOriginal
-                || tag == ExifTag::CreateDate
-                || tag == ExifTag::ModifyDate
-            {
-                // assert_eq!(data_format, 2);
-                // if data_format != 2 {
-                //     return Err(Error::InvalidData(
-                //         "invalid DirectoryEntry: date format is invalid".into(),
-                //     ));
-                // }
-                let s = get_cstr(data).map_err(|e| Error::InvalidData(e.to_string()))?;
-
-                let t = if let Some(tz) = tz {
-                    let s = format!("{s} {tz}");
-                    DateTime::parse_from_str(&s, "%Y:%m:%d %H:%M:%S %z")?
+        if components_num == 0 {
+            return Err(Error::InvalidData("components num is 0".into()));
+        }
+
+        if is_date_time_tag(tag) {
+            // assert_eq!(data_format, 2);
+            // if data_format != 2 {
+            //     return Err(Error::InvalidData(
+            //         "invalid DirectoryEntry: date format is invalid".into(),
+            //     ));
+            // }
+            let s = get_cstr(data).map_err(|e| Error::InvalidData(e.to_string()))?;
+
+            let Some(t) = parse_naive_time(&s) else {
+                // Keep the raw string rather than failing the whole entry
+                tracing::warn!(tag, s, "unrecognized date time format");
+                return Ok(EntryValue::Text(s));
+            };
+
+            let t = if let Some(tz) = tz {
+                let offset: FixedOffset = tz.parse()?;
+                offset
+                    .from_local_datetime(&t)
+                    .single()
+                    .ok_or_else(|| Error::InvalidData(format!("parse time failed: {s}")))?
+            } else {
+                let t = Local.from_local_datetime(&t);
+                let t = if let LocalResult::Single(t) = t {
+                    Ok(t)
                 } else {
-                    let t = NaiveDateTime::parse_from_str(&s, "%Y:%m:%d %H:%M:%S")?;
-                    let t = Local.from_local_datetime(&t);
-                    let t = if let LocalResult::Single(t) = t {
-                        Ok(t)
-                    } else {
-                        Err(Error::InvalidData(format!("parse time failed: {s}")))
-                    }?;
-
-                    t.with_timezone(t.offset())
-                };
-
-                return Ok(EntryValue::Time(t));
-            }
+                    Err(Error::InvalidData(format!("parse time failed: {s}")))
+                }?;
+
+                t.with_timezone(t.offset())
+            };
+
+            return Ok(EntryValue::Time(t));
         }
 
         match data_format {
             DataFormat::U8 => match components_num {
                 1 => Ok(Self::U8(data[0])),
-                x => Err(Error::Unsupported(format!(
-                    "unsigned byte with {x} components"
-                ))),
+                _ => Ok(Self::U8Array(data.to_vec())),
             },
             DataFormat::Text => Ok(EntryValue::Text(
                 get_cstr(data).map_err(|e| Error::InvalidData(e.to_string()))?,
@@ -183,7 +189,7 @@ impl EntryValue {
                     Ok(Self::U16Array(v))
                 }
             }
-            DataFormat::U32 => {
+            DataFormat::U32 | DataFormat::Ifd => {
                 if components_num == 1 {
                     Ok(Self::U32(u32::try_from_bytes(data, endian)?))
                 } else {
@@ -213,12 +219,21 @@ impl EntryValue {
                 ))),
             },
             DataFormat::Undefined => Ok(Self::Undefined(data.to_vec())),
-            DataFormat::I16 => match components_num {
-                1 => Ok(Self::I16(i16::try_from_bytes(data, endian)?)),
-                x => Err(Error::Unsupported(format!(
-                    "signed short with {x} components"
-                ))),
-            },
+            DataFormat::I16 => {
+                if components_num == 1 {
+                    Ok(Self::I16(i16::try_from_bytes(data, endian)?))
+                } else {
+                    let (_, v) = many_m_n::<_, _, nom::error::Error<_>, _>(
+                        components_num as usize,
+                        components_num as usize,
+                        nom::number::complete::i16(endian),
+                    )(data)
+                    .map_err(|e| {
+                        ParseEntryError::InvalidData(format!("parse I16Array error: {e:?}"))
+                    })?;
+                    Ok(Self::I16Array(v))
+                }
+            }
             DataFormat::I32 => match components_num {
                 1 => Ok(Self::I32(i32::try_from_bytes(data, endian)?)),
                 x => Err(Error::Unsupported(format!(
@@ -244,23 +259,69 @@ impl EntryValue {
         }
     }
 
-    fn variant_default(data_format: DataFormat) -> EntryValue {
-        match data_format {
-            DataFormat::U8 => Self::U8(0),
-            DataFormat::Text => Self::Text(String::default()),
-            DataFormat::U16 => Self::U16(0),
-            DataFormat::U32 => Self::U32(0),
-            DataFormat::URational => Self::URational(URational::default()),
-            DataFormat::I8 => Self::I8(0),
-            DataFormat::Undefined => Self::Undefined(Vec::default()),
-            DataFormat::I16 => Self::I16(0),
-            DataFormat::I32 => Self::I32(0),
-            DataFormat::IRational => Self::IRational(IRational::default()),
-            DataFormat::F32 => Self::F32(0.0),
-            DataFormat::F64 => Self::F64(0.0),
+    /// Returns true if the two values are equal, or both are scalar numbers
+    /// with the same numeric value regardless of their types, e.g. `U16(3)`
+    /// and `U32(3)`, or `URational(1/2)` and `URational(2/4)`.
+    pub(crate) fn numeric_eq(&self, other: &EntryValue) -> bool {
+        if self == other {
+            return true;
+        }
+        match (self.as_f64(), other.as_f64()) {
+            (Some(a), Some(b)) => a == b,
+            _ => false,
+        }
+    }
+
+    /// Bytes allocated on the heap by this value, i.e. the capacity of the
+    /// string or the array, not including `size_of::<EntryValue>()` itself.
+    pub(crate) fn heap_bytes(&self) -> usize {
+        use std::mem::size_of;
+        match self {
+            EntryValue::Text(v) => v.capacity(),
+            EntryValue::Undefined(v) | EntryValue::U8Array(v) => v.capacity(),
+            EntryValue::URationalArray(v) => v.capacity() * size_of::<URational>(),
+            EntryValue::IRationalArray(v) => v.capacity() * size_of::<IRational>(),
+            EntryValue::U16Array(v) => v.capacity() * size_of::<u16>(),
+            EntryValue::U32Array(v) => v.capacity() * size_of::<u32>(),
+            EntryValue::I16Array(v) => v.capacity() * size_of::<i16>(),
+            _ => 0,
         }
     }
 
+    /// Converts a scalar number into an `f64`, regardless of its type, e.g.
+    /// for building a histogram of `FocalLength` values.
+    ///
+    /// Returns `None` for text, time, `Undefined` & array values, and for a
+    /// rational with a zero denominator. Use [`Self::iter_f64`] for arrays.
+    ///
+    /// ```rust
+    /// use nom_exif::*;
+    ///
+    /// assert_eq!(EntryValue::U16(3).as_f64(), Some(3.0));
+    /// assert_eq!(EntryValue::I32(-2).as_f64(), Some(-2.0));
+    /// assert_eq!(EntryValue::URational((1, 4).into()).as_f64(), Some(0.25));
+    /// assert_eq!(EntryValue::URational((1, 0).into()).as_f64(), None);
+    /// assert_eq!(EntryValue::Text("1".into()).as_f64(), None);
+    /// ```
+    pub fn as_f64(&self) -> Option<f64> {
+        let v = match self {
+            EntryValue::U8(v) => *v as f64,
+            EntryValue::U16(v) => *v as f64,
+            EntryValue::U32(v) => *v as f64,
+            EntryValue::U64(v) => *v as f64,
+            EntryValue::I8(v) => *v as f64,
+            EntryValue::I16(v) => *v as f64,
+            EntryValue::I32(v) => *v as f64,
+            EntryValue::I64(v) => *v as f64,
+            EntryValue::F32(v) => *v as f64,
+            EntryValue::F64(v) => *v,
+            EntryValue::URational(v) if v.1 != 0 => v.as_float(),
+            EntryValue::IRational(v) if v.1 != 0 => v.as_float(),
+            _ => return None,
+        };
+        Some(v)
+    }
+
     pub fn as_str(&self) -> Option<&str> {
         match self {
             EntryValue::Text(v) => Some(v),
@@ -355,6 +416,134 @@ impl EntryValue {
             None
         }
     }
+
+    /// Returns the lossless "numerator/denominator" form of a rational value,
+    /// e.g. "175/100", without the decimal approximation appended by
+    /// `Display`. The components of a rational array are separated by ", ".
+    ///
+    /// Returns `None` if the value isn't a rational or rational array.
+    ///
+    /// ```rust
+    /// use nom_exif::*;
+    ///
+    /// let v = EntryValue::URational((175, 100).into());
+    /// assert_eq!(v.to_string(), "175/100 (1.7500)");
+    /// assert_eq!(v.as_fraction_string().unwrap(), "175/100");
+    ///
+    /// let v = EntryValue::IRationalArray(vec![(-1, 3).into(), (2, 1).into()]);
+    /// assert_eq!(v.as_fraction_string().unwrap(), "-1/3, 2/1");
+    /// assert_eq!(EntryValue::U32(1).as_fraction_string(), None);
+    /// ```
+    pub fn as_fraction_string(&self) -> Option<String> {
+        fn join<T: Display>(v: &[Rational<T>]) -> String {
+            v.iter()
+                .map(|x| format!("{}/{}", x.0, x.1))
+                .collect::<Vec<_>>()
+                .join(", ")
+        }
+
+        match self {
+            EntryValue::URational(v) => Some(join(std::slice::from_ref(v))),
+            EntryValue::IRational(v) => Some(join(std::slice::from_ref(v))),
+            EntryValue::URationalArray(v) => Some(join(v)),
+            EntryValue::IRationalArray(v) => Some(join(v)),
+            _ => None,
+        }
+    }
+
+    pub fn as_u8_array(&self) -> Option<&[u8]> {
+        if let EntryValue::U8Array(v) = self {
+            Some(v)
+        } else {
+            None
+        }
+    }
+
+    pub fn as_u16_array(&self) -> Option<&[u16]> {
+        if let EntryValue::U16Array(v) = self {
+            Some(v)
+        } else {
+            None
+        }
+    }
+
+    pub fn as_u32_array(&self) -> Option<&[u32]> {
+        if let EntryValue::U32Array(v) = self {
+            Some(v)
+        } else {
+            None
+        }
+    }
+
+    pub fn as_i16_array(&self) -> Option<&[i16]> {
+        if let EntryValue::I16Array(v) = self {
+            Some(v)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the number of elements, i.e. the length of an array (or
+    /// `Undefined` bytes), or 1 for a scalar number. Returns `None` for text
+    /// and time values.
+    ///
+    /// ```rust
+    /// use nom_exif::*;
+    ///
+    /// assert_eq!(EntryValue::U16Array(vec![1, 2, 3]).len(), Some(3));
+    /// assert_eq!(EntryValue::U32(1).len(), Some(1));
+    /// assert_eq!(EntryValue::Text("abc".into()).len(), None);
+    /// ```
+    pub fn len(&self) -> Option<usize> {
+        let len = match self {
+            EntryValue::Text(_) | EntryValue::Time(_) => return None,
+            EntryValue::Undefined(v) | EntryValue::U8Array(v) => v.len(),
+            EntryValue::URationalArray(v) => v.len(),
+            EntryValue::IRationalArray(v) => v.len(),
+            EntryValue::U16Array(v) => v.len(),
+            EntryValue::U32Array(v) => v.len(),
+            EntryValue::I16Array(v) => v.len(),
+            _ => 1,
+        };
+        Some(len)
+    }
+
+    /// Returns whether an array value is empty, see [`Self::len`].
+    pub fn is_empty(&self) -> Option<bool> {
+        self.len().map(|x| x == 0)
+    }
+
+    /// Iterates the numeric elements as `f64`, a scalar number is treated as
+    /// an array with only one element. Returns `None` for text, time and
+    /// `Undefined` values.
+    ///
+    /// A rational with a zero denominator results in an infinite or NaN
+    /// element.
+    ///
+    /// ```rust
+    /// use nom_exif::*;
+    ///
+    /// let v = EntryValue::URationalArray(vec![(1, 2).into(), (3, 1).into()]);
+    /// assert_eq!(v.iter_f64().unwrap().collect::<Vec<_>>(), [0.5, 3.0]);
+    /// assert_eq!(EntryValue::U8(7).iter_f64().unwrap().collect::<Vec<_>>(), [7.0]);
+    /// assert!(EntryValue::Undefined(vec![1]).iter_f64().is_none());
+    /// ```
+    pub fn iter_f64(&self) -> Option<Box<dyn Iterator<Item = f64> + '_>> {
+        let iter: Box<dyn Iterator<Item = f64>> = match self {
+            EntryValue::Text(_) | EntryValue::Time(_) | EntryValue::Undefined(_) => return None,
+            EntryValue::URational(v) => Box::new(std::iter::once(v.as_float())),
+            EntryValue::IRational(v) => Box::new(std::iter::once(v.as_float())),
+            EntryValue::URationalArray(v) => Box::new(v.iter().map(|x| x.as_float())),
+            EntryValue::IRationalArray(v) => Box::new(v.iter().map(|x| x.as_float())),
+            EntryValue::U8Array(v) => Box::new(v.iter().map(|x| *x as f64)),
+            EntryValue::U16Array(v) => Box::new(v.iter().map(|x| *x as f64)),
+            EntryValue::U32Array(v) => Box::new(v.iter().map(|x| *x as f64)),
+            EntryValue::I16Array(v) => Box::new(v.iter().map(|x| *x as f64)),
+            // Safe unwrap: the remaining variants are all scalar numbers
+            v => Box::new(std::iter::once(v.as_f64().unwrap())),
+        };
+        Some(iter)
+    }
 }
 
 /// # Exif Data format
@@ -388,14 +577,35 @@ pub(crate) enum DataFormat {
     IRational = 10,
     F32 = 11,
     F64 = 12,
+    /// An offset to an IFD, e.g. the values of `SubIFDs`
+    Ifd = 13,
 }
 
 impl DataFormat {
+    /// Name of the TIFF field type, e.g. "rational".
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::U8 => "byte",
+            Self::Text => "ascii",
+            Self::U16 => "short",
+            Self::U32 => "long",
+            Self::URational => "rational",
+            Self::I8 => "sbyte",
+            Self::Undefined => "undefined",
+            Self::I16 => "sshort",
+            Self::I32 => "slong",
+            Self::IRational => "srational",
+            Self::F32 => "float",
+            Self::F64 => "double",
+            Self::Ifd => "ifd",
+        }
+    }
+
     pub fn component_size(&self) -> usize {
         match self {
             Self::U8 | Self::I8 | Self::Text | Self::Undefined => 1,
             Self::U16 | Self::I16 => 2,
-            Self::U32 | Self::I32 | Self::F32 => 4,
+            Self::U32 | Self::I32 | Self::F32 | Self::Ifd => 4,
             Self::URational | Self::IRational | Self::F64 => 8,
         }
     }
@@ -404,7 +614,7 @@ impl DataFormat {
 impl TryFrom<u16> for DataFormat {
     type Error = Error;
     fn try_from(v: u16) -> Result<Self, Self::Error> {
-        if v >= Self::U8 as u16 && v <= Self::F64 as u16 {
+        if v >= Self::U8 as u16 && v <= Self::Ifd as u16 {
             Ok(unsafe { std::mem::transmute::<u16, Self>(v) })
         } else {
             Err(Error::InvalidData(format!("data format {v}")))
@@ -422,6 +632,52 @@ impl Serialize for EntryValue {
     }
 }
 
+#[cfg(feature = "json_dump")]
+impl EntryValue {
+    /// Convert into a typed JSON value, rather than the string used by the
+    /// default `Serialize` implementation:
+    ///
+    /// - Integer/float/rational values are converted into JSON numbers, a
+    ///   rational with a zero denominator is converted into `null`.
+    /// - Array values (including `Undefined` bytes) are converted into JSON
+    ///   arrays.
+    /// - Text and time values are converted into JSON strings.
+    ///
+    /// ```rust
+    /// use nom_exif::*;
+    ///
+    /// assert_eq!(EntryValue::from((175, 100)).to_json_value(), serde_json::json!(1.75));
+    /// assert_eq!(EntryValue::U16(3).to_json_value(), serde_json::json!(3));
+    /// ```
+    pub fn to_json_value(&self) -> serde_json::Value {
+        use serde_json::Value;
+
+        match self {
+            EntryValue::Text(v) => Value::from(v.as_str()),
+            EntryValue::URational(v) => Value::from(v.as_float()),
+            EntryValue::IRational(v) => Value::from(v.as_float()),
+            EntryValue::U8(v) => Value::from(*v),
+            EntryValue::U16(v) => Value::from(*v),
+            EntryValue::U32(v) => Value::from(*v),
+            EntryValue::U64(v) => Value::from(*v),
+            EntryValue::I8(v) => Value::from(*v),
+            EntryValue::I16(v) => Value::from(*v),
+            EntryValue::I32(v) => Value::from(*v),
+            EntryValue::I64(v) => Value::from(*v),
+            EntryValue::F32(v) => Value::from(*v),
+            EntryValue::F64(v) => Value::from(*v),
+            EntryValue::Time(v) => Value::from(v.to_rfc3339()),
+            EntryValue::Undefined(v) => Value::from(v.as_slice()),
+            EntryValue::URationalArray(v) => v.iter().map(|x| x.as_float()).collect(),
+            EntryValue::IRationalArray(v) => v.iter().map(|x| x.as_float()).collect(),
+            EntryValue::U8Array(v) => Value::from(v.as_slice()),
+            EntryValue::U16Array(v) => Value::from(v.as_slice()),
+            EntryValue::U32Array(v) => Value::from(v.as_slice()),
+            EntryValue::I16Array(v) => Value::from(v.as_slice()),
+        }
+    }
+}
+
 impl Display for EntryValue {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -468,8 +724,10 @@ impl Display for EntryValue {
             EntryValue::IRationalArray(v) => {
                 format!("IRationalArray[{}]", rationals_to_string::<i32>(v)).fmt(f)
             }
+            EntryValue::U8Array(v) => array_to_string("U8Array", v, f),
             EntryValue::U32Array(v) => array_to_string("U32Array", v, f),
             EntryValue::U16Array(v) => array_to_string("U16Array", v, f),
+            EntryValue::I16Array(v) => array_to_string("I16Array", v, f),
         }
     }
 }
@@ -626,6 +884,72 @@ where
     pub fn as_float(&self) -> f64 {
         std::convert::Into::<f64>::into(self.0) / std::convert::Into::<f64>::into(self.1)
     }
+
+    /// Converts to `f64`, rounded to `decimals` decimal places.
+    ///
+    /// ```rust
+    /// use nom_exif::URational;
+    ///
+    /// assert_eq!(URational::from((1, 3)).to_f64_rounded(2), 0.33);
+    /// ```
+    pub fn to_f64_rounded(self, decimals: u32) -> f64 {
+        let scale = 10f64.powi(decimals.min(i32::MAX as u32) as i32);
+        (self.as_float() * scale).round() / scale
+    }
+}
+
+/// Implements the overflow-safe arithmetic of `Rational<$t>`, the results are
+/// computed in `i128` and then reduced.
+macro_rules! impl_rational_arith {
+    ($t:ty) => {
+        impl Rational<$t> {
+            /// Computes `self * rhs`, returns `None` if a denominator is zero
+            /// or the reduced result doesn't fit.
+            pub fn checked_mul(self, rhs: Self) -> Option<Self> {
+                Self::reduce(
+                    self.0 as i128 * rhs.0 as i128,
+                    self.1 as i128 * rhs.1 as i128,
+                )
+            }
+
+            /// Computes `self / rhs`, returns `None` if a denominator or `rhs`
+            /// is zero, or the reduced result doesn't fit.
+            pub fn checked_div(self, rhs: Self) -> Option<Self> {
+                Self::reduce(
+                    self.0 as i128 * rhs.1 as i128,
+                    self.1 as i128 * rhs.0 as i128,
+                )
+            }
+
+            /// Computes `self + rhs`, returns `None` if a denominator is zero
+            /// or the reduced result doesn't fit.
+            pub fn checked_add(self, rhs: Self) -> Option<Self> {
+                Self::reduce(
+                    self.0 as i128 * rhs.1 as i128 + rhs.0 as i128 * self.1 as i128,
+                    self.1 as i128 * rhs.1 as i128,
+                )
+            }
+
+            fn reduce(num: i128, den: i128) -> Option<Self> {
+                if den == 0 {
+                    return None;
+                }
+                let (num, den) = if den < 0 { (-num, -den) } else { (num, den) };
+                let g = gcd(num.unsigned_abs(), den.unsigned_abs()) as i128;
+                Some(Self((num / g).try_into().ok()?, (den / g).try_into().ok()?))
+            }
+        }
+    };
+}
+
+impl_rational_arith!(u32);
+impl_rational_arith!(i32);
+
+fn gcd(mut a: u128, mut b: u128) -> u128 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
 }
 
 impl<T> From<(T, T)> for Rational<T>
@@ -652,6 +976,49 @@ impl From<IRational> for URational {
     }
 }
 
+/// Date time formats seen in the wild, the first one is the standard Exif
+/// format.
+const TIME_FORMATS: &[&str] = &[
+    "%Y:%m:%d %H:%M:%S",
+    "%Y-%m-%d %H:%M:%S",
+    "%Y/%m/%d %H:%M:%S",
+    "%Y-%m-%dT%H:%M:%S",
+    "%Y:%m:%d %H:%M",
+    "%Y-%m-%d %H:%M",
+    "%Y/%m/%d %H:%M",
+];
+
+/// Parses a date time string leniently: besides the standard
+/// "YYYY:MM:DD HH:MM:SS", dashes or slashes as date separators, missing
+/// seconds, non zero-padded fields and 2-digit years are accepted.
+fn parse_naive_time(s: &str) -> Option<NaiveDateTime> {
+    let s = s.trim();
+    // "%Y" accepts 2 digits as well, e.g. "23" would be parsed as year 23
+    let two_digit_year = s.find(|c: char| !c.is_ascii_digit()) == Some(2);
+
+    TIME_FORMATS.iter().find_map(|fmt| {
+        if two_digit_year {
+            NaiveDateTime::parse_from_str(s, &fmt.replacen("%Y", "%y", 1)).ok()
+        } else {
+            NaiveDateTime::parse_from_str(s, fmt).ok()
+        }
+    })
+}
+
+/// Returns true if `tag` holds a date time string, which will be parsed as an
+/// `EntryValue::Time`.
+pub(crate) fn is_date_time_tag(tag: u16) -> bool {
+    tag == ExifTag::DateTimeOriginal.code()
+        || tag == ExifTag::CreateDate.code()
+        || tag == ExifTag::ModifyDate.code()
+}
+
+/// Like `get_cstr`, but borrows the string from `data`.
+pub(crate) fn get_cstr_ref(data: &[u8]) -> std::result::Result<&str, Utf8Error> {
+    let end = data.iter().position(|b| *b == 0).unwrap_or(data.len());
+    std::str::from_utf8(&data[..end]) // Safe-slice
+}
+
 fn get_cstr(data: &[u8]) -> std::result::Result<String, FromUtf8Error> {
     String::from_utf8(
         data.iter()
@@ -864,9 +1231,39 @@ pub(crate) fn decode_rational<T: TryFromBytes>(
 #[cfg(test)]
 mod tests {
     use chrono::{Local, NaiveDateTime, TimeZone};
+    use test_case::test_case;
 
     use super::*;
 
+    #[test]
+    fn rational_arith() {
+        let _ = tracing_subscriber::fmt().with_test_writer().try_init();
+
+        let a = URational::from((1, 2));
+        let b = URational::from((2, 3));
+        assert_eq!(a.checked_mul(b), Some((1, 3).into()));
+        assert_eq!(a.checked_div(b), Some((3, 4).into()));
+        assert_eq!(a.checked_add(b), Some((7, 6).into()));
+        assert_eq!(a.checked_div((0, 1).into()), None);
+        assert_eq!(a.checked_add((1, 0).into()), None);
+        // intermediate results overflow u32, but the reduced ones fit
+        let big = URational::from((u32::MAX, u32::MAX));
+        assert_eq!(big.checked_mul(big), Some((1, 1).into()));
+        assert_eq!(URational::from((u32::MAX, 1)).checked_add(a), None);
+
+        let c = IRational::from((-1, 2));
+        assert_eq!(c.checked_mul((1, -3).into()), Some((1, 6).into()));
+        assert_eq!(c.checked_add((1, 3).into()), Some((-1, 6).into()));
+        assert_eq!(c.checked_div((-1, 4).into()), Some((2, 1).into()));
+        assert_eq!(
+            IRational::from((i32::MIN, 1)).checked_div((-1, 1).into()),
+            None
+        );
+
+        assert_eq!(URational::from((2, 3)).to_f64_rounded(3), 0.667);
+        assert_eq!(IRational::from((-1, 3)).to_f64_rounded(0), -0.0);
+    }
+
     #[test]
     fn test_parse_time() {
         let tz = Local::now().format("%:z").to_string();
@@ -898,4 +1295,159 @@ mod tests {
         assert_eq!(t1, t2);
         assert!(t3 > t2);
     }
+
+    #[test_case("2023:07:09 20:36:33", "2023-07-09 20:36:33")]
+    #[test_case("2023-07-09 20:36:33", "2023-07-09 20:36:33")]
+    #[test_case("2023/07/09 20:36:33", "2023-07-09 20:36:33")]
+    #[test_case("2023-07-09T20:36:33", "2023-07-09 20:36:33")]
+    #[test_case("2023:7:9 20:36:33", "2023-07-09 20:36:33")]
+    #[test_case("2023:07:09 20:36", "2023-07-09 20:36:00")]
+    #[test_case("2023/7/9 8:36", "2023-07-09 08:36:00")]
+    #[test_case("23:07:09 20:36:33", "2023-07-09 20:36:33")]
+    #[test_case(" 2023:07:09 20:36:33 ", "2023-07-09 20:36:33")]
+    fn parse_time_variants(s: &str, expect: &str) {
+        let expect = NaiveDateTime::parse_from_str(expect, "%Y-%m-%d %H:%M:%S").unwrap();
+        assert_eq!(parse_naive_time(s), Some(expect));
+
+        let entry = EntryData {
+            endian: Endianness::Big,
+            tag: ExifTag::DateTimeOriginal.code(),
+            data: s.as_bytes(),
+            data_format: DataFormat::Text,
+            components_num: s.len() as u32,
+        };
+        let tz = Some("+08:00".to_string());
+        let t = EntryValue::parse(&entry, &tz).unwrap();
+        assert_eq!(
+            t.as_time().unwrap(),
+            FixedOffset::east_opt(8 * 3600)
+                .unwrap()
+                .from_local_datetime(&expect)
+                .unwrap()
+        );
+    }
+
+    #[test_case("0000:00:00 00:00:00")]
+    #[test_case("    :  :     :  :  ")]
+    #[test_case("unknown")]
+    fn parse_time_fallback(s: &str) {
+        assert_eq!(parse_naive_time(s), None);
+
+        let entry = EntryData {
+            endian: Endianness::Big,
+            tag: ExifTag::DateTimeOriginal.code(),
+            data: s.as_bytes(),
+            data_format: DataFormat::Text,
+            components_num: s.len() as u32,
+        };
+        assert_eq!(
+            EntryValue::parse(&entry, &None).unwrap(),
+            EntryValue::Text(s.to_string())
+        );
+    }
+
+    #[test]
+    fn numeric_eq() {
+        let eq = |a: EntryValue, b: EntryValue| a.numeric_eq(&b);
+        assert!(eq(EntryValue::U16(3), EntryValue::U32(3)));
+        assert!(eq(EntryValue::I8(-1), EntryValue::I64(-1)));
+        assert!(eq((1, 2).into(), (2, 4).into()));
+        assert!(eq((1, 2).into(), EntryValue::F64(0.5)));
+        assert!(eq("a".into(), "a".into()));
+        assert!(!eq(EntryValue::U16(3), EntryValue::U16(4)));
+        assert!(!eq((1, 0).into(), (2, 0).into()));
+        assert!(!eq("3".into(), EntryValue::U8(3)));
+    }
+
+    #[test_case(EntryValue::U8(200), Some(200.0))]
+    #[test_case(EntryValue::U16(65535), Some(65535.0))]
+    #[test_case(EntryValue::U32(70000), Some(70000.0))]
+    #[test_case(EntryValue::U64(1 << 40), Some(1099511627776.0))]
+    #[test_case(EntryValue::I8(-128), Some(-128.0))]
+    #[test_case(EntryValue::I16(-300), Some(-300.0))]
+    #[test_case(EntryValue::I32(-70000), Some(-70000.0))]
+    #[test_case(EntryValue::I64(-(1 << 40)), Some(-1099511627776.0))]
+    #[test_case(EntryValue::F32(1.5), Some(1.5))]
+    #[test_case(EntryValue::F64(-0.125), Some(-0.125))]
+    #[test_case(EntryValue::URational((9, 2).into()), Some(4.5))]
+    #[test_case(EntryValue::IRational((-3, 4).into()), Some(-0.75))]
+    #[test_case(EntryValue::URational((1, 0).into()), None; "urational zero denominator")]
+    #[test_case(EntryValue::IRational((-1, 0).into()), None; "irational zero denominator")]
+    #[test_case(EntryValue::URational((0, 0).into()), None; "urational zero")]
+    #[test_case(EntryValue::Text("3".into()), None)]
+    #[test_case(EntryValue::Undefined(vec![3]), None)]
+    #[test_case(EntryValue::U16Array(vec![3]), None)]
+    #[test_case(EntryValue::URationalArray(vec![(1, 2).into()]), None)]
+    fn as_f64(v: EntryValue, expect: Option<f64>) {
+        assert_eq!(v.as_f64(), expect);
+    }
+
+    #[test]
+    fn as_f64_time() {
+        let t = DateTime::parse_from_rfc3339("2024-01-01T00:00:00+08:00").unwrap();
+        assert_eq!(EntryValue::Time(t).as_f64(), None);
+    }
+
+    #[test]
+    fn len_and_iter_f64() {
+        let values = [
+            (
+                EntryValue::U8Array(vec![1, 2]),
+                Some(2),
+                Some(vec![1.0, 2.0]),
+            ),
+            (EntryValue::I16Array(vec![-1]), Some(1), Some(vec![-1.0])),
+            (EntryValue::U32Array(vec![]), Some(0), Some(vec![])),
+            (
+                EntryValue::IRationalArray(vec![(-1, 4).into(), (3, 2).into()]),
+                Some(2),
+                Some(vec![-0.25, 1.5]),
+            ),
+            (EntryValue::I64(-3), Some(1), Some(vec![-3.0])),
+            (EntryValue::F32(0.5), Some(1), Some(vec![0.5])),
+            ((3, 4).into(), Some(1), Some(vec![0.75])),
+            (EntryValue::Undefined(vec![0; 4]), Some(4), None),
+            ("abc".into(), None, None),
+        ];
+        for (v, len, elems) in values {
+            assert_eq!(v.len(), len, "{v:?}");
+            assert_eq!(v.iter_f64().map(|x| x.collect::<Vec<_>>()), elems, "{v:?}");
+            if let Some(elems) = v.iter_f64().map(|x| x.count()) {
+                assert_eq!(Some(elems), len);
+            }
+        }
+    }
+
+    #[cfg(feature = "json_dump")]
+    #[test]
+    fn to_json_value() {
+        use serde_json::json;
+
+        let t = DateTime::parse_from_str("2023-11-02T19:58:34+08:00", "%+").unwrap();
+        let values = [
+            (EntryValue::Text("vivo".into()), json!("vivo")),
+            (EntryValue::URational((175, 100).into()), json!(1.75)),
+            (EntryValue::URational((1, 0).into()), json!(null)),
+            (EntryValue::IRational((-1, 2).into()), json!(-0.5)),
+            (EntryValue::U8(1), json!(1)),
+            (EntryValue::I64(-2), json!(-2)),
+            (EntryValue::F64(0.25), json!(0.25)),
+            (EntryValue::Time(t), json!("2023-11-02T19:58:34+08:00")),
+            (EntryValue::Undefined(vec![0x30, 0x32]), json!([48, 50])),
+            (
+                EntryValue::URationalArray(vec![(22, 1).into(), (1, 2).into()]),
+                json!([22.0, 0.5]),
+            ),
+            (EntryValue::U16Array(vec![1, 2]), json!([1, 2])),
+        ];
+        for (v, expect) in values {
+            assert_eq!(v.to_json_value(), expect, "{v:?}");
+        }
+
+        // the default `Serialize` is kept as it is
+        assert_eq!(
+            serde_json::to_value(EntryValue::URational((175, 100).into())).unwrap(),
+            json!("175/100 (1.7500)")
+        );
+    }
 }
@@ -4,10 +4,13 @@ use std::{
 };
 
 use crate::{
-    ebml::webm::parse_webm,
-    error::ParsingError,
+    ebml::webm::parse_webm_with_state,
+    error::ParsingErrorState,
     file::MimeVideo,
-    mov::{extract_moov_body_from_buf, parse_mp4, parse_qt},
+    mov::{
+        extract_moov_body_from_buf, parse_mp4, parse_qt, parse_timed_metadata_tracks, parse_tracks,
+    },
+    parser::ParsingState,
     EntryValue, GPSInfo,
 };
 
@@ -45,6 +48,61 @@ pub enum TrackInfoTag {
     /// If you need a parsed [`GPSInfo`] which provides more detailed GPS info,
     /// please use [`TrackInfo::get_gps_info`].
     GpsIso6709,
+
+    /// Its value is an `EntryValue::Text`.
+    Title,
+
+    /// Its value is an `EntryValue::Text`.
+    Artist,
+
+    /// Its value is an `EntryValue::Text`.
+    Album,
+
+    /// The number of time units that pass per second, its value is an
+    /// `EntryValue::U32`.
+    Timescale,
+
+    /// The preferred playback rate, 1.0 indicates normal rate, its value is an
+    /// `EntryValue::F64`.
+    PreferredRate,
+
+    /// The projection of a 360° video, its value is an `EntryValue::Text`,
+    /// one of "equirectangular", "cubemap" and "mesh".
+    ///
+    /// It's parsed from the `sv3d` box (Google Spatial Media) in ISOBMFF
+    /// files, or the `Projection` element in Matroska files.
+    Projection,
+
+    /// The stereo layout of a 3D video, its value is an `EntryValue::Text`,
+    /// one of "mono", "top-bottom", "bottom-top", "left-right", "right-left"
+    /// and "stereo-custom".
+    ///
+    /// It's parsed from the `st3d` box (Google Spatial Media) in ISOBMFF
+    /// files, or the `StereoMode` element in Matroska files.
+    StereoMode,
+
+    /// The number of frames of the video track, its value is an
+    /// `EntryValue::U64`.
+    ///
+    /// It's computed from the sample table (`stts` or `stsz` box) in ISOBMFF
+    /// files. It's not available for Matroska/WebM files.
+    FrameCount,
+
+    /// The color primaries of the video track, its value is an
+    /// `EntryValue::U16` as defined in ISO/IEC 23091-2 (ITU-T H.273), e.g. 1
+    /// for BT.709, 9 for BT.2020, 2 means unspecified.
+    ///
+    /// It's parsed from the `colr` box (`nclx` or `nclc`) in ISOBMFF files,
+    /// or the `Colour` element in Matroska files.
+    ColorPrimaries,
+
+    /// The transfer characteristics of the video track, its value is an
+    /// `EntryValue::U16` as defined in ISO/IEC 23091-2 (ITU-T H.273), e.g. 1
+    /// for BT.709, 16 for PQ (SMPTE ST 2084) & 18 for HLG, the latter two
+    /// indicate an HDR video. 2 means unspecified.
+    ///
+    /// It's parsed from the same place as [`TrackInfoTag::ColorPrimaries`].
+    TransferCharacteristics,
 }
 
 /// Represents parsed track info.
@@ -52,6 +110,44 @@ pub enum TrackInfoTag {
 pub struct TrackInfo {
     entries: BTreeMap<TrackInfoTag, EntryValue>,
     gps_info: Option<GPSInfo>,
+    timed_metadata_tracks: Vec<String>,
+    tracks: Vec<SingleTrack>,
+    pub(crate) tags: BTreeMap<String, String>,
+    pub(crate) create_date_source: Option<DateSource>,
+}
+
+/// Represents a track of an ISOBMFF file, see [`TrackInfo::tracks`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct SingleTrack {
+    /// Track ID from the `tkhd` box, which is unique within the file.
+    pub track_id: u32,
+
+    /// Alternate group from the `tkhd` box. Tracks with the same non-zero
+    /// value are alternatives to each other (e.g. different languages or
+    /// angles), only one of them should be played at a time. 0 means the
+    /// track isn't in any group.
+    pub alternate_group: u16,
+
+    /// Handler type from the `hdlr` box, e.g. "vide", "soun", "meta".
+    pub handler_type: String,
+}
+
+/// Where the value of [`TrackInfoTag::CreateDate`] comes from, see
+/// [`TrackInfo::create_date_source`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum DateSource {
+    /// The creation time of the `mvhd` box, which is in UTC. Many devices
+    /// don't set it correctly, so it's less reliable.
+    Mvhd,
+
+    /// The `com.apple.quicktime.creationdate` key (or the `©day` item), which
+    /// usually carries a real time zone offset.
+    QuickTimeKey,
+
+    /// The `DateUTC` element of Matroska/WebM files, which is in UTC.
+    EbmlDate,
 }
 
 impl TrackInfo {
@@ -66,12 +162,79 @@ impl TrackInfo {
         self.gps_info.as_ref()
     }
 
+    /// Get where the value of [`TrackInfoTag::CreateDate`] comes from, returns
+    /// `None` if there is no `CreateDate`.
+    ///
+    /// ```rust
+    /// use nom_exif::*;
+    ///
+    /// let mut parser = MediaParser::new();
+    /// let ms = MediaSource::file_path("./testdata/meta.mov").unwrap();
+    /// let info: TrackInfo = parser.parse(ms).unwrap();
+    /// assert_eq!(info.create_date_source(), Some(DateSource::Mvhd));
+    /// ```
+    pub fn create_date_source(&self) -> Option<DateSource> {
+        self.create_date_source
+    }
+
     /// Get an iterator for `(&TrackInfoTag, &EntryValue)`. The parsed
     /// `GPSInfo` is not included.
     pub fn iter(&self) -> impl Iterator<Item = (&TrackInfoTag, &EntryValue)> {
         self.entries.iter()
     }
 
+    /// Get the subtypes of timed metadata tracks, e.g. "mebx" (Apple), "gpmd"
+    /// (GoPro), "camm" (Camera Motion Metadata), "tmcd" (timecode).
+    ///
+    /// The payloads of these tracks are not decoded, this can be used to decide
+    /// whether a heavier telemetry parsing is worthwhile.
+    pub fn timed_metadata_tracks(&self) -> Vec<String> {
+        self.timed_metadata_tracks.clone()
+    }
+
+    /// Get all tracks of an ISOBMFF file, e.g. for building a track selection
+    /// UI. It's always empty for Matroska/WebM files.
+    ///
+    /// ```rust
+    /// use nom_exif::*;
+    ///
+    /// let mut parser = MediaParser::new();
+    /// let ms = MediaSource::file_path("./testdata/meta.mp4").unwrap();
+    /// let info: TrackInfo = parser.parse(ms).unwrap();
+    /// let tracks = info.tracks();
+    /// assert_eq!(tracks[0].track_id, 1);
+    /// assert_eq!(tracks[0].handler_type, "vide");
+    /// ```
+    pub fn tracks(&self) -> &[SingleTrack] {
+        &self.tracks
+    }
+
+    /// Get the general metadata stored in the `Tags` element of a
+    /// Matroska/WebM file, e.g. "TITLE", "ARTIST", "ENCODER". Only the tags
+    /// targeting the whole file are included, tag names are in uppercase.
+    /// It's always empty for ISOBMFF files.
+    ///
+    /// ```rust
+    /// use nom_exif::*;
+    ///
+    /// let mut parser = MediaParser::new();
+    /// let ms = MediaSource::file_path("./testdata/webm_480.webm").unwrap();
+    /// let info: TrackInfo = parser.parse(ms).unwrap();
+    /// assert_eq!(info.tags()["ENCODER"], "Lavf57.83.100");
+    /// ```
+    pub fn tags(&self) -> &BTreeMap<String, String> {
+        &self.tags
+    }
+
+    /// Returns true if there is a timed metadata track which may carry GPS
+    /// telemetry, i.e. a GoPro GPMF ("gpmd") or a Camera Motion Metadata
+    /// ("camm") track.
+    pub fn gps_track_present(&self) -> bool {
+        self.timed_metadata_tracks
+            .iter()
+            .any(|x| x == "gpmd" || x == "camm")
+    }
+
     pub(crate) fn put(&mut self, tag: TrackInfoTag, value: EntryValue) {
         self.entries.insert(tag, value);
     }
@@ -139,7 +302,8 @@ impl TrackInfo {
 pub(crate) fn parse_track_info(
     input: &[u8],
     mime_video: MimeVideo,
-) -> Result<TrackInfo, ParsingError> {
+    state: Option<ParsingState>,
+) -> Result<TrackInfo, ParsingErrorState> {
     let mut info: TrackInfo = match mime_video {
         crate::file::MimeVideo::QuickTime
         | crate::file::MimeVideo::_3gpp
@@ -147,15 +311,18 @@ pub(crate) fn parse_track_info(
             let range = extract_moov_body_from_buf(input)?;
             let moov_body = &input[range];
 
-            match mime_video {
-                MimeVideo::QuickTime => parse_qt(moov_body)?.into(),
+            let mut info: TrackInfo = match mime_video {
+                MimeVideo::QuickTime => parse_qt(moov_body)?,
 
-                MimeVideo::Mp4 | MimeVideo::_3gpp => parse_mp4(moov_body)?.into(),
+                MimeVideo::Mp4 | MimeVideo::_3gpp => parse_mp4(moov_body)?,
                 _ => unreachable!(),
-            }
+            };
+            info.timed_metadata_tracks = parse_timed_metadata_tracks(moov_body);
+            info.tracks = parse_tracks(moov_body);
+            info
         }
         crate::file::MimeVideo::Webm | crate::file::MimeVideo::Matroska => {
-            parse_webm(input)?.into()
+            parse_webm_with_state(input, state)?.into()
         }
     };
 
@@ -180,6 +347,10 @@ impl From<BTreeMap<TrackInfoTag, EntryValue>> for TrackInfo {
         Self {
             entries,
             gps_info: None,
+            timed_metadata_tracks: Vec::new(),
+            tracks: Vec::new(),
+            tags: BTreeMap::new(),
+            create_date_source: None,
         }
     }
 }
@@ -202,6 +373,16 @@ impl From<TrackInfoTag> for &str {
             TrackInfoTag::ImageWidth => "ImageWidth",
             TrackInfoTag::ImageHeight => "ImageHeight",
             TrackInfoTag::GpsIso6709 => "GpsIso6709",
+            TrackInfoTag::Title => "Title",
+            TrackInfoTag::Artist => "Artist",
+            TrackInfoTag::Album => "Album",
+            TrackInfoTag::Timescale => "Timescale",
+            TrackInfoTag::PreferredRate => "PreferredRate",
+            TrackInfoTag::Projection => "Projection",
+            TrackInfoTag::StereoMode => "StereoMode",
+            TrackInfoTag::FrameCount => "FrameCount",
+            TrackInfoTag::ColorPrimaries => "ColorPrimaries",
+            TrackInfoTag::TransferCharacteristics => "TransferCharacteristics",
         }
     }
 }
@@ -0,0 +1,236 @@
+use crate::{
+    error::{ParsingError, ParsingErrorState},
+    exif::check_exif_header,
+    parser::ParsingState,
+};
+
+/// Size of the RIFF header: "RIFF", file size, "WEBP".
+const RIFF_HEADER_SIZE: usize = 12;
+
+/// Size of the fourcc & size fields of a chunk.
+const CHUNK_HEADER_SIZE: usize = 8;
+
+/// Size of the `VP8X` chunk data.
+const VP8X_SIZE: usize = 10;
+
+/// The `VP8X` flag indicating that the file contains an `EXIF` chunk.
+const VP8X_EXIF_FLAG: u8 = 0x08;
+
+/// Refer to: [WebP Container
+/// Specification](https://developers.google.com/speed/webp/docs/riff_container)
+pub(crate) fn check_webp(input: &[u8]) -> crate::Result<()> {
+    if input.len() >= RIFF_HEADER_SIZE && input.starts_with(b"RIFF") && &input[8..12] == b"WEBP" {
+        Ok(())
+    } else {
+        Err("invalid WebP header".into())
+    }
+}
+
+/// Extract Exif TIFF data from the `EXIF` chunk of a WebP file.
+///
+/// Only the extended file format (with a `VP8X` chunk first) can carry Exif
+/// data, `None` is returned for simple lossy (`VP8 `) & lossless (`VP8L`)
+/// files, or if the Exif flag of the `VP8X` chunk is not set.
+///
+/// The `EXIF` chunk is located after the image data, chunks which haven't
+/// been fully loaded are skipped with `ParsingError::ClearAndSkip`. After
+/// that, the buffer starts at the next chunk rather than the RIFF header,
+/// which is indicated by `ParsingState::WebpChunk`. The state carries the
+/// canvas size read from the `VP8X` chunk, and is returned along with the
+/// Exif data.
+#[tracing::instrument(skip_all)]
+pub(crate) fn extract_exif_data(
+    input: &[u8],
+    state: Option<ParsingState>,
+) -> Result<(Option<&[u8]>, Option<ParsingState>), ParsingErrorState> {
+    let (mut pos, canvas_size) = match state {
+        Some(ParsingState::WebpChunk { canvas_size }) => (0, canvas_size),
+        _ => {
+            let vp8x_end = RIFF_HEADER_SIZE + CHUNK_HEADER_SIZE + VP8X_SIZE;
+            if input.len() < vp8x_end {
+                let need = ParsingError::Need(vp8x_end - input.len());
+                return Err(ParsingErrorState::new(need, state));
+            }
+            if check_webp(input).is_err() {
+                let failed = ParsingError::Failed("invalid WebP header".into());
+                return Err(ParsingErrorState::new(failed, state));
+            }
+            let header = &input[RIFF_HEADER_SIZE..vp8x_end]; // Safe-slice
+            if &header[..4] != b"VP8X" {
+                tracing::debug!("simple file format, no Exif");
+                return Ok((None, state));
+            }
+            let data = &header[CHUNK_HEADER_SIZE..]; // Safe-slice
+            if data[0] & VP8X_EXIF_FLAG == 0 {
+                tracing::debug!("Exif flag is not set");
+                return Ok((None, state));
+            }
+            let read_u24 = |x: &[u8]| u32::from_le_bytes([x[0], x[1], x[2], 0]);
+            let canvas_size = (read_u24(&data[4..7]) + 1, read_u24(&data[7..10]) + 1);
+            // Start from the `VP8X` chunk, it's skipped like the others
+            (RIFF_HEADER_SIZE, canvas_size)
+        }
+    };
+    // Returned only when the buffer is going to start at a chunk, or along
+    // with the Exif data. `Need` keeps the incoming state, since the buffer
+    // start doesn't change.
+    let chunk_state = Some(ParsingState::WebpChunk { canvas_size });
+
+    loop {
+        let Some(header) = input.get(pos..pos + CHUNK_HEADER_SIZE) else {
+            let need = ParsingError::Need(pos + CHUNK_HEADER_SIZE - input.len());
+            return Err(ParsingErrorState::new(need, state));
+        };
+        let fourcc = &header[..4]; // Safe-slice
+        let size = u32::from_le_bytes([header[4], header[5], header[6], header[7]]) as usize;
+        let data_start = pos + CHUNK_HEADER_SIZE;
+        let data_end = data_start.saturating_add(size);
+        tracing::debug!(
+            fourcc = String::from_utf8_lossy(fourcc).as_ref(),
+            size,
+            "Got"
+        );
+
+        if fourcc == b"EXIF" {
+            let Some(data) = input.get(data_start..data_end) else {
+                let need = ParsingError::Need(data_end - input.len());
+                return Err(ParsingErrorState::new(need, state));
+            };
+            // Some writers keep the "Exif\0\0" header of the JPEG APP1 segment
+            let data = if check_exif_header(data).unwrap_or(false) {
+                &data[6..] // Safe-slice
+            } else {
+                data
+            };
+            return Ok((Some(data), chunk_state));
+        }
+
+        let chunk_end = data_start.saturating_add(padded(size));
+        if chunk_end > input.len() {
+            let skip = ParsingError::ClearAndSkip(chunk_end);
+            return Err(ParsingErrorState::new(skip, chunk_state));
+        }
+        pos = chunk_end;
+    }
+}
+
+/// Chunk data is padded to an even size.
+fn padded(size: usize) -> usize {
+    size.saturating_add(size & 1)
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use crate::{exif::extract_exif_with_mime, file::MimeImage, testkit::read_sample};
+
+    use super::*;
+
+    fn push_chunk(webp: &mut Vec<u8>, fourcc: &[u8], data: &[u8]) {
+        webp.extend_from_slice(fourcc);
+        webp.extend_from_slice(&(data.len() as u32).to_le_bytes());
+        webp.extend_from_slice(data);
+        if data.len() % 2 == 1 {
+            webp.push(0);
+        }
+    }
+
+    fn finish(mut webp: Vec<u8>) -> Vec<u8> {
+        let size = (webp.len() - 8) as u32;
+        webp[4..8].copy_from_slice(&size.to_le_bytes());
+        webp
+    }
+
+    /// Builds a minimal extended WebP file with a 640x480 canvas, which
+    /// embeds the Exif data of "exif.jpg" after a `VP8 ` chunk of `vp8_size`
+    /// bytes. Returns the WebP data and the Exif data.
+    pub(crate) fn build_webp(vp8_size: usize) -> (Vec<u8>, Vec<u8>) {
+        let buf = read_sample("exif.jpg").unwrap();
+        let (exif, _) = extract_exif_with_mime(MimeImage::Jpeg, &buf, None).unwrap();
+        let exif = exif.unwrap().to_vec();
+
+        let mut webp = b"RIFF\0\0\0\0WEBP".to_vec();
+        // Exif flag, reserved, canvas width - 1, canvas height - 1
+        push_chunk(
+            &mut webp,
+            b"VP8X",
+            &[VP8X_EXIF_FLAG, 0, 0, 0, 0x7f, 0x02, 0, 0xdf, 0x01, 0],
+        );
+        push_chunk(&mut webp, b"VP8 ", &vec![0x55; vp8_size]);
+        push_chunk(&mut webp, b"EXIF", &exif);
+
+        (finish(webp), exif)
+    }
+
+    #[test]
+    fn webp_exif() {
+        let _ = tracing_subscriber::fmt().with_test_writer().try_init();
+
+        for vp8_size in [0, 99, 100] {
+            let (webp, exif) = build_webp(vp8_size);
+            check_webp(&webp).unwrap();
+            let (data, state) = extract_exif_data(&webp, None).unwrap();
+            assert_eq!(data, Some(&exif[..]));
+            assert!(matches!(
+                state,
+                Some(ParsingState::WebpChunk {
+                    canvas_size: (640, 480)
+                })
+            ));
+        }
+
+        check_webp(&read_sample("exif.jpg").unwrap()).unwrap_err();
+        check_webp(b"RIFF\0\0\0\0AVI LIST").unwrap_err();
+    }
+
+    #[test]
+    fn webp_skip_chunks() {
+        let _ = tracing_subscriber::fmt().with_test_writer().try_init();
+
+        let (webp, exif) = build_webp(0x10001);
+        let vp8_pos = RIFF_HEADER_SIZE + CHUNK_HEADER_SIZE + VP8X_SIZE;
+        let vp8_end = vp8_pos + CHUNK_HEADER_SIZE + 0x10002;
+        let err = extract_exif_data(&webp[..vp8_pos + 100], None).unwrap_err();
+        assert!(matches!(err.err, ParsingError::ClearAndSkip(n) if n == vp8_end));
+
+        // The buffer starts at the chunk after `VP8 `
+        let (data, _) = extract_exif_data(&webp[vp8_end..], err.state).unwrap();
+        assert_eq!(data, Some(&exif[..]));
+
+        let err = extract_exif_data(&webp[..vp8_pos + 4], None).unwrap_err();
+        assert!(matches!(err.err, ParsingError::Need(4)));
+    }
+
+    #[test]
+    fn webp_without_exif() {
+        let _ = tracing_subscriber::fmt().with_test_writer().try_init();
+
+        // Lossless, simple file format
+        let mut webp = b"RIFF\0\0\0\0WEBP".to_vec();
+        push_chunk(&mut webp, b"VP8L", &[0x2f; 21]);
+        let webp = finish(webp);
+        let (data, _) = extract_exif_data(&webp, None).unwrap();
+        assert_eq!(data, None);
+
+        // Exif flag is not set
+        let (mut webp, _) = build_webp(10);
+        webp[RIFF_HEADER_SIZE + CHUNK_HEADER_SIZE] = 0;
+        let (data, _) = extract_exif_data(&webp, None).unwrap();
+        assert_eq!(data, None);
+    }
+
+    #[test]
+    fn webp_truncated() {
+        let _ = tracing_subscriber::fmt().with_test_writer().try_init();
+
+        let (mut webp, _) = build_webp(10);
+        let pos = webp.windows(4).position(|x| x == b"EXIF").unwrap();
+        webp[pos + 4..pos + 8].copy_from_slice(&u32::MAX.to_le_bytes());
+        let err = extract_exif_data(&webp, None).unwrap_err();
+        assert!(matches!(err.err, ParsingError::Need(_)));
+
+        let vp8_pos = RIFF_HEADER_SIZE + CHUNK_HEADER_SIZE + VP8X_SIZE;
+        webp[vp8_pos + 4..vp8_pos + 8].copy_from_slice(&u32::MAX.to_le_bytes());
+        let err = extract_exif_data(&webp, None).unwrap_err();
+        assert!(matches!(err.err, ParsingError::ClearAndSkip(_)));
+    }
+}